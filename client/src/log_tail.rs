@@ -0,0 +1,180 @@
+use crate::{decoder, swap_indexer};
+use anyhow::{format_err, Result};
+use common::{common_types, confirmed_txn, ConfirmedInstruction, PoolMints};
+use solana_client::{
+    pubsub_client::PubsubClient,
+    rpc_client::RpcClient,
+    rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter},
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    str::FromStr,
+    sync::mpsc,
+    thread,
+};
+
+/// How many recent signatures are remembered to skip the transactions delivered by more than
+/// one subscription.
+const RECENT_SIGNATURES: usize = 1000;
+
+/// Filters of the tail, an empty filter matches all.
+#[derive(Clone, Debug, Default)]
+pub struct TailFilter {
+    pub pools: Vec<Pubkey>,
+    pub mints: Vec<Pubkey>,
+    /// The event types, matched case insensitively.
+    pub event_types: Vec<String>,
+}
+
+fn get_instructions(
+    rpc_client: &RpcClient,
+    signature: &Signature,
+) -> Result<Vec<ConfirmedInstruction>> {
+    let confirmed_txn = confirmed_txn::get_confirmed_txn(rpc_client, signature)?;
+    let (versioned_txn, meta) = confirmed_txn::decode_confirmed_txn(&confirmed_txn)?;
+    let account_keys = confirmed_txn::get_account_keys(&versioned_txn, &meta)?;
+    confirmed_txn::get_confirmed_instructions(&versioned_txn, &meta, &account_keys)
+}
+
+/// Subscribe to the logs mentioning the programs and print the decoded events as json lines.
+/// The ray_log and the position events of clmm do not contain the pool, they are resolved from
+/// the landed transaction only if a pool or mint filter is given.
+pub fn tail_logs(
+    rpc_client: &RpcClient,
+    config: &common_types::CommonConfig,
+    programs: &[Pubkey],
+    filter: &TailFilter,
+) -> Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    // the subscriptions are unsubscribed once dropped
+    let mut subscriptions = Vec::new();
+    for program in programs {
+        // the mentions filter supports only one address
+        let (subscription, logs) = PubsubClient::logs_subscribe(
+            config.cluster().ws_url(),
+            RpcTransactionLogsFilter::Mentions(vec![program.to_string()]),
+            RpcTransactionLogsConfig {
+                commitment: Some(CommitmentConfig::confirmed()),
+            },
+        )?;
+        subscriptions.push(subscription);
+        let sender = sender.clone();
+        thread::spawn(move || {
+            for response in logs.iter() {
+                if sender.send(response).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    drop(sender);
+
+    let resolve_pool = !filter.pools.is_empty() || !filter.mints.is_empty();
+    let mut recent_signatures = VecDeque::new();
+    let mut seen_signatures = HashSet::new();
+    let mut pool_mints: HashMap<Pubkey, PoolMints> = HashMap::new();
+    for response in receiver.iter() {
+        let logs = response.value;
+        // the events of the failed transaction are reverted
+        if logs.err.is_some() || !seen_signatures.insert(logs.signature.clone()) {
+            continue;
+        }
+        recent_signatures.push_back(logs.signature.clone());
+        if recent_signatures.len() > RECENT_SIGNATURES {
+            if let Some(signature) = recent_signatures.pop_front() {
+                seen_signatures.remove(&signature);
+            }
+        }
+        let signature = Signature::from_str(&logs.signature)?;
+
+        let mut instructions: Option<Vec<ConfirmedInstruction>> = None;
+        for program_log in confirmed_txn::get_program_logs(&logs.logs) {
+            if !programs.contains(&program_log.program_id) {
+                continue;
+            }
+            let event =
+                match decoder::decode_event(config, &program_log.program_id, &program_log.log) {
+                    Ok(Some(event)) => event,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        eprintln!("{}: {}", signature, e);
+                        continue;
+                    }
+                };
+            if !filter.event_types.is_empty()
+                && !filter
+                    .event_types
+                    .iter()
+                    .any(|event_type| event_type.eq_ignore_ascii_case(event.event_type()))
+            {
+                continue;
+            }
+
+            let mut pool_id = event.pool_id();
+            if pool_id.is_none() && resolve_pool {
+                if instructions.is_none() {
+                    // the transaction may not be served yet right after its logs
+                    instructions = match get_instructions(rpc_client, &signature) {
+                        Ok(instructions) => Some(instructions),
+                        Err(e) => {
+                            eprintln!("{}: {}", signature, e);
+                            continue;
+                        }
+                    };
+                }
+                pool_id = instructions
+                    .as_ref()
+                    .and_then(|instructions| instructions.get(program_log.invoke_index))
+                    .filter(|instruction| instruction.program_id == program_log.program_id)
+                    .and_then(|instruction| {
+                        decoder::instruction_pool_id(
+                            config,
+                            &instruction.program_id,
+                            &instruction.data,
+                            &instruction.accounts,
+                        )
+                    });
+            }
+            if !filter.pools.is_empty() && !pool_id.is_some_and(|id| filter.pools.contains(&id)) {
+                continue;
+            }
+            if !filter.mints.is_empty() {
+                let mints = match pool_id {
+                    Some(pool_id) => match pool_mints.get(&pool_id) {
+                        Some(mints) => *mints,
+                        None => {
+                            let mints =
+                                match swap_indexer::get_pool_mints(rpc_client, config, &pool_id) {
+                                    Ok(mints) => mints,
+                                    Err(e) => {
+                                        eprintln!("{}: {}", signature, e);
+                                        continue;
+                                    }
+                                };
+                            pool_mints.insert(pool_id, mints);
+                            mints
+                        }
+                    },
+                    None => continue,
+                };
+                if !filter.mints.contains(&mints.mint_0) && !filter.mints.contains(&mints.mint_1) {
+                    continue;
+                }
+            }
+
+            println!(
+                "{}",
+                serde_json::json!({
+                    "signature": logs.signature,
+                    "slot": response.context.slot,
+                    "program": event.program(),
+                    "event_type": event.event_type(),
+                    "pool_id": pool_id.map(|pool_id| pool_id.to_string()),
+                    "event": format!("{:?}", event),
+                })
+            );
+        }
+    }
+    Err(format_err!("the websocket subscriptions are closed"))
+}
@@ -1,19 +1,35 @@
 #![allow(dead_code)]
 
-use anyhow::{Ok, Result};
+use anyhow::{format_err, Ok, Result};
 use clap::Parser;
 use solana_client::rpc_client::RpcClient;
-use solana_sdk::{commitment_config::CommitmentConfig, signer::Signer};
-use std::sync::Arc;
+use solana_sdk::{
+    message::Message, pubkey::Pubkey, signature::Signature, signer::Signer,
+    transaction::Transaction,
+};
+use std::{
+    str::FromStr,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+mod log_tail;
+mod pool_watch;
+mod swap_indexer;
 
 use {
     amm_cli::{self, AmmCommands},
     clmm_cli::{self, ClmmCommands},
-    common::{common_types, common_utils, rpc},
+    common::{
+        common_types::{self, CommandInstructions},
+        common_utils, confirmed_txn, indexer, offline, receipt, rpc, MintInput, NonceCommands,
+        PoolProgram, ReceiptEvent, SwapQuote,
+    },
     cpswap_cli::{self, CpSwapCommands},
+    raydium_decoder::decoder,
 };
 /// commands
-#[derive(Debug, Parser)]
+#[derive(Clone, Debug, Parser)]
 pub enum Command {
     CPSWAP {
         #[clap(subcommand)]
@@ -27,6 +43,111 @@ pub enum Command {
         #[clap(subcommand)]
         subcmd: AmmCommands,
     },
+    /// Create, inspect or advance durable nonce accounts.
+    Nonce {
+        #[clap(subcommand)]
+        subcmd: NonceCommands,
+    },
+    /// Add the signatures of local keypairs to an exported legacy transaction, v0 transactions are not supported.
+    Sign {
+        /// The exported transaction file or the encoded transaction.
+        #[clap(short, long)]
+        input: String,
+        /// The keypair files to sign with. If none is given, sign with the configured wallet.
+        #[clap(short, long)]
+        keypair: Vec<String>,
+    },
+    /// Broadcast a fully signed legacy transaction, v0 transactions are not supported.
+    Submit {
+        /// The exported transaction file or the encoded transaction.
+        #[clap(short, long)]
+        input: String,
+    },
+    /// Decode the instructions, events and token balance changes of a landed transaction.
+    DecodeTx {
+        /// The transaction signature.
+        signature: Signature,
+    },
+    /// Index the swaps of a pool into a local csv store and derive the OHLCV candles.
+    /// The store resumes from the signatures indexed by the previous runs.
+    Index {
+        /// The pool id of the amm, cp-swap or clmm pool.
+        pool_id: Pubkey,
+        /// The csv file of the trades, `<POOL_ID>.csv` by default.
+        #[clap(long)]
+        store: Option<String>,
+        /// The max signatures to catch up and backfill in this run.
+        #[clap(long, default_value_t = 1000)]
+        limit: usize,
+        /// The candle interval in seconds.
+        #[clap(long, default_value_t = 3600, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+        /// Write the candles to the csv file instead of printing them.
+        #[clap(long)]
+        candles: Option<String>,
+    },
+    /// Stream the price, reserves and liquidity of a pool over the websocket endpoint.
+    Watch {
+        /// The pool id of the amm, cp-swap or clmm pool.
+        pool_id: Pubkey,
+        /// Print the updates as json lines.
+        #[clap(long, action)]
+        json: bool,
+        /// Alert when the price moved more than the bps since the last alert.
+        #[clap(long)]
+        alert_price_change_bps: Option<u64>,
+        /// Alert when a reserve moved more than the bps since the last alert.
+        #[clap(long)]
+        alert_reserve_change_bps: Option<u64>,
+    },
+    /// Tail the logs of the raydium programs and print the decoded events as json lines.
+    Tail {
+        /// The programs to subscribe. If none is given, subscribe all the three programs.
+        #[clap(long, value_parser = ["amm", "cpswap", "clmm"])]
+        program: Vec<String>,
+        /// Only print the events of the pools.
+        #[clap(long)]
+        pool: Vec<Pubkey>,
+        /// Only print the events of the pools of the mints, or their symbols.
+        #[clap(long)]
+        mint: Vec<MintInput>,
+        /// Only print the events of the types, e.g. Swap, SwapBaseIn, LpChange.
+        #[clap(long)]
+        event_type: Vec<String>,
+    },
+    /// Read the time weighted average price of a cp-swap or clmm pool from its observations.
+    Twap {
+        /// The pool id of the cp-swap or clmm pool.
+        pool_id: Pubkey,
+        /// The window of the twap in seconds.
+        #[clap(long, default_value_t = 300)]
+        window: u64,
+    },
+    /// Print the authorities and extensions of a mint and warn about the risky configurations.
+    InspectMint {
+        /// The spl token or token 2022 mint, or its symbol.
+        mint: MintInput,
+    },
+    /// Build or refresh the local pool index of `--config.pool_index` and query it by mints.
+    /// The programs whose pools are older than `--config.pool_index_ttl` are refreshed first.
+    Pools {
+        /// The programs to refresh and query. If none is given, all the three programs.
+        #[clap(long, value_parser = ["amm", "cpswap", "clmm"])]
+        program: Vec<String>,
+        /// Only print the pools of the mint, or of the pair of mints in either order.
+        #[clap(long)]
+        mint: Vec<MintInput>,
+        /// Refresh the programs regardless of the ttl.
+        #[clap(long, action)]
+        refresh: bool,
+    },
+    /// Close the empty token accounts and the wsol accounts of the wallet and reclaim their rent.
+    /// The accounts of the nft mints and with withheld transfer fees are skipped.
+    Cleanup {
+        /// Only list the accounts to close.
+        #[clap(long, action)]
+        dry_run: bool,
+    },
 }
 
 #[derive(Debug, Parser)]
@@ -46,47 +167,707 @@ pub fn entry(opts: Opts) -> Result<()> {
     let command_override = opts.command_override;
     config.command_override(command_override);
 
-    let payer = common_utils::read_keypair_file(&config.wallet())?;
-    let fee_payer = payer.pubkey();
-    let mut signing_keypairs: Vec<Arc<dyn Signer>> = Vec::new();
-    let payer: Arc<dyn Signer> = Arc::new(payer);
-    if !signing_keypairs.contains(&payer) {
-        signing_keypairs.push(payer);
+    let command = match opts.command {
+        Command::Sign { input, keypair } => {
+            let mut txn = offline::read_offline_txn(&input, config.encoding())?;
+            let keypair_paths = if keypair.is_empty() {
+                vec![config.wallet()]
+            } else {
+                keypair
+            };
+            let mut signing_keypairs: Vec<Arc<dyn Signer>> = Vec::new();
+            for keypair_path in keypair_paths {
+                let signer: Arc<dyn Signer> =
+                    Arc::new(common_utils::read_keypair_file(&keypair_path)?);
+                signing_keypairs.push(signer);
+            }
+            offline::sign_offline_txn(&mut txn, &signing_keypairs)?;
+            let offline_txn = offline::export_txn(&txn, config.encoding())?;
+            offline::write_offline_txn(&offline_txn, config.output_path().as_deref())?;
+            return Ok(());
+        }
+        Command::Submit { input } => {
+            let txn = offline::read_offline_txn(&input, config.encoding())?;
+            let unsigned_signers = offline::unsigned_signers(&txn);
+            if !unsigned_signers.is_empty() {
+                return Err(anyhow::format_err!(
+                    "transaction is missing signatures of {:?}",
+                    unsigned_signers
+                ));
+            }
+            let rpc_client = RpcClient::new(config.cluster().url());
+            let signature =
+                rpc::send_txn_with_rebroadcast(&rpc_client, &txn, config.skip_preflight())?.ok_or(
+                    anyhow::format_err!("blockhash expired, the transaction must be signed again"),
+                )?;
+            // the quote was made offline, the slippage of the config is assumed
+            print_receipt(
+                &rpc_client,
+                &config,
+                &signature,
+                &txn.message,
+                config.slippage(),
+            )?;
+            return Ok(());
+        }
+        Command::DecodeTx { signature } => {
+            let rpc_client = RpcClient::new(config.cluster().url());
+            decode_txn(&rpc_client, &config, &signature)?;
+            return Ok(());
+        }
+        Command::Index {
+            pool_id,
+            store,
+            limit,
+            interval,
+            candles,
+        } => {
+            let rpc_client = RpcClient::new(config.cluster().url());
+            let mints = swap_indexer::get_pool_mints(&rpc_client, &config, &pool_id)?;
+            let store = store.unwrap_or(format!("{}.csv", pool_id));
+            swap_indexer::index_pool(&rpc_client, &config, &pool_id, &store, limit)?;
+            let trades = indexer::load_trades(&store)?;
+            let pool_candles = indexer::build_candles(&trades, &mints, interval as i64);
+            match candles {
+                Some(path) => indexer::write_candles(&path, &pool_candles)?,
+                None => indexer::print_candles(&pool_candles),
+            }
+            return Ok(());
+        }
+        Command::Watch {
+            pool_id,
+            json,
+            alert_price_change_bps,
+            alert_reserve_change_bps,
+        } => {
+            let rpc_client = RpcClient::new(config.cluster().url());
+            pool_watch::watch_pool(
+                &rpc_client,
+                &config,
+                &pool_id,
+                json,
+                alert_price_change_bps,
+                alert_reserve_change_bps,
+            )?;
+            return Ok(());
+        }
+        Command::Tail {
+            program,
+            pool,
+            mint,
+            event_type,
+        } => {
+            let rpc_client = RpcClient::new(config.cluster().url());
+            let programs: Vec<Pubkey> = if program.is_empty() {
+                vec![
+                    config.amm_program(),
+                    config.cp_program(),
+                    config.clmm_program(),
+                ]
+            } else {
+                program
+                    .iter()
+                    .map(|program| match program.as_str() {
+                        "amm" => config.amm_program(),
+                        "cpswap" => config.cp_program(),
+                        _ => config.clmm_program(),
+                    })
+                    .collect()
+            };
+            let mints = mint
+                .iter()
+                .map(|mint| mint.resolve(&config))
+                .collect::<Result<Vec<_>>>()?;
+            let filter = log_tail::TailFilter {
+                pools: pool,
+                mints,
+                event_types: event_type,
+            };
+            log_tail::tail_logs(&rpc_client, &config, &programs, &filter)?;
+            return Ok(());
+        }
+        Command::Twap { pool_id, window } => {
+            let rpc_client = RpcClient::new(config.cluster().url());
+            let pool_twap = get_pool_twap(&rpc_client, &config, &pool_id, window)?;
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            common::print_pool_twap(&pool_twap, window, now);
+            return Ok(());
+        }
+        Command::InspectMint { mint } => {
+            let rpc_client = RpcClient::new(config.cluster().url());
+            let mint = mint.resolve(&config)?;
+            let inspections =
+                common::inspect_mints(&rpc_client, &[mint], config.max_transfer_fee())?;
+            let symbols = common::get_token_symbols(&rpc_client, &config, &[mint])?;
+            if let Some(token) = symbols.get(&mint) {
+                println!("name:{}, symbol:{}", token.name, token.symbol);
+            }
+            common::print_mint_inspection(&inspections[0]);
+            return Ok(());
+        }
+        Command::Pools {
+            program,
+            mint,
+            refresh,
+        } => {
+            let rpc_client = RpcClient::new(config.cluster().url());
+            let path = config.pool_index().ok_or(format_err!(
+                "the pool index file is not set by --config.pool_index"
+            ))?;
+            if mint.len() > 2 {
+                return Err(format_err!("at most a pair of mints is queried"));
+            }
+            let mints = mint
+                .iter()
+                .map(|mint| mint.resolve(&config))
+                .collect::<Result<Vec<_>>>()?;
+            let programs: Vec<PoolProgram> = if program.is_empty() {
+                PoolProgram::ALL.to_vec()
+            } else {
+                program
+                    .iter()
+                    .map(|program| PoolProgram::from_str(program))
+                    .collect::<Result<Vec<_>>>()?
+            };
+            let mut index = common::load_pool_index(&path)?;
+            for program in programs.iter() {
+                if !refresh && !index.is_stale(*program, config.pool_index_ttl()) {
+                    continue;
+                }
+                match program {
+                    PoolProgram::Amm => {
+                        amm_cli::refresh_pool_index(&rpc_client, &config, &path, &mut index)?
+                    }
+                    PoolProgram::CpSwap => {
+                        cpswap_cli::refresh_pool_index(&rpc_client, &config, &path, &mut index)?
+                    }
+                    PoolProgram::Clmm => {
+                        clmm_cli::refresh_pool_index(&rpc_client, &config, &path, &mut index)?
+                    }
+                };
+            }
+            let mut count = 0;
+            for program in programs {
+                for pool in index.find_pools(Some(program), mints.first(), mints.get(1)) {
+                    println!(
+                        "{} pool_id:{}, mint_0:{}, mint_1:{}, fee_config:{}",
+                        pool.program, pool.pool_id, pool.mint_0, pool.mint_1, pool.fee_config
+                    );
+                    count += 1;
+                }
+            }
+            println!("{} pools of {} indexed", count, index.pools.len());
+            return Ok(());
+        }
+        Command::Cleanup { dry_run } => {
+            if config.sign_only() {
+                return Err(format_err!(
+                    "cleanup sends several transactions and can not be signed offline"
+                ));
+            }
+            let rpc_client = RpcClient::new(config.cluster().url());
+            let payer = common_utils::read_keypair_file(&config.wallet())?;
+            let owner = payer.pubkey();
+            let clmm_program = config.clmm_program();
+            let (accounts, skipped) =
+                common::get_closable_token_accounts(&rpc_client, &owner, |nft_mint| {
+                    clmm_cli::personal_position_key(&clmm_program, nft_mint)
+                })?;
+            common::print_closable_token_accounts(&accounts, &skipped);
+            if dry_run || accounts.is_empty() {
+                return Ok(());
+            }
+            if !config.confirm() && !config.yes() && !config.simulate() {
+                return Err(format_err!(
+                    "cleanup closes the wsol accounts with their sol, pass --confirm or --yes"
+                ));
+            }
+            let cost_accounts = [
+                amm_cli::cost_accounts(),
+                cpswap_cli::cost_accounts(),
+                clmm_cli::cost_accounts(&config),
+            ];
+            let signing_keypairs: Vec<Arc<dyn Signer>> = vec![Arc::new(payer)];
+            let instructions = common::close_token_account_instructions(&owner, &accounts);
+            let batches = common::batch_instructions(&instructions, &owner);
+            let balance = rpc_client.get_balance(&owner)?;
+            let mut closed = 0;
+            for (i, batch) in batches.iter().enumerate() {
+                let txn = rpc::build_txn(&rpc_client, batch, &owner, &signing_keypairs)?;
+                if config.confirm() {
+                    let preview = common::preview_txn(&rpc_client, &txn, &owner, &cost_accounts)?;
+                    common::print_txn_preview(&rpc_client, &config, &preview)?;
+                    if !config.simulate() && !config.yes() {
+                        common::confirm_txn()?;
+                    }
+                }
+                if config.simulate() {
+                    simulate_txn(&rpc_client, &config, &txn)?;
+                    continue;
+                }
+                let signature = rpc::send_txn(&rpc_client, &txn, config.skip_preflight())?;
+                closed += batch.len();
+                println!(
+                    "transaction {}/{}: closed {} token accounts, signature: {}",
+                    i + 1,
+                    batches.len(),
+                    batch.len(),
+                    signature
+                );
+            }
+            if closed > 0 {
+                let reclaimed = rpc_client.get_balance(&owner)?.saturating_sub(balance);
+                println!(
+                    "closed {} token accounts in {} transactions, reclaimed {} sol net of the fees",
+                    closed,
+                    batches.len(),
+                    common::raw_amount_to_ui(reclaimed, 9)
+                );
+            }
+            return Ok(());
+        }
+        command => command,
+    };
+
+    let fee_payer = config.wallet_pubkey()?;
+    // the wallet signs offline in the sign-only mode
+    let payer: Option<Arc<dyn Signer>> = if config.sign_only() {
+        None
+    } else {
+        Some(Arc::new(common_utils::read_keypair_file(&config.wallet())?))
+    };
+    let rpc_client = RpcClient::new(config.cluster().url());
+
+    // the nonce commands operate on the nonce account itself
+    let with_nonce = !matches!(command, Command::Nonce { .. });
+    // the mints of the swap are checked and the recent price movement of the auto slippage is
+    // read once, not on every re-quote
+    if let Some(pool_id) = swap_pool_id(&command) {
+        let pool_mints = swap_indexer::get_pool_mints(&rpc_client, &config, &pool_id)?;
+        common::check_mint_risks(
+            &rpc_client,
+            &config,
+            &[pool_mints.mint_0, pool_mints.mint_1],
+        )?;
+        if config.auto_slippage() {
+            let price_movement_bps = get_price_movement_bps(&rpc_client, &config, &command)
+                .unwrap_or_else(|e| {
+                    println!("failed to read the recent price movement: {}", e);
+                    config.max_slippage()
+                });
+            config.set_price_movement_bps(price_movement_bps);
+        }
     }
+    let mut retries = 0;
+    loop {
+        // the command is processed again on every retry to re-quote with the latest pool state
+        let mut signing_keypairs: Vec<Arc<dyn Signer>> = payer.iter().cloned().collect();
+        let CommandInstructions {
+            instructions,
+            slippage_bps,
+        } = match process_command(command.clone(), &config, &mut signing_keypairs)? {
+            Some(command_instructions) => command_instructions,
+            None => break,
+        };
+        // the slippage applied to the quote, the auto slippage varies with each quote
+        let slippage_bps = slippage_bps.unwrap_or(config.slippage());
+        // build txn
+        if config.sign_only() {
+            // the fee payer signs offline
+            signing_keypairs.retain(|signer| signer.pubkey() != fee_payer);
+        }
+        let nonce_account = config.nonce_account().filter(|_| with_nonce);
+        let txn = if let Some(nonce_account) = nonce_account {
+            let nonce_authority = if config.sign_only() {
+                // the nonce authority signs offline too
+                rpc::get_nonce_data(&rpc_client, &nonce_account)?.authority
+            } else if let Some(nonce_authority) = config.nonce_authority() {
+                let authority_keypair = common_utils::read_keypair_file(&nonce_authority)?;
+                let authority = authority_keypair.pubkey();
+                let signer: Arc<dyn Signer> = Arc::new(authority_keypair);
+                if !signing_keypairs.contains(&signer) {
+                    signing_keypairs.push(signer);
+                }
+                authority
+            } else {
+                fee_payer
+            };
+            rpc::build_nonce_txn(
+                &rpc_client,
+                &instructions,
+                &fee_payer,
+                &nonce_account,
+                &nonce_authority,
+                &signing_keypairs,
+            )
+            .unwrap()
+        } else {
+            rpc::build_txn(&rpc_client, &instructions, &fee_payer, &signing_keypairs).unwrap()
+        };
+        if let Some(tolerance_bps) = config.quote_tolerance_bps() {
+            if !config.sign_only()
+                && !verify_quote(&rpc_client, &config, &txn, slippage_bps, tolerance_bps)?
+            {
+                if retries >= config.max_retries() {
+                    return Err(format_err!(
+                        "the quote is off the simulation after {} retries",
+                        retries
+                    ));
+                }
+                retries += 1;
+                println!("re-quote, retry {}/{}", retries, config.max_retries());
+                continue;
+            }
+        }
+        let cost_accounts = [
+            amm_cli::cost_accounts(),
+            cpswap_cli::cost_accounts(),
+            clmm_cli::cost_accounts(&config),
+        ];
+        if config.confirm() && !config.sign_only() {
+            // the preview includes the cost estimate
+            let preview = common::preview_txn(&rpc_client, &txn, &fee_payer, &cost_accounts)?;
+            common::print_txn_preview(&rpc_client, &config, &preview)?;
+            if !config.simulate() && !config.yes() {
+                common::confirm_txn()?;
+            }
+        } else if config.estimate_cost() {
+            let estimate =
+                common::estimate_txn_cost(&rpc_client, &txn, &fee_payer, &cost_accounts)?;
+            common::print_cost_estimate(&estimate);
+        }
+        if config.sign_only() {
+            let offline_txn = offline::export_txn(&txn, config.encoding())?;
+            offline::write_offline_txn(&offline_txn, config.output_path().as_deref())?;
+        } else if config.simulate() {
+            simulate_txn(&rpc_client, &config, &txn)?;
+        } else {
+            //  send txn
+            match rpc::send_txn_with_rebroadcast(&rpc_client, &txn, config.skip_preflight())? {
+                Some(signature) => {
+                    print_receipt(&rpc_client, &config, &signature, &txn.message, slippage_bps)?;
+                }
+                None => {
+                    if retries >= config.max_retries() {
+                        return Err(anyhow::format_err!(
+                            "blockhash expired after {} retries",
+                            retries
+                        ));
+                    }
+                    retries += 1;
+                    println!(
+                        "re-quote and re-sign, retry {}/{}",
+                        retries,
+                        config.max_retries()
+                    );
+                    continue;
+                }
+            }
+        }
+        break;
+    }
+    Ok(())
+}
 
-    let instructions = match opts.command {
+fn process_command(
+    command: Command,
+    config: &common_types::CommonConfig,
+    signing_keypairs: &mut Vec<Arc<dyn Signer>>,
+) -> Result<Option<CommandInstructions>> {
+    let instructions = match command {
         Command::CPSWAP { subcmd } => {
-            cpswap_cli::process_cpswap_commands(subcmd, &config, &mut signing_keypairs).unwrap()
+            cpswap_cli::process_cpswap_commands(subcmd, config, signing_keypairs)?
         }
-        Command::AMM { subcmd } => amm_cli::process_amm_commands(subcmd, &config).unwrap(),
+        Command::AMM { subcmd } => amm_cli::process_amm_commands(subcmd, config)?,
         Command::CLMM { subcmd } => {
-            clmm_cli::process_clmm_commands(subcmd, &config, &mut signing_keypairs).unwrap()
+            clmm_cli::process_clmm_commands(subcmd, config, signing_keypairs)?
+        }
+        Command::Nonce { subcmd } => {
+            common::process_nonce_commands(subcmd, config, signing_keypairs)?
+                .map(CommandInstructions::from)
+        }
+        Command::Sign { .. }
+        | Command::Submit { .. }
+        | Command::DecodeTx { .. }
+        | Command::Index { .. }
+        | Command::Watch { .. }
+        | Command::Tail { .. }
+        | Command::Twap { .. }
+        | Command::InspectMint { .. }
+        | Command::Pools { .. }
+        | Command::Cleanup { .. } => {
+            unreachable!()
         }
     };
-    match instructions {
-        Some(instructions) => {
-            // build txn
-            let rpc_client = RpcClient::new(config.cluster().url());
-            let txn =
-                rpc::build_txn(&rpc_client, &instructions, &fee_payer, &signing_keypairs).unwrap();
-            if config.simulate() {
-                let sig = rpc::simulate_transaction(
-                    &rpc_client,
-                    &txn,
-                    false,
-                    CommitmentConfig::confirmed(),
-                );
-                println!("{:#?}", sig);
-            } else {
-                //  send txn
-                let sig = rpc::send_txn(&rpc_client, &txn, true);
-                println!("{:#?}", sig);
+    Ok(instructions)
+}
+
+/// The pool of the swap commands.
+fn swap_pool_id(command: &Command) -> Option<Pubkey> {
+    match command {
+        Command::AMM {
+            subcmd: AmmCommands::Swap { pool_id, .. },
+        }
+        | Command::CPSWAP {
+            subcmd: CpSwapCommands::Swap { pool_id, .. },
+        }
+        | Command::CLMM {
+            subcmd: ClmmCommands::Swap { pool_id, .. },
+        } => Some(*pool_id),
+        _ => None,
+    }
+}
+
+/// The recent price movement in bps of the pool of the swap command.
+fn get_price_movement_bps(
+    rpc_client: &RpcClient,
+    config: &common_types::CommonConfig,
+    command: &Command,
+) -> Result<u64> {
+    match command {
+        Command::AMM {
+            subcmd: AmmCommands::Swap { pool_id, .. },
+        } => amm_cli::get_price_movement_bps(
+            rpc_client,
+            &config.amm_program(),
+            pool_id,
+            common_types::AUTO_SLIPPAGE_WINDOW,
+        ),
+        Command::CPSWAP {
+            subcmd: CpSwapCommands::Swap { pool_id, .. },
+        } => cpswap_cli::get_price_movement_bps(
+            rpc_client,
+            pool_id,
+            common_types::AUTO_SLIPPAGE_WINDOW,
+        ),
+        Command::CLMM {
+            subcmd: ClmmCommands::Swap { pool_id, .. },
+        } => clmm_cli::get_price_movement_bps(
+            rpc_client,
+            pool_id,
+            common_types::AUTO_SLIPPAGE_WINDOW,
+        ),
+        _ => Err(format_err!("the command does not swap")),
+    }
+}
+
+/// The amm pools have no observation account.
+fn get_pool_twap(
+    rpc_client: &RpcClient,
+    config: &common_types::CommonConfig,
+    pool_id: &Pubkey,
+    window: u64,
+) -> Result<common::PoolTwap> {
+    let owner = rpc_client.get_account(pool_id)?.owner;
+    if owner == config.cp_program() {
+        cpswap_cli::get_pool_twap(rpc_client, pool_id, window)
+    } else if owner == config.clmm_program() {
+        clmm_cli::get_pool_twap(rpc_client, pool_id, window)
+    } else {
+        Err(format_err!(
+            "{} is not a cp-swap or clmm pool with the observations",
+            pool_id
+        ))
+    }
+}
+
+/// Fetch the landed transaction and print the fee, compute units, the amounts decoded from
+/// the program events and the realized slippage versus the quote of the swap instruction.
+fn print_receipt(
+    rpc_client: &RpcClient,
+    config: &common_types::CommonConfig,
+    signature: &Signature,
+    message: &Message,
+    slippage_bps: u64,
+) -> Result<()> {
+    let mut txn_receipt = receipt::get_txn_receipt(rpc_client, signature)?;
+    // only the token accounts of the fee payer are concerned
+    let fee_payer = message.account_keys[0].to_string();
+    txn_receipt
+        .token_balance_changes
+        .retain(|change| change.owner.as_ref() == Some(&fee_payer));
+    let events = get_receipt_events(config, &txn_receipt.log_messages)?;
+    let quote = get_swap_quote(config, message)?;
+    receipt::print_receipt(&txn_receipt, &events, quote.as_ref(), slippage_bps);
+    Ok(())
+}
+
+/// The swap or liquidity amounts of the raydium events in the logs.
+fn get_receipt_events(
+    config: &common_types::CommonConfig,
+    log_messages: &[String],
+) -> Result<Vec<ReceiptEvent>> {
+    let mut events = Vec::new();
+    for program_log in confirmed_txn::get_program_logs(log_messages) {
+        if let Some(event) =
+            decoder::decode_event(config, &program_log.program_id, &program_log.log)?
+        {
+            events.extend(event.receipt_event());
+        }
+    }
+    Ok(events)
+}
+
+/// The quote of the first raydium swap instruction of the message.
+fn get_swap_quote(
+    config: &common_types::CommonConfig,
+    message: &Message,
+) -> Result<Option<SwapQuote>> {
+    for instruction in &message.instructions {
+        let program_id = message.account_keys[instruction.program_id_index as usize];
+        if let Some(instruction) =
+            decoder::decode_instruction(config, &program_id, &instruction.data)?
+        {
+            if let Some(quote) = instruction.swap_quote() {
+                return Ok(Some(quote));
             }
         }
+    }
+    Ok(None)
+}
+
+/// Simulate the swap of the transaction and compare the amount it swaps to the off-chain quote,
+/// returns false if it is off the quote by more than the tolerance.
+fn verify_quote(
+    rpc_client: &RpcClient,
+    config: &common_types::CommonConfig,
+    txn: &Transaction,
+    slippage_bps: u64,
+    tolerance_bps: u64,
+) -> Result<bool> {
+    let quote = match get_swap_quote(config, &txn.message)? {
+        Some(quote) => quote,
+        None => return Ok(true),
+    };
+    let simulation = common::simulate_txn(rpc_client, txn)?;
+    if let Some(err) = &simulation.err {
+        let failure = common::get_instruction_failure(&txn.message, err, |program_id, code| {
+            decoder::error_name(config, program_id, code, &simulation.logs)
+        });
+        return Err(format_err!(
+            "the simulation of the swap failed: {}{}",
+            err,
+            failure
+                .and_then(|failure| failure.name)
+                .map_or(String::new(), |name| format!(" {}", name))
+        ));
+    }
+    let events = get_receipt_events(config, &simulation.logs)?;
+    let token_balance_changes = common::get_simulated_token_balance_changes(&simulation.changes);
+    let swap = match common::simulated_swap(
+        &events,
+        &token_balance_changes,
+        &txn.message.account_keys[0],
+    ) {
+        Some(swap) => swap,
         None => {
-            // do nothing
+            println!("the swap is not found in the simulation, the quote is not verified");
+            return Ok(true);
+        }
+    };
+    match common::check_quote(&quote, slippage_bps, &swap, tolerance_bps) {
+        Result::Ok(deviation_bps) => {
+            println!(
+                "quote verified by simulation: {:?}, deviation_bps:{}",
+                swap, deviation_bps
+            );
+            Ok(true)
+        }
+        Err(e) => {
+            println!("{}", e);
+            Ok(false)
         }
     }
+}
+
+/// Simulate the transaction and print the raydium events it emits, the balance changes of the
+/// token accounts it writes, the compute units consumed and the program error it fails with.
+fn simulate_txn(
+    rpc_client: &RpcClient,
+    config: &common_types::CommonConfig,
+    txn: &Transaction,
+) -> Result<common::TxnSimulation> {
+    let simulation = common::simulate_txn(rpc_client, txn)?;
+    for program_log in confirmed_txn::get_program_logs(&simulation.logs) {
+        if let Some(event) =
+            decoder::decode_event(config, &program_log.program_id, &program_log.log)?
+        {
+            println!("event: {:#?}", event);
+        }
+    }
+    let token_balance_changes = common::get_simulated_token_balance_changes(&simulation.changes);
+    confirmed_txn::print_token_balance_changes(&token_balance_changes);
+    let failure = simulation.err.as_ref().and_then(|err| {
+        common::get_instruction_failure(&txn.message, err, |program_id, code| {
+            decoder::error_name(config, program_id, code, &simulation.logs)
+        })
+    });
+    common::print_simulation_result(&simulation, failure.as_ref());
+    Ok(simulation)
+}
+
+/// Walk the top level and inner instructions of the transaction and decode the ones of
+/// the raydium programs, then decode the program events in the order they were emitted.
+fn decode_txn(
+    rpc_client: &RpcClient,
+    config: &common_types::CommonConfig,
+    signature: &Signature,
+) -> Result<()> {
+    let confirmed_txn = confirmed_txn::get_confirmed_txn(rpc_client, signature)?;
+    let (versioned_txn, meta) = confirmed_txn::decode_confirmed_txn(&confirmed_txn)?;
+    let account_keys = confirmed_txn::get_account_keys(&versioned_txn, &meta)?;
+    println!(
+        "signature:{}, slot:{}, fee:{}, compute_units_consumed:{:?}, err:{:?}",
+        signature,
+        confirmed_txn.slot,
+        meta.fee,
+        Option::<u64>::from(meta.compute_units_consumed.clone()),
+        meta.err
+    );
+
+    let instructions =
+        confirmed_txn::get_confirmed_instructions(&versioned_txn, &meta, &account_keys)?;
+    for instruction in instructions {
+        let decoded =
+            decoder::decode_instruction(config, &instruction.program_id, &instruction.data)?;
+        match decoded {
+            Some(decoded) => {
+                println!("instruction {}: {:#?}", instruction.index, decoded);
+            }
+            None => {
+                println!(
+                    "instruction {}: program:{}",
+                    instruction.index, instruction.program_id
+                );
+                continue;
+            }
+        }
+        let names = decoder::instruction_account_names(
+            config,
+            &instruction.program_id,
+            &instruction.data,
+            instruction.accounts.len(),
+        );
+        for (i, account) in instruction.accounts.iter().enumerate() {
+            println!(
+                "    {}: {}",
+                names.get(i).copied().unwrap_or("remaining_account"),
+                account
+            );
+        }
+    }
+
+    let log_messages: Vec<String> = Option::from(meta.log_messages.clone()).unwrap_or_default();
+    for program_log in confirmed_txn::get_program_logs(&log_messages) {
+        if let Some(event) =
+            decoder::decode_event(config, &program_log.program_id, &program_log.log)?
+        {
+            println!("event: {:#?}", event);
+        }
+    }
+
+    let token_balance_changes = confirmed_txn::get_token_balance_changes(&meta, &account_keys)?;
+    confirmed_txn::print_token_balance_changes(&token_balance_changes);
     Ok(())
 }
 
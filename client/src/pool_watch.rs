@@ -0,0 +1,86 @@
+use anyhow::{format_err, Result};
+use common::{common_types, PoolSnapshot};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use std::collections::HashMap;
+
+type PoolSnapshotFn = fn(&Pubkey, &HashMap<Pubkey, Account>) -> Result<PoolSnapshot>;
+
+/// Subscribe to the accounts of the pool and print the snapshot of the pool once it changed.
+/// An alert is printed when the price or the reserves moved more than the thresholds since
+/// the last alert.
+pub fn watch_pool(
+    rpc_client: &RpcClient,
+    config: &common_types::CommonConfig,
+    pool_id: &Pubkey,
+    json: bool,
+    alert_price_change_bps: Option<u64>,
+    alert_reserve_change_bps: Option<u64>,
+) -> Result<()> {
+    let owner = rpc_client.get_account(pool_id)?.owner;
+    let pool_snapshot: PoolSnapshotFn = if owner == config.amm_program() {
+        amm_cli::pool_snapshot
+    } else if owner == config.cp_program() {
+        cpswap_cli::pool_snapshot
+    } else if owner == config.clmm_program() {
+        clmm_cli::pool_snapshot
+    } else {
+        return Err(format_err!(
+            "{} is not a pool of the raydium programs",
+            pool_id
+        ));
+    };
+    let get_watch_accounts = || {
+        if owner == config.amm_program() {
+            amm_cli::get_watch_accounts(rpc_client, pool_id)
+        } else if owner == config.cp_program() {
+            cpswap_cli::get_watch_accounts(rpc_client, pool_id)
+        } else {
+            clmm_cli::get_watch_accounts(rpc_client, &config.clmm_program(), pool_id)
+        }
+    };
+
+    let mut last_snapshot: Option<PoolSnapshot> = None;
+    let mut baseline: Option<PoolSnapshot> = None;
+    loop {
+        let accounts = get_watch_accounts()?;
+        common::watch_accounts(
+            rpc_client,
+            config.cluster().ws_url(),
+            &accounts,
+            |slot, account_datas| {
+                // the tick arrays of the clmm pool are the ones around the current price
+                if owner == config.clmm_program()
+                    && clmm_cli::watch_accounts_changed(
+                        &config.clmm_program(),
+                        pool_id,
+                        &accounts,
+                        account_datas,
+                    )?
+                {
+                    return Ok(false);
+                }
+                let snapshot = pool_snapshot(pool_id, account_datas)?;
+                if last_snapshot.as_ref() == Some(&snapshot) {
+                    return Ok(true);
+                }
+                let alerts = match &baseline {
+                    Some(baseline) => common::snapshot_alerts(
+                        baseline,
+                        &snapshot,
+                        alert_price_change_bps,
+                        alert_reserve_change_bps,
+                    ),
+                    None => Vec::new(),
+                };
+                if baseline.is_none() || !alerts.is_empty() {
+                    baseline = Some(snapshot.clone());
+                }
+                common::print_snapshot(slot, &snapshot, &alerts, json);
+                last_snapshot = Some(snapshot);
+                Ok(true)
+            },
+        )?;
+        eprintln!("the price left the watched tick arrays, subscribe to the new ones");
+    }
+}
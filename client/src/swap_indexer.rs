@@ -0,0 +1,198 @@
+use crate::decoder;
+use anyhow::{format_err, Result};
+use common::{common_types, confirmed_txn, indexer, PoolMints, Trade};
+use solana_client::{
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+    rpc_response::RpcConfirmedTransactionStatusWithSignature,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use std::str::FromStr;
+
+/// The max signatures returned by `getSignaturesForAddress` in one request.
+const SIGNATURES_PAGE_LIMIT: usize = 1000;
+
+pub fn get_pool_mints(
+    rpc_client: &RpcClient,
+    config: &common_types::CommonConfig,
+    pool_id: &Pubkey,
+) -> Result<PoolMints> {
+    if let Some(pool) = common::get_indexed_pool(config, pool_id)? {
+        return Ok(pool.pool_mints());
+    }
+    let owner = rpc_client.get_account(pool_id)?.owner;
+    if owner == config.amm_program() {
+        amm_cli::get_pool_mints(rpc_client, pool_id)
+    } else if owner == config.cp_program() {
+        cpswap_cli::get_pool_mints(rpc_client, pool_id)
+    } else if owner == config.clmm_program() {
+        clmm_cli::get_pool_mints(rpc_client, pool_id)
+    } else {
+        Err(format_err!(
+            "{} is not a pool of the raydium programs",
+            pool_id
+        ))
+    }
+}
+
+/// Page the signatures of the address from newest to oldest, starting before `before` and
+/// stopping at `until` or after `limit` signatures.
+/// Returns the signatures and whether the end of the range is reached.
+fn get_signatures(
+    rpc_client: &RpcClient,
+    address: &Pubkey,
+    mut before: Option<Signature>,
+    until: Option<Signature>,
+    limit: usize,
+) -> Result<(Vec<RpcConfirmedTransactionStatusWithSignature>, bool)> {
+    let mut signatures = Vec::new();
+    while signatures.len() < limit {
+        let page_limit = SIGNATURES_PAGE_LIMIT.min(limit - signatures.len());
+        let page = rpc_client.get_signatures_for_address_with_config(
+            address,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit: Some(page_limit),
+                commitment: Some(CommitmentConfig::confirmed()),
+            },
+        )?;
+        let reached_end = page.len() < page_limit;
+        if let Some(last) = page.last() {
+            before = Some(Signature::from_str(&last.signature)?);
+        }
+        signatures.extend(page);
+        if reached_end {
+            return Ok((signatures, true));
+        }
+    }
+    Ok((signatures, false))
+}
+
+/// Decode the swap events of the pool in the transaction.
+fn get_swap_trades(
+    rpc_client: &RpcClient,
+    config: &common_types::CommonConfig,
+    pool_id: &Pubkey,
+    signature: &Signature,
+) -> Result<Vec<Trade>> {
+    let confirmed_txn = confirmed_txn::get_confirmed_txn(rpc_client, signature)?;
+    // the candles are built on the block time
+    let block_time = match confirmed_txn.block_time {
+        Some(block_time) => block_time,
+        None => {
+            println!(
+                "skip the trades of {}, the block time is unknown",
+                signature
+            );
+            return Ok(Vec::new());
+        }
+    };
+    let (versioned_txn, meta) = confirmed_txn::decode_confirmed_txn(&confirmed_txn)?;
+    let account_keys = confirmed_txn::get_account_keys(&versioned_txn, &meta)?;
+    let instructions =
+        confirmed_txn::get_confirmed_instructions(&versioned_txn, &meta, &account_keys)?;
+    let log_messages: Vec<String> = Option::from(meta.log_messages.clone()).unwrap_or_default();
+
+    let mut trades = Vec::new();
+    for program_log in confirmed_txn::get_program_logs(&log_messages) {
+        let event = match decoder::decode_event(config, &program_log.program_id, &program_log.log)?
+        {
+            Some(event) => event,
+            None => continue,
+        };
+        let accounts = instructions
+            .get(program_log.invoke_index)
+            .filter(|instruction| instruction.program_id == program_log.program_id)
+            .map(|instruction| instruction.accounts.as_slice())
+            .unwrap_or_default();
+        if let Some(swap) = event.swap_trade(accounts) {
+            if swap.pool_id == *pool_id {
+                trades.push(Trade {
+                    signature: *signature,
+                    slot: confirmed_txn.slot,
+                    block_time,
+                    swap,
+                });
+            }
+        }
+    }
+    Ok(trades)
+}
+
+/// Decode the trades of the signatures from oldest to newest, failed transactions are skipped.
+fn index_signatures(
+    rpc_client: &RpcClient,
+    config: &common_types::CommonConfig,
+    pool_id: &Pubkey,
+    signatures: &[RpcConfirmedTransactionStatusWithSignature],
+) -> Result<Vec<Trade>> {
+    let mut trades = Vec::new();
+    for status in signatures.iter().rev() {
+        if status.err.is_some() {
+            continue;
+        }
+        let signature = Signature::from_str(&status.signature)?;
+        trades.extend(get_swap_trades(rpc_client, config, pool_id, &signature)?);
+    }
+    Ok(trades)
+}
+
+/// Index up to `limit` signatures of the pool into the csv store.
+/// The signatures after the newest indexed one are caught up first from oldest to newest, then
+/// the older signatures are backfilled, both page by page. The cursor is saved after each page
+/// so an interrupted run resumes from the last saved page.
+pub fn index_pool(
+    rpc_client: &RpcClient,
+    config: &common_types::CommonConfig,
+    pool_id: &Pubkey,
+    store: &str,
+    limit: usize,
+) -> Result<()> {
+    let mut cursor = indexer::load_cursor(store)?;
+    let mut remaining = limit;
+    if cursor.newest.is_some() {
+        // the signatures are listed newest first, the oldest ones are caught up first and the
+        // newer ones beyond the limit are left to the next run
+        let (signatures, _) = get_signatures(rpc_client, pool_id, None, cursor.newest, usize::MAX)?;
+        let signatures = &signatures[signatures.len().saturating_sub(remaining)..];
+        for page in signatures.rchunks(SIGNATURES_PAGE_LIMIT) {
+            let trades = index_signatures(rpc_client, config, pool_id, page)?;
+            indexer::append_trades(store, &trades)?;
+            cursor.newest = Some(Signature::from_str(&page[0].signature)?);
+            indexer::save_cursor(store, &cursor)?;
+            remaining -= page.len();
+            println!(
+                "caught up {} signatures, {} trades",
+                page.len(),
+                trades.len()
+            );
+        }
+    }
+
+    while !cursor.backfilled && remaining > 0 {
+        let (signatures, reached_end) = get_signatures(
+            rpc_client,
+            pool_id,
+            cursor.oldest,
+            None,
+            remaining.min(SIGNATURES_PAGE_LIMIT),
+        )?;
+        let trades = index_signatures(rpc_client, config, pool_id, &signatures)?;
+        indexer::append_trades(store, &trades)?;
+        if let (None, Some(newest)) = (cursor.newest, signatures.first()) {
+            cursor.newest = Some(Signature::from_str(&newest.signature)?);
+        }
+        if let Some(oldest) = signatures.last() {
+            cursor.oldest = Some(Signature::from_str(&oldest.signature)?);
+        }
+        cursor.backfilled = reached_end;
+        indexer::save_cursor(store, &cursor)?;
+        remaining -= signatures.len();
+        println!(
+            "backfilled {} signatures, {} trades",
+            signatures.len(),
+            trades.len()
+        );
+    }
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use common::common_utils;
+use common::{common_types::Slippage, common_utils};
 use raydium_amm::math::{CheckedCeilDiv, U128};
 
 pub fn pool_vault_deduct_pnl(
@@ -216,6 +216,7 @@ pub fn withdraw_amounts_with_slippage(
     Ok((receive_min_coin_amount, receive_min_pc_amount))
 }
 
+/// Returns the other amount threshold, the price impact and the slippage applied in bps.
 pub fn swap_with_slippage(
     pc_vault_amount: u64,
     coin_vault_amount: u64,
@@ -224,8 +225,8 @@ pub fn swap_with_slippage(
     swap_direction: raydium_amm::math::SwapDirection,
     amount_specified: u64,
     swap_base_in: bool,
-    slippage_bps: u64,
-) -> Result<u64> {
+    slippage: Slippage,
+) -> Result<(u64, u64, u64)> {
     let other_amount_threshold = swap_exact_amount(
         pc_vault_amount,
         coin_vault_amount,
@@ -235,6 +236,21 @@ pub fn swap_with_slippage(
         amount_specified,
         swap_base_in,
     )?;
+    let spot_rate = match swap_direction {
+        raydium_amm::math::SwapDirection::Coin2PC => {
+            pc_vault_amount as f64 / coin_vault_amount as f64
+        }
+        raydium_amm::math::SwapDirection::PC2Coin => {
+            coin_vault_amount as f64 / pc_vault_amount as f64
+        }
+    };
+    let quote_rate = if swap_base_in {
+        other_amount_threshold as f64 / amount_specified as f64
+    } else {
+        amount_specified as f64 / other_amount_threshold as f64
+    };
+    let price_impact_bps = common_utils::price_impact_bps(spot_rate, quote_rate);
+    let slippage_bps = slippage.bps(price_impact_bps);
     let other_amount_threshold = if swap_base_in {
         // min out
         common_utils::amount_with_slippage(other_amount_threshold, slippage_bps, false)?
@@ -242,5 +258,5 @@ pub fn swap_with_slippage(
         // max in
         common_utils::amount_with_slippage(other_amount_threshold, slippage_bps, true)?
     };
-    Ok(other_amount_threshold)
+    Ok((other_amount_threshold, price_impact_bps, slippage_bps))
 }
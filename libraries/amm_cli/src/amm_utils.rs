@@ -4,23 +4,35 @@ use arrayref::array_ref;
 use crate::{
     amm_math,
     amm_types::{AmmDepositInfoResult, AmmKeys, AmmSwapInfoResult, AmmWithdrawInfoResult},
+    decode_amm_ix_event,
+};
+use common::{
+    common_types::{Slippage, PROGRAM_LOG, RAY_LOG},
+    common_utils, confirmed_txn, get_watched_account, rpc, AmmIndexKeys, CommonConfig,
+    CostAccounts, FundsCheck, IndexedPool, PoolIndex, PoolMints, PoolOperation, PoolProgram,
+    PoolSnapshot, PoolStatus, PoolSummary,
 };
-use common::{common_utils, rpc};
 use raydium_amm::state::Loadable;
-use solana_client::rpc_client::RpcClient;
-use solana_sdk::pubkey::Pubkey;
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_sdk::{
+    account::Account, commitment_config::CommitmentConfig, program_pack::Pack, pubkey::Pubkey,
+    signature::Signature,
+};
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 pub fn calculate_deposit_info(
     rpc_client: &RpcClient,
-    amm_program: Pubkey,
+    amm_keys: &AmmKeys,
     pool_id: Pubkey,
     amount_specified: u64,
     another_min_limit: bool,
     slippage_bps: u64,
     base_side: u64,
 ) -> Result<AmmDepositInfoResult> {
-    // load amm keys
-    let amm_keys = load_amm_keys(&rpc_client, &amm_program, &pool_id).unwrap();
     // reload accounts data to calculate amm pool vault amount
     // get multiple accounts at the same time to ensure data consistency
     let load_pubkeys = vec![
@@ -98,13 +110,11 @@ pub fn calculate_deposit_info(
 
 pub fn calculate_withdraw_info(
     rpc_client: &RpcClient,
-    amm_program: Pubkey,
+    amm_keys: &AmmKeys,
     pool_id: Pubkey,
     input_lp_amount: u64,
     slippage_bps: Option<u64>,
 ) -> Result<AmmWithdrawInfoResult> {
-    // load amm keys
-    let amm_keys = load_amm_keys(&rpc_client, &amm_program, &pool_id).unwrap();
     // reload accounts data to calculate amm pool vault amount
     // get multiple accounts at the same time to ensure data consistency
     let load_pubkeys = vec![
@@ -185,15 +195,13 @@ pub fn calculate_withdraw_info(
 
 pub fn calculate_swap_info(
     rpc_client: &RpcClient,
-    amm_program: Pubkey,
+    amm_keys: &AmmKeys,
     pool_id: Pubkey,
     user_input_token: Pubkey,
     amount_specified: u64,
-    slippage_bps: u64,
+    slippage: Slippage,
     base_in: bool,
 ) -> Result<AmmSwapInfoResult> {
-    // load amm keys
-    let amm_keys = load_amm_keys(&rpc_client, &amm_program, &pool_id).unwrap();
     // reload accounts data to calculate amm pool vault amount
     // get multiple accounts at the same time to ensure data consistency
     let load_pubkeys = vec![
@@ -214,8 +222,8 @@ pub fn calculate_swap_info(
         common_utils::unpack_token(&amm_pc_vault_account.as_ref().unwrap().data).unwrap();
     let amm_coin_vault =
         common_utils::unpack_token(&amm_coin_vault_account.as_ref().unwrap().data).unwrap();
-    let user_input_token_info =
-        common_utils::unpack_token(&user_input_token_account.as_ref().unwrap().data).unwrap();
+    let user_input_mint =
+        common_utils::get_user_token_mint(&user_input_token, user_input_token_account.as_ref())?;
 
     // assert for amm not share any liquidity to openbook
     assert_eq!(
@@ -231,23 +239,22 @@ pub fn calculate_swap_info(
         )
         .unwrap();
 
-    let (swap_direction, input_mint, output_mint) =
-        if user_input_token_info.base.mint == amm_keys.amm_coin_mint {
-            (
-                raydium_amm::math::SwapDirection::Coin2PC,
-                amm_keys.amm_coin_mint,
-                amm_keys.amm_pc_mint,
-            )
-        } else if user_input_token_info.base.mint == amm_keys.amm_pc_mint {
-            (
-                raydium_amm::math::SwapDirection::PC2Coin,
-                amm_keys.amm_pc_mint,
-                amm_keys.amm_coin_mint,
-            )
-        } else {
-            panic!("input tokens not match pool vaults");
-        };
-    let other_amount_threshold = amm_math::swap_with_slippage(
+    let (swap_direction, input_mint, output_mint) = if user_input_mint == amm_keys.amm_coin_mint {
+        (
+            raydium_amm::math::SwapDirection::Coin2PC,
+            amm_keys.amm_coin_mint,
+            amm_keys.amm_pc_mint,
+        )
+    } else if user_input_mint == amm_keys.amm_pc_mint {
+        (
+            raydium_amm::math::SwapDirection::PC2Coin,
+            amm_keys.amm_pc_mint,
+            amm_keys.amm_coin_mint,
+        )
+    } else {
+        panic!("input tokens not match pool vaults");
+    };
+    let (other_amount_threshold, price_impact_bps, slippage_bps) = amm_math::swap_with_slippage(
         amm_pool_pc_vault_amount,
         amm_pool_coin_vault_amount,
         amm_state.fees.swap_fee_numerator,
@@ -255,7 +262,7 @@ pub fn calculate_swap_info(
         swap_direction,
         amount_specified,
         base_in,
-        slippage_bps,
+        slippage,
     )?;
 
     Ok(AmmSwapInfoResult {
@@ -276,6 +283,8 @@ pub fn calculate_swap_info(
         market_asks: amm_keys.amm_open_order,   // padding readwrite account
         amount_specified,
         other_amount_threshold,
+        price_impact_bps,
+        slippage_bps,
     })
 }
 
@@ -348,6 +357,16 @@ pub fn get_amm_pda_keys(
     })
 }
 
+/// The lamports the amm program charges the creator of a pool, set in its amm config.
+pub fn get_create_pool_fee(client: &RpcClient, amm_program: &Pubkey) -> Result<u64> {
+    let amm_config_key =
+        Pubkey::find_program_address(&[&raydium_amm::processor::AMM_CONFIG_SEED], amm_program).0;
+    let amm_config_account = client.get_account(&amm_config_key)?;
+    let amm_config =
+        raydium_amm::state::AmmConfig::load_from_bytes(&amm_config_account.data).unwrap();
+    Ok(amm_config.create_pool_fee)
+}
+
 pub fn load_amm_keys(
     client: &RpcClient,
     amm_program: &Pubkey,
@@ -374,3 +393,315 @@ pub fn load_amm_keys(
         nonce: amm.nonce as u8,
     })
 }
+
+/// The amm keys from the pool index of the config, loaded from the pool if it is not indexed.
+pub fn get_amm_keys(
+    client: &RpcClient,
+    config: &CommonConfig,
+    amm_pool: &Pubkey,
+) -> Result<AmmKeys> {
+    let amm_program = config.amm_program();
+    let pool = match common::get_indexed_pool(config, amm_pool)? {
+        Some(pool) if pool.amm_keys.is_some() => pool,
+        _ => return load_amm_keys(client, &amm_program, amm_pool),
+    };
+    let keys = pool.amm_keys.unwrap();
+    Ok(AmmKeys {
+        amm_pool: *amm_pool,
+        amm_target: keys.target_orders,
+        amm_coin_vault: pool.vault_0,
+        amm_pc_vault: pool.vault_1,
+        amm_lp_mint: pool.lp_mint,
+        amm_open_order: keys.open_orders,
+        amm_coin_mint: pool.mint_0,
+        amm_pc_mint: pool.mint_1,
+        amm_authority: raydium_amm::processor::Processor::authority_id(
+            &amm_program,
+            raydium_amm::processor::AUTHORITY_AMM,
+            keys.nonce as u8,
+        )?,
+        market: keys.market,
+        market_program: keys.market_program,
+        nonce: keys.nonce as u8,
+    })
+}
+
+/// Decode the keys of the amm pool for the pool index.
+pub fn decode_indexed_pool(amm_pool: &Pubkey, amm_account: &Account) -> Result<IndexedPool> {
+    let amm = raydium_amm::state::AmmInfo::load_from_bytes(&amm_account.data)?;
+    Ok(IndexedPool {
+        program: PoolProgram::Amm,
+        pool_id: *amm_pool,
+        mint_0: amm.coin_vault_mint,
+        mint_1: amm.pc_vault_mint,
+        decimals_0: amm.coin_decimals as u8,
+        decimals_1: amm.pc_decimals as u8,
+        fee_config: Pubkey::default(),
+        vault_0: amm.coin_vault,
+        vault_1: amm.pc_vault,
+        lp_mint: amm.lp_mint,
+        observation: Pubkey::default(),
+        amm_keys: Some(AmmIndexKeys {
+            open_orders: amm.open_orders,
+            target_orders: amm.target_orders,
+            market_program: amm.market_program,
+            market: amm.market,
+            nonce: amm.nonce,
+        }),
+    })
+}
+
+/// Add the new amm pools to the index at `path` and drop the closed ones.
+pub fn refresh_pool_index(
+    client: &RpcClient,
+    config: &CommonConfig,
+    path: &str,
+    index: &mut PoolIndex,
+) -> Result<(usize, usize)> {
+    common::refresh_program_pools(
+        client,
+        path,
+        index,
+        PoolProgram::Amm,
+        &config.amm_program(),
+        core::mem::size_of::<raydium_amm::state::AmmInfo>() as u64,
+        decode_indexed_pool,
+    )
+}
+
+pub fn get_pool_mints(client: &RpcClient, amm_pool: &Pubkey) -> Result<PoolMints> {
+    let amm_data = rpc::get_account(client, amm_pool)?.unwrap();
+    let amm = raydium_amm::state::AmmInfo::load_from_bytes(&amm_data).unwrap();
+    Ok(PoolMints {
+        mint_0: amm.coin_vault_mint,
+        mint_1: amm.pc_vault_mint,
+        decimals_0: amm.coin_decimals as u8,
+        decimals_1: amm.pc_decimals as u8,
+    })
+}
+
+/// The pool and the vaults of the amm pool.
+pub fn get_watch_accounts(client: &RpcClient, amm_pool: &Pubkey) -> Result<Vec<Pubkey>> {
+    let amm_data = rpc::get_account(client, amm_pool)?.unwrap();
+    let amm = raydium_amm::state::AmmInfo::load_from_bytes(&amm_data).unwrap();
+    Ok(vec![*amm_pool, amm.coin_vault, amm.pc_vault])
+}
+
+/// Recompute the reserves and the price of the amm pool from the watched accounts,
+/// the reserves are the vault amounts without take pnl.
+pub fn pool_snapshot(
+    amm_pool: &Pubkey,
+    accounts: &HashMap<Pubkey, Account>,
+) -> Result<PoolSnapshot> {
+    let amm_account = get_watched_account(accounts, amm_pool)?;
+    let amm = raydium_amm::state::AmmInfo::load_from_bytes(&amm_account.data).unwrap();
+    let amm_coin_vault =
+        common_utils::unpack_token(&get_watched_account(accounts, &amm.coin_vault)?.data)?;
+    let amm_pc_vault =
+        common_utils::unpack_token(&get_watched_account(accounts, &amm.pc_vault)?.data)?;
+    let (amm_pool_pc_vault_amount, amm_pool_coin_vault_amount) =
+        raydium_amm::math::Calculator::calc_total_without_take_pnl_no_orderbook(
+            amm_pc_vault.base.amount,
+            amm_coin_vault.base.amount,
+            amm,
+        )
+        .unwrap();
+    let mints = PoolMints {
+        mint_0: amm.coin_vault_mint,
+        mint_1: amm.pc_vault_mint,
+        decimals_0: amm.coin_decimals as u8,
+        decimals_1: amm.pc_decimals as u8,
+    };
+    Ok(PoolSnapshot {
+        pool_id: *amm_pool,
+        mints,
+        price: common::reserves_price(amm_pool_coin_vault_amount, amm_pool_pc_vault_amount, &mints),
+        reserve_0: amm_pool_coin_vault_amount,
+        reserve_1: amm_pool_pc_vault_amount,
+        liquidity: None,
+        tick_current: None,
+        initialized_ticks: None,
+    })
+}
+
+/// The spot price of token 0 quoted in token 1 of the amm pool.
+pub fn get_pool_price(client: &RpcClient, amm_pool: &Pubkey) -> Result<f64> {
+    let accounts = get_watch_accounts(client, amm_pool)?;
+    let rsps = client.get_multiple_accounts(&accounts)?;
+    let account_datas: HashMap<Pubkey, Account> = accounts
+        .into_iter()
+        .zip(rsps)
+        .filter_map(|(key, account)| account.map(|account| (key, account)))
+        .collect();
+    Ok(pool_snapshot(amm_pool, &account_datas)?.price)
+}
+
+/// Decode the `AmmStatus` of the amm pool into the operations it enables, a waiting pool
+/// enables the swaps once the open time passed.
+pub fn decode_pool_status(amm: &raydium_amm::state::AmmInfo) -> PoolStatus {
+    let status = raydium_amm::state::AmmStatus::from_u64(amm.status);
+    let waiting_trade = matches!(status, raydium_amm::state::AmmStatus::WaitingTrade);
+    PoolStatus {
+        status: amm.status,
+        operations: vec![
+            (
+                PoolOperation::Swap,
+                status.swap_permission() || waiting_trade,
+            ),
+            (PoolOperation::Deposit, status.deposit_permission()),
+            (PoolOperation::Withdraw, status.withdraw_permission()),
+        ],
+        open_time: amm.state_data.pool_open_time,
+    }
+}
+
+/// Refuse the operation the status of the amm pool disables, or a swap before the pool opens.
+pub fn check_pool_operation(
+    client: &RpcClient,
+    amm_pool: &Pubkey,
+    operation: PoolOperation,
+) -> Result<()> {
+    let amm_data = rpc::get_account(client, amm_pool)?.unwrap();
+    let amm = raydium_amm::state::AmmInfo::load_from_bytes(&amm_data).unwrap();
+    common::check_pool_status(amm_pool, &decode_pool_status(amm), operation)
+}
+
+fn fee_rate(numerator: u64, denominator: u64) -> u64 {
+    if denominator == 0 {
+        return 0;
+    }
+    (numerator as u128 * common::FEE_RATE_DENOMINATOR as u128 / denominator as u128) as u64
+}
+
+/// The price, the reserves without take pnl, the lp supply, the fees and the status of the amm pool.
+pub fn get_pool_summary(client: &RpcClient, amm_pool: &Pubkey) -> Result<PoolSummary> {
+    let accounts = get_watch_accounts(client, amm_pool)?;
+    let rsps = client.get_multiple_accounts(&accounts)?;
+    let account_datas: HashMap<Pubkey, Account> = accounts
+        .into_iter()
+        .zip(rsps)
+        .filter_map(|(key, account)| account.map(|account| (key, account)))
+        .collect();
+    let snapshot = pool_snapshot(amm_pool, &account_datas)?;
+    let amm_account = get_watched_account(&account_datas, amm_pool)?;
+    let amm = raydium_amm::state::AmmInfo::load_from_bytes(&amm_account.data).unwrap();
+    Ok(PoolSummary {
+        snapshot,
+        lp_supply: Some(amm.lp_amount),
+        fee_rates: vec![
+            (
+                "swap",
+                fee_rate(amm.fees.swap_fee_numerator, amm.fees.swap_fee_denominator),
+            ),
+            (
+                "trade",
+                fee_rate(amm.fees.trade_fee_numerator, amm.fees.trade_fee_denominator),
+            ),
+            (
+                "pnl_share",
+                fee_rate(amm.fees.pnl_numerator, amm.fees.pnl_denominator),
+            ),
+        ],
+        status: decode_pool_status(amm),
+    })
+}
+
+/// The max recent signatures of the amm pool the price movement is read from.
+const RECENT_SWAP_SIGNATURES: usize = 20;
+
+/// The recent price movement in bps of the amm pool, the range of the prices of the swaps
+/// landed in the window, the amm pool has no observation account.
+pub fn get_price_movement_bps(
+    client: &RpcClient,
+    amm_program: &Pubkey,
+    amm_pool: &Pubkey,
+    window: u64,
+) -> Result<u64> {
+    let mints = get_pool_mints(client, amm_pool)?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    let signatures = client.get_signatures_for_address_with_config(
+        amm_pool,
+        GetConfirmedSignaturesForAddress2Config {
+            before: None,
+            until: None,
+            limit: Some(RECENT_SWAP_SIGNATURES),
+            commitment: Some(CommitmentConfig::confirmed()),
+        },
+    )?;
+    let mut prices = Vec::new();
+    for status in signatures {
+        if status.err.is_some()
+            || status
+                .block_time
+                .map_or(true, |block_time| block_time < now - window as i64)
+        {
+            continue;
+        }
+        let signature = Signature::from_str(&status.signature)?;
+        // the price movement is best effort, the transactions the rpc can't serve are skipped
+        let confirmed_txn = match confirmed_txn::get_confirmed_txn_once(client, &signature) {
+            Ok(confirmed_txn) => confirmed_txn,
+            Err(_) => continue,
+        };
+        let (versioned_txn, meta) = confirmed_txn::decode_confirmed_txn(&confirmed_txn)?;
+        let account_keys = confirmed_txn::get_account_keys(&versioned_txn, &meta)?;
+        let instructions =
+            confirmed_txn::get_confirmed_instructions(&versioned_txn, &meta, &account_keys)?;
+        let log_messages: Vec<String> = Option::from(meta.log_messages.clone()).unwrap_or_default();
+        for program_log in confirmed_txn::get_program_logs(&log_messages) {
+            if program_log.program_id != *amm_program {
+                continue;
+            }
+            let event = match program_log
+                .log
+                .strip_prefix(PROGRAM_LOG)
+                .and_then(|log| log.strip_prefix(RAY_LOG))
+            {
+                Some(data) => decode_amm_ix_event::decode_amm_event(data)?,
+                None => None,
+            };
+            let accounts = instructions
+                .get(program_log.invoke_index)
+                .filter(|instruction| instruction.program_id == program_log.program_id)
+                .map(|instruction| instruction.accounts.as_slice())
+                .unwrap_or_default();
+            if let Some(swap) =
+                event.and_then(|event| decode_amm_ix_event::swap_trade(&event, accounts))
+            {
+                if swap.pool_id == *amm_pool {
+                    prices.extend(common::trade_price(&swap, &mints));
+                }
+            }
+        }
+    }
+    Ok(common_utils::price_movement_bps(&prices))
+}
+
+/// The rent of the accounts the initialization of an amm pool creates, the open orders are
+/// padded by the openbook program.
+pub fn create_pool_rent(funds: &mut FundsCheck) {
+    funds.rent(
+        "amm pool",
+        std::mem::size_of::<raydium_amm::state::AmmInfo>(),
+    );
+    funds.rent(
+        "amm open orders",
+        std::mem::size_of::<serum_dex::state::OpenOrders>() + 12,
+    );
+    funds.rent(
+        "amm target orders",
+        std::mem::size_of::<raydium_amm::state::TargetOrders>(),
+    );
+    funds.rent("lp mint", spl_token::state::Mint::LEN);
+    funds.rent("coin vault", spl_token::state::Account::LEN);
+    funds.rent("pc vault", spl_token::state::Account::LEN);
+    funds.rent("lp token account", spl_token::state::Account::LEN);
+}
+
+/// The create pool fee address receives the creation fee of the amm pools.
+pub fn cost_accounts() -> CostAccounts {
+    CostAccounts {
+        fee_receivers: vec![raydium_amm::processor::config_feature::create_pool_fee_address::id()],
+        refundable: Vec::new(),
+    }
+}
@@ -1,7 +1,14 @@
 use anchor_client::ClientError;
 use anyhow::Result;
-use common::{common_types, InstructionDecodeType};
-use raydium_amm::{instruction::*, log::decode_ray_log};
+use common::{common_types, InstructionDecodeType, ReceiptEvent, SwapQuote, SwapTrade};
+pub use raydium_amm::instruction::AmmInstruction;
+use raydium_amm::{
+    error::AmmError,
+    instruction::*,
+    log::{DepositLog, InitLog, SwapBaseInLog, SwapBaseOutLog, WithdrawLog},
+    math::SwapDirection,
+};
+use solana_sdk::{decode_error::DecodeError, pubkey::Pubkey};
 
 pub fn handle_program_instruction(
     instr_data: &str,
@@ -34,13 +41,41 @@ pub fn handle_program_instruction(
         }
     }
 
-    let ix_data: &[u8] = &data[..];
-    // println!("{:?}", disc);
-    let instruction = AmmInstruction::unpack(ix_data)?;
+    let instruction = decode_amm_instruction(&data)?;
     println!("{:#?}", instruction);
     Ok(())
 }
 
+pub fn decode_amm_instruction(data: &[u8]) -> Result<AmmInstruction, ClientError> {
+    Ok(AmmInstruction::unpack(data)?)
+}
+
+#[derive(Debug)]
+pub enum AmmEvent {
+    Init(InitLog),
+    Deposit(DepositLog),
+    Withdraw(WithdrawLog),
+    SwapBaseIn(SwapBaseInLog),
+    SwapBaseOut(SwapBaseOutLog),
+}
+
+/// Decode the base64 data of a `ray_log:` log, returns `None` if the log type is unknown.
+pub fn decode_amm_event(log: &str) -> Result<Option<AmmEvent>, ClientError> {
+    let bytes = anchor_lang::__private::base64::decode(log)
+        .map_err(|e| ClientError::LogParseError(e.to_string()))?;
+    let parse_err = |e: bincode::Error| ClientError::LogParseError(e.to_string());
+    // the first byte is the `LogType`
+    let event = match bytes.first() {
+        Some(0) => AmmEvent::Init(bincode::deserialize(&bytes).map_err(parse_err)?),
+        Some(1) => AmmEvent::Deposit(bincode::deserialize(&bytes).map_err(parse_err)?),
+        Some(2) => AmmEvent::Withdraw(bincode::deserialize(&bytes).map_err(parse_err)?),
+        Some(3) => AmmEvent::SwapBaseIn(bincode::deserialize(&bytes).map_err(parse_err)?),
+        Some(4) => AmmEvent::SwapBaseOut(bincode::deserialize(&bytes).map_err(parse_err)?),
+        _ => return Ok(None),
+    };
+    Ok(Some(event))
+}
+
 pub fn handle_program_event(log_event: &str, with_prefix: bool) -> Result<(), ClientError> {
     // Log emitted from the current program.
     if let Some(log) = if with_prefix {
@@ -51,9 +86,201 @@ pub fn handle_program_event(log_event: &str, with_prefix: bool) -> Result<(), Cl
     } else {
         Some(log_event)
     } {
-        decode_ray_log(log);
-        return Ok(());
+        match decode_amm_event(log.strip_prefix(common_types::RAY_LOG).unwrap_or(log))? {
+            Some(event) => println!("{:#?}", event),
+            None => println!("unknow event: {}", log_event),
+        }
+    }
+    Ok(())
+}
+
+pub fn event_type(event: &AmmEvent) -> &'static str {
+    match event {
+        AmmEvent::Init(_) => "Init",
+        AmmEvent::Deposit(_) => "Deposit",
+        AmmEvent::Withdraw(_) => "Withdraw",
+        AmmEvent::SwapBaseIn(_) => "SwapBaseIn",
+        AmmEvent::SwapBaseOut(_) => "SwapBaseOut",
+    }
+}
+
+/// The ray_log does not contain the pool id, the pool is the `amm` account of the instruction.
+pub fn event_pool_id(_event: &AmmEvent) -> Option<Pubkey> {
+    None
+}
+
+/// The swap and liquidity amounts of the ray_log, the ray_log does not contain the pool id.
+pub fn receipt_event(event: &AmmEvent) -> Option<ReceiptEvent> {
+    match event {
+        AmmEvent::Deposit(log) => Some(ReceiptEvent::Deposit {
+            pool_id: None,
+            amount_0: log.deduct_coin,
+            amount_1: log.deduct_pc,
+        }),
+        AmmEvent::Withdraw(log) => Some(ReceiptEvent::Withdraw {
+            pool_id: None,
+            amount_0: log.out_coin,
+            amount_1: log.out_pc,
+        }),
+        AmmEvent::SwapBaseIn(log) => Some(ReceiptEvent::Swap {
+            pool_id: None,
+            input_amount: log.amount_in,
+            output_amount: log.out_amount,
+        }),
+        AmmEvent::SwapBaseOut(log) => Some(ReceiptEvent::Swap {
+            pool_id: None,
+            input_amount: log.deduct_in,
+            output_amount: log.amount_out,
+        }),
+        AmmEvent::Init(_) => None,
+    }
+}
+
+/// The swap amounts of the swap instruction.
+pub fn swap_quote(instruction: &AmmInstruction) -> Option<SwapQuote> {
+    match instruction {
+        AmmInstruction::SwapBaseIn(swap) => Some(SwapQuote {
+            base_in: true,
+            amount_specified: swap.amount_in,
+            other_amount_threshold: swap.minimum_amount_out,
+        }),
+        AmmInstruction::SwapBaseOut(swap) => Some(SwapQuote {
+            base_in: false,
+            amount_specified: swap.amount_out,
+            other_amount_threshold: swap.max_amount_in,
+        }),
+        _ => None,
+    }
+}
+
+/// The swap of the ray_log, the ray_log does not contain the pool id, so the pool is the `amm`
+/// account of the swap instruction emitted it.
+pub fn swap_trade(event: &AmmEvent, accounts: &[Pubkey]) -> Option<SwapTrade> {
+    let (direction, amount_in, amount_out) = match event {
+        AmmEvent::SwapBaseIn(log) => (log.direction, log.amount_in, log.out_amount),
+        AmmEvent::SwapBaseOut(log) => (log.direction, log.deduct_in, log.amount_out),
+        _ => return None,
+    };
+    let zero_for_one = direction == SwapDirection::Coin2PC as u64;
+    let (amount_0, amount_1) = if zero_for_one {
+        (amount_in, amount_out)
     } else {
-        return Ok(());
+        (amount_out, amount_in)
+    };
+    Some(SwapTrade {
+        pool_id: *accounts.get(1)?,
+        zero_for_one,
+        amount_0,
+        amount_1,
+    })
+}
+
+/// The name of the `AmmError` of the custom program error code.
+pub fn amm_error_name(code: u32) -> Option<String> {
+    <AmmError as DecodeError<AmmError>>::decode_custom_error_to_enum(code)
+        .map(|error| format!("{:?}", error))
+}
+
+/// The account names of the instruction in the order of the account metas.
+/// The amm target orders account is optional for swap, and withdraw of the legacy layout
+/// contains the withdraw queue and temp lp accounts.
+pub fn instruction_account_names(instr_data: &[u8], accounts_len: usize) -> Vec<&'static str> {
+    let instruction = match AmmInstruction::unpack(instr_data) {
+        Ok(instruction) => instruction,
+        Err(_) => return Vec::new(),
+    };
+    match instruction {
+        AmmInstruction::Initialize2(_) => vec![
+            "token_program",
+            "associated_token_program",
+            "system_program",
+            "rent",
+            "amm",
+            "amm_authority",
+            "amm_open_orders",
+            "lp_mint",
+            "coin_mint",
+            "pc_mint",
+            "pool_coin_token_account",
+            "pool_pc_token_account",
+            "amm_target_orders",
+            "amm_config",
+            "create_fee_destination",
+            "market_program",
+            "market",
+            "user_wallet",
+            "user_token_coin",
+            "user_token_pc",
+            "user_token_lp",
+        ],
+        AmmInstruction::Deposit(_) => vec![
+            "token_program",
+            "amm",
+            "amm_authority",
+            "amm_open_orders",
+            "amm_target_orders",
+            "lp_mint",
+            "pool_coin_token_account",
+            "pool_pc_token_account",
+            "market",
+            "user_coin_token_account",
+            "user_pc_token_account",
+            "user_lp_token_account",
+            "user_owner",
+            "market_event_queue",
+        ],
+        AmmInstruction::Withdraw(_) => {
+            let mut names = vec![
+                "token_program",
+                "amm",
+                "amm_authority",
+                "amm_open_orders",
+                "amm_target_orders",
+                "lp_mint",
+                "pool_coin_token_account",
+                "pool_pc_token_account",
+            ];
+            if accounts_len > 20 {
+                names.extend(["pool_withdraw_queue", "pool_temp_lp_token_account"]);
+            }
+            names.extend([
+                "market_program",
+                "market",
+                "market_coin_vault",
+                "market_pc_vault",
+                "market_vault_signer",
+                "user_lp_token_account",
+                "user_coin_token_account",
+                "user_pc_token_account",
+                "user_owner",
+                "market_event_queue",
+                "market_bids",
+                "market_asks",
+            ]);
+            names
+        }
+        AmmInstruction::SwapBaseIn(_) | AmmInstruction::SwapBaseOut(_) => {
+            let mut names = vec!["token_program", "amm", "amm_authority", "amm_open_orders"];
+            if accounts_len > 17 {
+                names.push("amm_target_orders");
+            }
+            names.extend([
+                "pool_coin_token_account",
+                "pool_pc_token_account",
+                "market_program",
+                "market",
+                "market_bids",
+                "market_asks",
+                "market_event_queue",
+                "market_coin_vault",
+                "market_pc_vault",
+                "market_vault_signer",
+                "user_source_token_account",
+                "user_destination_token_account",
+                "user_owner",
+            ]);
+            names
+        }
+        _ => Vec::new(),
     }
 }
@@ -1,27 +1,27 @@
 use crate::{amm_instructions, amm_utils, decode_amm_ix_event, openbook};
-use anyhow::Ok;
 use anyhow::Result;
+use anyhow::{format_err, Ok};
 use clap::Parser;
-use common::{common_types, common_utils, rpc, token};
+use common::{common_types, rpc, token, AmountInput, MintInput, PoolOperation, PoolProgram};
 use raydium_amm::state::Loadable;
 use solana_client::{
     rpc_client::RpcClient,
     rpc_filter::{Memcmp, RpcFilterType},
 };
-use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Signer};
+use solana_sdk::pubkey::Pubkey;
 
-#[derive(Debug, Parser)]
+#[derive(Clone, Debug, Parser)]
 pub enum AmmCommands {
     CreatePool {
         /// The amm associated with openbook market
         #[clap(short, long)]
         market: Pubkey,
-        /// The openbook market's coin_mint
+        /// The openbook market's coin_mint, or its symbol
         #[clap(long)]
-        coin_mint: Pubkey,
-        /// The openbook market's pc_mint
+        coin_mint: MintInput,
+        /// The openbook market's pc_mint, or its symbol
         #[clap(long)]
-        pc_mint: Pubkey,
+        pc_mint: MintInput,
         /// User's token coin.
         /// The token's mint must match with the market's coin_mint.
         #[clap(long)]
@@ -31,13 +31,13 @@ pub enum AmmCommands {
         #[clap(long)]
         user_token_pc: Pubkey,
         /// The amount to init of toke coin.
-        /// Ammount can't be 0.
+        /// Ammount can't be 0, accepts ui units like `1.5`, base units like `1500000raw`, a percentage like `50%` or `max`.
         #[clap(long)]
-        init_coin_amount: u64,
+        init_coin_amount: AmountInput,
         /// The amount to init of toke pc.
-        /// Ammount can't be 0.
+        /// Ammount can't be 0, accepts ui units like `1.5`, base units like `1500000raw`, a percentage like `50%` or `max`.
         #[clap(long)]
-        init_pc_amount: u64,
+        init_pc_amount: AmountInput,
         /// The time of the pool is allowed to swap.
         /// If time is less than or equal to the on-chain timestamp, it will be set to swap immediately.
         #[arg(short, long, default_value_t = 0)]
@@ -49,10 +49,12 @@ pub enum AmmCommands {
         pool_id: Pubkey,
         /// The specified token coin of the user deposit.
         /// If none is given, the account will be ATA account.
+        /// For the native mint, a temporary wsol account is used and closed after.
         #[clap(long)]
         deposit_token_coin: Option<Pubkey>,
         /// The specified token pc of the user deposit.
         /// If none is given, the account will be ATA account.
+        /// For the native mint, a temporary wsol account is used and closed after.
         #[clap(long)]
         deposit_token_pc: Option<Pubkey>,
         /// The specified lp token of the user will receive.
@@ -60,7 +62,8 @@ pub enum AmmCommands {
         #[clap(long)]
         recipient_token_lp: Option<Pubkey>,
         /// The amount of the specified token to deposit.
-        amount_specified: u64,
+        /// Accepts ui units like `1.5`, base units like `1500000raw`, a percentage of the balance like `50%` or `max`.
+        amount_specified: AmountInput,
         /// The amount of the other side to be calculated may be less than expected due to price fluctuations.
         /// It's necessary to add an optional parameter to limit the minimum amount of the other side.
         #[clap(short, long, action)]
@@ -79,15 +82,18 @@ pub enum AmmCommands {
         withdraw_token_lp: Option<Pubkey>,
         /// The specified token coin of the user will receive.
         /// If none is given, the account will be ATA account.
+        /// For the native mint, a temporary wsol account is used and closed after.
         #[clap(long)]
         recipient_token_coin: Option<Pubkey>,
         /// The specified token pc of the user will receive.
         /// If none is given, the account will be ATA account.
+        /// For the native mint, a temporary wsol account is used and closed after.
         #[clap(long)]
         recipient_token_pc: Option<Pubkey>,
         /// The amount of liquidity to withdraw.
+        /// Accepts ui units like `1.5`, base units like `1500000raw`, a percentage of the lp balance like `50%` or `max`.
         #[clap(short, long)]
-        input_lp_amount: u64,
+        input_lp_amount: AmountInput,
         /// The amount of both tokens to be calculated though `input_lp_amount` may be less than expected due to price fluctuations.
         /// It's necessary to add an optional parameter to limit the minimum amount of the tokens.
         #[clap(short, long, action)]
@@ -98,15 +104,20 @@ pub enum AmmCommands {
         #[clap(short, long)]
         pool_id: Pubkey,
         /// The token of user want to swap to.
+        /// Pass the native mint to pay with sol, which is wrapped into a temporary wsol account.
+        /// A token symbol like `SOL` or `USDC` stands for the sol of the wallet or the ATA account.
         #[clap(long)]
-        user_input_token: Pubkey,
+        user_input_token: MintInput,
         /// The token of user want to swap to.
         /// If none is given, the account will be ATA account.
+        /// For the native mint, a temporary wsol account is used and closed after.
+        /// A token symbol like `USDC` is the same as none given.
         #[clap(long)]
-        user_output_token: Option<Pubkey>,
+        user_output_token: Option<MintInput>,
         /// The amount specified of user want to swap from or to token
+        /// Accepts ui units like `1.5`, base units like `1500000raw`, and for the input a percentage like `50%` or `max`.
         #[clap(short, long)]
-        amount_specified: u64,
+        amount_specified: AmountInput,
         /// The amount specified is output_token or not.
         #[clap(short, long, action)]
         base_out: bool,
@@ -116,12 +127,15 @@ pub enum AmmCommands {
         /// If the pool_id is specified, coin_mint and pc_mint will be ignored.
         #[clap(long)]
         pool_id: Option<Pubkey>,
-        /// Fetch pools by specified coin_mint.
+        /// Fetch pools by specified coin_mint, or its symbol.
         #[clap(long)]
-        coin_mint: Option<Pubkey>,
-        /// Fetch pools by specified pc_mint.
+        coin_mint: Option<MintInput>,
+        /// Fetch pools by specified pc_mint, or its symbol.
         #[clap(long)]
-        pc_mint: Option<Pubkey>,
+        pc_mint: Option<MintInput>,
+        /// Print the raw pool state instead of the summary of the specified pool.
+        #[clap(long, action)]
+        raw: bool,
     },
     DecodeIx {
         // Instruction hex data
@@ -141,10 +155,9 @@ pub enum AmmCommands {
 pub fn process_amm_commands(
     command: AmmCommands,
     config: &common_types::CommonConfig,
-) -> Result<Option<Vec<Instruction>>> {
+) -> Result<Option<common_types::CommandInstructions>> {
     let rpc_client = RpcClient::new(config.cluster().url());
-    let wallet_keypair = common_utils::read_keypair_file(&config.wallet())?;
-    let payer_pubkey = wallet_keypair.pubkey();
+    let payer_pubkey = config.wallet_pubkey()?;
 
     match command {
         AmmCommands::CreatePool {
@@ -157,6 +170,8 @@ pub fn process_amm_commands(
             init_pc_amount,
             open_time,
         } => {
+            let coin_mint = coin_mint.resolve(config)?;
+            let pc_mint = pc_mint.resolve(config)?;
             let market_keys =
                 openbook::get_keys_for_market(&rpc_client, &config.openbook_program(), &market)
                     .unwrap();
@@ -169,22 +184,45 @@ pub fn process_amm_commands(
                 &coin_mint,
                 &pc_mint,
             )?;
+            let init_coin_amount = common::resolve_token_amount(
+                &rpc_client,
+                &init_coin_amount,
+                &payer_pubkey,
+                &coin_mint,
+                Some(&user_token_coin),
+            )?;
+            let init_pc_amount = common::resolve_token_amount(
+                &rpc_client,
+                &init_pc_amount,
+                &payer_pubkey,
+                &pc_mint,
+                Some(&user_token_pc),
+            )?;
+            let mut funds = common::FundsCheck::default();
+            funds.debit(Some(user_token_coin), coin_mint, init_coin_amount);
+            funds.debit(Some(user_token_pc), pc_mint, init_pc_amount);
+            funds.spend(
+                "pool creation fee",
+                amm_utils::get_create_pool_fee(&rpc_client, &config.amm_program())?,
+            );
+            amm_utils::create_pool_rent(&mut funds);
+            common::check_user_funds(&rpc_client, &payer_pubkey, &funds)?;
             let instruction = amm_instructions::initialize_amm_pool(
                 &config.amm_program(),
                 &amm_keys,
                 &raydium_amm::processor::config_feature::create_pool_fee_address::id(),
-                &wallet_keypair.pubkey(),
+                &payer_pubkey,
                 &user_token_coin,
                 &user_token_pc,
                 &spl_associated_token_account::get_associated_token_address(
-                    &wallet_keypair.pubkey(),
+                    &payer_pubkey,
                     &amm_keys.amm_lp_mint,
                 ),
                 open_time,
                 init_pc_amount,
                 init_coin_amount,
             )?;
-            return Ok(Some(vec![instruction]));
+            return Ok(Some(vec![instruction].into()));
         }
         AmmCommands::Deposit {
             pool_id,
@@ -195,10 +233,32 @@ pub fn process_amm_commands(
             another_min_limit,
             base_coin,
         } => {
+            amm_utils::check_pool_operation(&rpc_client, &pool_id, PoolOperation::Deposit)?;
             let base_side = if base_coin { 0 } else { 1 };
+            let amm_keys = amm_utils::get_amm_keys(&rpc_client, config, &pool_id)?;
+            let pool_mints = common::get_indexed_pool_mints(config, &pool_id, || {
+                amm_utils::get_pool_mints(&rpc_client, &pool_id)
+            })?;
+            let amount_specified = if base_coin {
+                common::resolve_token_amount(
+                    &rpc_client,
+                    &amount_specified,
+                    &payer_pubkey,
+                    &pool_mints.mint_0,
+                    deposit_token_coin.as_ref(),
+                )?
+            } else {
+                common::resolve_token_amount(
+                    &rpc_client,
+                    &amount_specified,
+                    &payer_pubkey,
+                    &pool_mints.mint_1,
+                    deposit_token_pc.as_ref(),
+                )?
+            };
             let result = amm_utils::calculate_deposit_info(
                 &rpc_client,
-                config.amm_program(),
+                &amm_keys,
                 pool_id,
                 amount_specified,
                 another_min_limit,
@@ -206,8 +266,32 @@ pub fn process_amm_commands(
                 base_side,
             )
             .unwrap();
+            println!(
+                "ui max_coin_amount:{}, ui max_pc_amount:{}",
+                pool_mints.ui_amount(&pool_mints.mint_0, result.max_coin_amount),
+                pool_mints.ui_amount(&pool_mints.mint_1, result.max_pc_amount)
+            );
+            let mut funds = common::FundsCheck::default();
+            funds.debit(
+                deposit_token_coin,
+                result.amm_coin_mint,
+                result.max_coin_amount,
+            );
+            funds.debit(deposit_token_pc, result.amm_pc_mint, result.max_pc_amount);
+            funds.credit(recipient_token_lp, result.amm_lp_mint);
+            common::check_user_funds(&rpc_client, &payer_pubkey, &funds)?;
+            let mut instructions = Vec::new();
+            // the sol is wrapped into a temporary wsol account, which is closed at the end
+            let mut close_wsol_instructions = Vec::new();
             let deposit_token_coin = if let Some(deposit_token_coin) = deposit_token_coin {
                 deposit_token_coin
+            } else if result.amm_coin_mint == spl_token::native_mint::id() {
+                token::wrap_sol_temp_account(
+                    &payer_pubkey,
+                    result.max_coin_amount,
+                    &mut instructions,
+                    &mut close_wsol_instructions,
+                )
             } else {
                 spl_associated_token_account::get_associated_token_address(
                     &payer_pubkey,
@@ -216,6 +300,13 @@ pub fn process_amm_commands(
             };
             let deposit_token_pc = if let Some(deposit_token_pc) = deposit_token_pc {
                 deposit_token_pc
+            } else if result.amm_pc_mint == spl_token::native_mint::id() {
+                token::wrap_sol_temp_account(
+                    &payer_pubkey,
+                    result.max_pc_amount,
+                    &mut instructions,
+                    &mut close_wsol_instructions,
+                )
             } else {
                 spl_associated_token_account::get_associated_token_address(
                     &payer_pubkey,
@@ -223,7 +314,6 @@ pub fn process_amm_commands(
                 )
             };
 
-            let mut instructions = Vec::new();
             let recipient_token_lp = if let Some(recipient_token_lp) = recipient_token_lp {
                 recipient_token_lp
             } else {
@@ -256,15 +346,16 @@ pub fn process_amm_commands(
                 &deposit_token_coin,
                 &deposit_token_pc,
                 &recipient_token_lp,
-                &wallet_keypair.pubkey(),
+                &payer_pubkey,
                 result.max_coin_amount,
                 result.max_pc_amount,
                 base_side,
                 result.another_min_amount,
             )?;
             instructions.extend(vec![instruction]);
+            instructions.extend(close_wsol_instructions);
 
-            return Ok(Some(instructions));
+            return Ok(Some(instructions.into()));
         }
         AmmCommands::Withdraw {
             pool_id,
@@ -274,9 +365,18 @@ pub fn process_amm_commands(
             input_lp_amount,
             slippage_limit,
         } => {
+            amm_utils::check_pool_operation(&rpc_client, &pool_id, PoolOperation::Withdraw)?;
+            let amm_keys = amm_utils::get_amm_keys(&rpc_client, config, &pool_id)?;
+            let input_lp_amount = common::resolve_token_amount(
+                &rpc_client,
+                &input_lp_amount,
+                &payer_pubkey,
+                &amm_keys.amm_lp_mint,
+                withdraw_token_lp.as_ref(),
+            )?;
             let result = amm_utils::calculate_withdraw_info(
                 &rpc_client,
-                config.amm_program(),
+                &amm_keys,
                 pool_id,
                 input_lp_amount,
                 if slippage_limit {
@@ -295,8 +395,17 @@ pub fn process_amm_commands(
                 )
             };
             let mut instructions = Vec::new();
+            // the sol is received by a temporary wsol account, which is closed at the end
+            let mut close_wsol_instructions = Vec::new();
             let recipient_token_coin = if let Some(recipient_token_coin) = recipient_token_coin {
                 recipient_token_coin
+            } else if result.amm_coin_mint == spl_token::native_mint::id() {
+                token::wrap_sol_temp_account(
+                    &payer_pubkey,
+                    0,
+                    &mut instructions,
+                    &mut close_wsol_instructions,
+                )
             } else {
                 // create ata token coin or not
                 let create_user_token_coin_instr = token::create_ata_token_or_not(
@@ -314,6 +423,13 @@ pub fn process_amm_commands(
             };
             let recipient_token_pc = if let Some(recipient_token_pc) = recipient_token_pc {
                 recipient_token_pc
+            } else if result.amm_pc_mint == spl_token::native_mint::id() {
+                token::wrap_sol_temp_account(
+                    &payer_pubkey,
+                    0,
+                    &mut instructions,
+                    &mut close_wsol_instructions,
+                )
             } else {
                 // create ata token pc or not
                 let create_user_token_pc_instr = token::create_ata_token_or_not(
@@ -346,7 +462,7 @@ pub fn process_amm_commands(
                 &withdraw_token_lp,
                 &recipient_token_coin,
                 &recipient_token_pc,
-                &wallet_keypair.pubkey(),
+                &payer_pubkey,
                 &result.market_event_queue,
                 &result.market_bids,
                 &result.market_asks,
@@ -356,8 +472,9 @@ pub fn process_amm_commands(
                 result.receive_min_pc_amount,
             )?;
             instructions.extend(vec![instruction]);
+            instructions.extend(close_wsol_instructions);
 
-            return Ok(Some(instructions));
+            return Ok(Some(instructions.into()));
         }
         AmmCommands::Swap {
             pool_id,
@@ -366,19 +483,126 @@ pub fn process_amm_commands(
             amount_specified,
             base_out,
         } => {
+            amm_utils::check_pool_operation(&rpc_client, &pool_id, PoolOperation::Swap)?;
             let base_in = !base_out;
+            if let Some(max_deviation_bps) = config.oracle_max_deviation_bps() {
+                let (price, reference_price) = match config.oracle_source() {
+                    common_types::OracleSource::Twap => {
+                        return Err(format_err!(
+                            "the amm pool has no observations, use a pyth or file oracle"
+                        ));
+                    }
+                    source => (
+                        amm_utils::get_pool_price(&rpc_client, &pool_id)?,
+                        common::get_reference_price(&rpc_client, &source)?,
+                    ),
+                };
+                common::check_price_deviation(&pool_id, price, reference_price, max_deviation_bps)?;
+            }
+            let slippage = config.swap_slippage(|| {
+                amm_utils::get_price_movement_bps(
+                    &rpc_client,
+                    &config.amm_program(),
+                    &pool_id,
+                    common_types::AUTO_SLIPPAGE_WINDOW,
+                )
+            });
+            let user_input_token = common::resolve_user_input_token(
+                &rpc_client,
+                config,
+                &user_input_token,
+                &payer_pubkey,
+            )?;
+            let amm_keys = amm_utils::get_amm_keys(&rpc_client, config, &pool_id)?;
+            let pool_mints = common::get_indexed_pool_mints(config, &pool_id, || {
+                amm_utils::get_pool_mints(&rpc_client, &pool_id)
+            })?;
+            let user_output_token =
+                common::resolve_user_output_token(config, user_output_token.as_ref(), &pool_mints)?;
+            let amount_specified = common::resolve_swap_amount(
+                &rpc_client,
+                &amount_specified,
+                &payer_pubkey,
+                &user_input_token,
+                &pool_mints,
+                base_out,
+            )?;
             let result = amm_utils::calculate_swap_info(
                 &rpc_client,
-                config.amm_program(),
+                &amm_keys,
                 pool_id,
                 user_input_token,
                 amount_specified,
-                config.slippage(),
+                slippage,
                 base_in,
             )?;
+            let symbols = common::get_token_symbols(
+                &rpc_client,
+                config,
+                &[result.input_mint, result.output_mint],
+            )?;
+            println!(
+                "swap {} to {}",
+                common::mint_label(&symbols, &result.input_mint),
+                common::mint_label(&symbols, &result.output_mint)
+            );
+            println!(
+                "amount_specified:{}, other_amount_threshold:{}, price_impact_bps:{}, slippage_bps:{}, auto_slippage:{}",
+                result.amount_specified,
+                result.other_amount_threshold,
+                result.price_impact_bps,
+                result.slippage_bps,
+                config.auto_slippage()
+            );
+            let (specified_mint, other_mint) = if base_in {
+                (result.input_mint, result.output_mint)
+            } else {
+                (result.output_mint, result.input_mint)
+            };
+            println!(
+                "ui amount_specified:{}, ui other_amount_threshold:{}",
+                pool_mints.ui_amount(&specified_mint, result.amount_specified),
+                pool_mints.ui_amount(&other_mint, result.other_amount_threshold)
+            );
+            let mut funds = common::FundsCheck::default();
+            funds.debit(
+                Some(user_input_token),
+                result.input_mint,
+                if base_in {
+                    result.amount_specified
+                } else {
+                    result.other_amount_threshold
+                },
+            );
+            funds.credit(user_output_token, result.output_mint);
+            common::check_user_funds(&rpc_client, &payer_pubkey, &funds)?;
             let mut instructions = Vec::new();
+            // the sol is wrapped into a temporary wsol account, which is closed at the end
+            let mut close_wsol_instructions = Vec::new();
+            let user_input_token = if user_input_token == spl_token::native_mint::id() {
+                let amount = if base_in {
+                    result.amount_specified
+                } else {
+                    result.other_amount_threshold
+                };
+                token::wrap_sol_temp_account(
+                    &payer_pubkey,
+                    amount,
+                    &mut instructions,
+                    &mut close_wsol_instructions,
+                )
+            } else {
+                user_input_token
+            };
             let user_output_token = if let Some(user_output_token) = user_output_token {
                 user_output_token
+            } else if result.output_mint == spl_token::native_mint::id() {
+                token::wrap_sol_temp_account(
+                    &payer_pubkey,
+                    0,
+                    &mut instructions,
+                    &mut close_wsol_instructions,
+                )
             } else {
                 // create output token or not
                 let create_user_output_token_instr = token::create_ata_token_or_not(
@@ -413,7 +637,7 @@ pub fn process_amm_commands(
                     &result.market_vault_signer,
                     &user_input_token,
                     &user_output_token,
-                    &wallet_keypair.pubkey(),
+                    &payer_pubkey,
                     result.amount_specified,
                     result.other_amount_threshold,
                 )?
@@ -435,29 +659,62 @@ pub fn process_amm_commands(
                     &result.market_vault_signer,
                     &user_input_token,
                     &user_output_token,
-                    &wallet_keypair.pubkey(),
+                    &payer_pubkey,
                     result.other_amount_threshold,
                     result.amount_specified,
                 )?
             };
             instructions.extend(vec![swap_instruction]);
-            return Ok(Some(instructions));
+            instructions.extend(close_wsol_instructions);
+            return Ok(Some(common_types::CommandInstructions {
+                instructions,
+                // the receipt recovers the quote with the applied slippage
+                slippage_bps: Some(result.slippage_bps),
+            }));
         }
         AmmCommands::FetchPool {
             pool_id,
             coin_mint,
             pc_mint,
+            raw,
         } => {
+            let coin_mint = coin_mint.map(|mint| mint.resolve(config)).transpose()?;
+            let pc_mint = pc_mint.map(|mint| mint.resolve(config)).transpose()?;
             if pool_id.is_some() {
+                if !raw {
+                    // summarize specified pool
+                    let summary = amm_utils::get_pool_summary(&rpc_client, &pool_id.unwrap())?;
+                    let mints = summary.snapshot.mints;
+                    let symbols = common::get_token_symbols(
+                        &rpc_client,
+                        config,
+                        &[mints.mint_0, mints.mint_1],
+                    )?;
+                    common::print_pool_summary(&summary, &symbols);
+                    return Ok(None);
+                }
                 // fetch specified pool
                 let amm_data = rpc::get_account(&rpc_client, &pool_id.unwrap())
                     .unwrap()
                     .unwrap();
                 let pool_state = raydium_amm::state::AmmInfo::load_from_bytes(&amm_data).unwrap();
                 println!("{:#?}", pool_state);
+                let symbols = common::get_token_symbols(
+                    &rpc_client,
+                    config,
+                    &[pool_state.coin_vault_mint, pool_state.pc_vault_mint],
+                )?;
+                println!(
+                    "coin_mint:{}, pc_mint:{}",
+                    common::mint_label(&symbols, &pool_state.coin_vault_mint),
+                    common::mint_label(&symbols, &pool_state.pc_vault_mint)
+                );
             } else {
-                // fetch pool by filters
+                // fetch pool by filters, or from the pool index in either order of the mints
                 let pool_len = core::mem::size_of::<raydium_amm::state::AmmInfo>() as u64;
+                let pool_index = config
+                    .pool_index()
+                    .filter(|_| coin_mint.is_some() || pc_mint.is_some());
                 let filters = match (coin_mint, pc_mint) {
                     (None, None) => Some(vec![RpcFilterType::DataSize(pool_len)]),
                     (Some(coin_mint), None) => Some(vec![
@@ -480,18 +737,46 @@ pub fn process_amm_commands(
                         RpcFilterType::DataSize(pool_len),
                     ]),
                 };
-                let pools = rpc::get_program_accounts_with_filters(
-                    &rpc_client,
-                    config.amm_program(),
-                    filters,
-                )
-                .unwrap();
+                let pools = if let Some(path) = pool_index {
+                    common::fetch_indexed_pools(
+                        &rpc_client,
+                        config,
+                        &path,
+                        PoolProgram::Amm,
+                        coin_mint.as_ref(),
+                        pc_mint.as_ref(),
+                        |index| amm_utils::refresh_pool_index(&rpc_client, config, &path, index),
+                    )?
+                } else {
+                    rpc::get_program_accounts_with_filters(
+                        &rpc_client,
+                        config.amm_program(),
+                        filters,
+                    )
+                    .unwrap()
+                };
+                // the symbols of all the pools are fetched in batches
+                let mut mints = Vec::new();
+                for pool in pools.iter() {
+                    if let Some(pool_state) =
+                        raydium_amm::state::AmmInfo::load_from_bytes(&pool.1.data).ok()
+                    {
+                        mints.push(pool_state.coin_vault_mint);
+                        mints.push(pool_state.pc_vault_mint);
+                    }
+                }
+                let symbols = common::get_token_symbols(&rpc_client, config, &mints)?;
                 for pool in pools {
                     println!("pool_id:{}", pool.0);
-                    println!(
-                        "{:#?}",
-                        raydium_amm::state::AmmInfo::load_from_bytes(&pool.1.data)
-                    );
+                    let pool_state = raydium_amm::state::AmmInfo::load_from_bytes(&pool.1.data);
+                    println!("{:#?}", pool_state);
+                    if let Some(pool_state) = pool_state.ok() {
+                        println!(
+                            "coin_mint:{}, pc_mint:{}",
+                            common::mint_label(&symbols, &pool_state.coin_vault_mint),
+                            common::mint_label(&symbols, &pool_state.pc_vault_mint)
+                        );
+                    }
                 }
             }
             return Ok(None);
@@ -508,7 +793,7 @@ pub fn process_amm_commands(
             return Ok(None);
         }
         AmmCommands::SimulateInfo { pool_id } => {
-            let amm_keys = amm_utils::load_amm_keys(&rpc_client, &config.amm_program(), &pool_id)?;
+            let amm_keys = amm_utils::get_amm_keys(&rpc_client, config, &pool_id)?;
             let market_state = openbook::get_keys_for_market(
                 &rpc_client,
                 &amm_keys.market_program,
@@ -528,7 +813,7 @@ pub fn process_amm_commands(
                 &market_state.event_q,
                 None,
             )?;
-            return Ok(Some(vec![simulate_instr]));
+            return Ok(Some(vec![simulate_instr].into()));
         }
     }
 }
@@ -1,7 +1,7 @@
 use anchor_client::Client;
 use anchor_lang::prelude::AccountMeta;
 use anchor_spl::{memo::ID as MEMO_ID, metadata::mpl_token_metadata::ID as MPL_ID};
-use anyhow::{format_err, Result};
+use anyhow::Result;
 use common::common_types::CommonConfig;
 use raydium_amm_v3::{
     accounts as raydium_clmm_accounts, instruction as raydium_clmm_instruction,
@@ -10,7 +10,10 @@ use raydium_amm_v3::{
         TICK_ARRAY_SEED,
     },
 };
-use solana_sdk::{instruction::Instruction, pubkey::Pubkey, system_program, sysvar};
+use solana_sdk::{
+    instruction::Instruction, pubkey::Pubkey, signer::null_signer::NullSigner, system_program,
+    sysvar,
+};
 use std::rc::Rc;
 const MPL_PREFIX: &str = "metadata";
 
@@ -24,8 +27,7 @@ pub fn create_pool_instr(
     sqrt_price_x64: u128,
     open_time: u64,
 ) -> Result<Vec<Instruction>> {
-    let wallet = solana_sdk::signature::read_keypair_file(config.wallet())
-        .map_err(|_| format_err!("failed to read keypair from {}", config.wallet()))?;
+    let wallet = NullSigner::new(&config.wallet_pubkey()?);
     let cluster = config.cluster();
     // Client.
     let client = Client::new(cluster, Rc::new(wallet));
@@ -116,8 +118,7 @@ pub fn open_position_instr(
     tick_array_upper_start_index: i32,
     with_metadata: bool,
 ) -> Result<Vec<Instruction>> {
-    let wallet = solana_sdk::signature::read_keypair_file(config.wallet())
-        .map_err(|_| format_err!("failed to read keypair from {}", config.wallet()))?;
+    let wallet = NullSigner::new(&config.wallet_pubkey()?);
     let cluster = config.cluster();
     // Client.
     let client = Client::new(cluster, Rc::new(wallet));
@@ -225,8 +226,7 @@ pub fn open_position_with_token22_nft_instr(
     tick_array_upper_start_index: i32,
     with_metadata: bool,
 ) -> Result<Vec<Instruction>> {
-    let wallet = solana_sdk::signature::read_keypair_file(config.wallet())
-        .map_err(|_| format_err!("failed to read keypair from {}", config.wallet()))?;
+    let wallet = NullSigner::new(&config.wallet_pubkey()?);
     let cluster = config.cluster();
     // Client.
     let client = Client::new(cluster, Rc::new(wallet));
@@ -326,8 +326,7 @@ pub fn increase_liquidity_instr(
     tick_array_lower_start_index: i32,
     tick_array_upper_start_index: i32,
 ) -> Result<Vec<Instruction>> {
-    let wallet = solana_sdk::signature::read_keypair_file(config.wallet())
-        .map_err(|_| format_err!("failed to read keypair from {}", config.wallet()))?;
+    let wallet = NullSigner::new(&config.wallet_pubkey()?);
     let cluster = config.cluster();
     // Client.
     let client = Client::new(cluster, Rc::new(wallet));
@@ -414,8 +413,7 @@ pub fn decrease_liquidity_instr(
     tick_array_lower_start_index: i32,
     tick_array_upper_start_index: i32,
 ) -> Result<Vec<Instruction>> {
-    let wallet = solana_sdk::signature::read_keypair_file(config.wallet())
-        .map_err(|_| format_err!("failed to read keypair from {}", config.wallet()))?;
+    let wallet = NullSigner::new(&config.wallet_pubkey()?);
     let cluster = config.cluster();
     // Client.
     let client = Client::new(cluster, Rc::new(wallet));
@@ -486,8 +484,7 @@ pub fn close_personal_position_instr(
     config: &CommonConfig,
     nft_mint_key: Pubkey,
 ) -> Result<Vec<Instruction>> {
-    let wallet = solana_sdk::signature::read_keypair_file(config.wallet())
-        .map_err(|_| format_err!("failed to read keypair from {}", config.wallet()))?;
+    let wallet = NullSigner::new(&config.wallet_pubkey()?);
     let cluster = config.cluster();
     // Client.
     let client = Client::new(cluster, Rc::new(wallet));
@@ -530,8 +527,7 @@ pub fn swap_instr(
     sqrt_price_limit_x64: Option<u128>,
     is_base_input: bool,
 ) -> Result<Vec<Instruction>> {
-    let wallet = solana_sdk::signature::read_keypair_file(config.wallet())
-        .map_err(|_| format_err!("failed to read keypair from {}", config.wallet()))?;
+    let wallet = NullSigner::new(&config.wallet_pubkey()?);
     let cluster = config.cluster();
     // Client.
     let client = Client::new(cluster, Rc::new(wallet));
@@ -579,8 +575,7 @@ pub fn swap_v2_instr(
     sqrt_price_limit_x64: Option<u128>,
     is_base_input: bool,
 ) -> Result<Vec<Instruction>> {
-    let wallet = solana_sdk::signature::read_keypair_file(config.wallet())
-        .map_err(|_| format_err!("failed to read keypair from {}", config.wallet()))?;
+    let wallet = NullSigner::new(&config.wallet_pubkey()?);
     let cluster = config.cluster();
     // Client.
     let client = Client::new(cluster, Rc::new(wallet));
@@ -627,8 +622,7 @@ pub fn initialize_reward_instr(
     end_time: u64,
     emissions_per_second_x64: u128,
 ) -> Result<Vec<Instruction>> {
-    let wallet = solana_sdk::signature::read_keypair_file(config.wallet())
-        .map_err(|_| format_err!("failed to read keypair from {}", config.wallet()))?;
+    let wallet = NullSigner::new(&config.wallet_pubkey()?);
     let cluster = config.cluster();
     // Client.
     let client = Client::new(cluster, Rc::new(wallet));
@@ -671,8 +665,7 @@ pub fn set_reward_params_instr(
     end_time: u64,
     emissions_per_second_x64: u128,
 ) -> Result<Vec<Instruction>> {
-    let wallet = solana_sdk::signature::read_keypair_file(config.wallet())
-        .map_err(|_| format_err!("failed to read keypair from {}", config.wallet()))?;
+    let wallet = NullSigner::new(&config.wallet_pubkey()?);
     let cluster = config.cluster();
     // Client.
     let client = Client::new(cluster, Rc::new(wallet));
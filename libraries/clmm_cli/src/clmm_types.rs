@@ -54,6 +54,10 @@ pub struct ClmmSwapChangeResult {
     pub other_amount_threshold: u64,
     pub sqrt_price_limit_x64: Option<u128>,
     pub is_base_input: bool,
+    /// The price impact of the quote versus the spot price, the trade fee included.
+    pub price_impact_bps: u64,
+    /// The slippage applied to the other amount threshold.
+    pub slippage_bps: u64,
 }
 
 // the top level state of the swap, the results of which are recorded in storage at the end
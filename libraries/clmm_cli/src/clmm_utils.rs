@@ -5,17 +5,29 @@ use crate::{
         StepComputations, SwapState,
     },
 };
-use anyhow::Result;
+use anchor_lang::Discriminator;
+use anyhow::{format_err, Result};
 use arrayref::array_ref;
-use common::{common_types::TokenInfo, common_utils, rpc};
+use common::{
+    common_types::{Slippage, TokenInfo},
+    common_utils, get_watched_account, rpc, CommonConfig, CostAccounts, FundsCheck, IndexedPool,
+    PoolIndex, PoolMints, PoolOperation, PoolProgram, PoolSnapshot, PoolStatus, PoolSummary,
+    PoolTwap,
+};
 use raydium_amm_v3::libraries::{liquidity_math, tick_math};
 use solana_client::rpc_client::RpcClient;
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{account::Account, program_pack::Pack, pubkey::Pubkey};
 use std::{
-    collections::VecDeque,
+    collections::{HashMap, VecDeque},
     ops::{DerefMut, Neg},
 };
 
+/// The max size of a metaplex metadata account.
+const METADATA_ACCOUNT_LEN: usize = 679;
+/// A bound of the size of a Token-2022 position nft mint, with the close authority, the metadata
+/// pointer and the metadata of the position.
+const TOKEN_2022_NFT_MINT_LEN: usize = 512;
+
 pub fn create_pool_price(
     rpc_client: &RpcClient,
     mint0: Pubkey,
@@ -59,6 +71,7 @@ pub fn calculate_liquidity_change(
     tick_lower_price: f64,
     tick_upper_price: f64,
     input_amount: u64,
+    liquidity: Option<u128>,
     slippage_bps: u64,
     collect_reward: bool,
     is_base_0: bool,
@@ -83,37 +96,33 @@ pub fn calculate_liquidity_change(
         }
     }
     let mut rsps = rpc_client.get_multiple_accounts(&load_pubkeys).unwrap();
-    let mint0_token_program = rsps.remove(0).unwrap().owner;
-    let mint1_token_program = rsps.remove(0).unwrap().owner;
+    let mint0_account = rsps.remove(0).unwrap();
+    let mint1_account = rsps.remove(0).unwrap();
+    common_utils::check_transfer_hook(
+        &pool.token_mint_0,
+        &common_utils::unpack_mint(&mint0_account.data)?,
+    )?;
+    common_utils::check_transfer_hook(
+        &pool.token_mint_1,
+        &common_utils::unpack_mint(&mint1_account.data)?,
+    )?;
+    let mint0_token_program = mint0_account.owner;
+    let mint1_token_program = mint1_account.owner;
     for (item, rsp) in reward_items.iter_mut().zip(rsps.iter()) {
         item.token_program = rsp.as_ref().unwrap().owner;
     }
 
-    let tick_lower_price_x64 = clmm_math::price_to_sqrt_price_x64(
-        tick_lower_price,
-        pool.mint_decimals_0,
-        pool.mint_decimals_1,
-    );
-    let tick_upper_price_x64 = clmm_math::price_to_sqrt_price_x64(
-        tick_upper_price,
-        pool.mint_decimals_0,
-        pool.mint_decimals_1,
-    );
-    let tick_lower_index = clmm_math::tick_with_spacing(
-        tick_math::get_tick_at_sqrt_price(tick_lower_price_x64)?,
-        pool.tick_spacing.into(),
-    );
-    let tick_upper_index = clmm_math::tick_with_spacing(
-        tick_math::get_tick_at_sqrt_price(tick_upper_price_x64)?,
-        pool.tick_spacing.into(),
-    );
+    let (tick_lower_index, tick_upper_index) =
+        get_tick_index_range(&pool, tick_lower_price, tick_upper_price)?;
     println!(
         "tick_lower_index:{}, tick_upper_index:{}",
         tick_lower_index, tick_upper_index
     );
     let tick_lower_price_x64 = tick_math::get_sqrt_price_at_tick(tick_lower_index)?;
     let tick_upper_price_x64 = tick_math::get_sqrt_price_at_tick(tick_upper_index)?;
-    let liquidity = if is_base_0 {
+    let liquidity = if let Some(liquidity) = liquidity {
+        liquidity
+    } else if is_base_0 {
         liquidity_math::get_liquidity_from_single_amount_0(
             pool.sqrt_price_x64,
             tick_lower_price_x64,
@@ -189,6 +198,68 @@ pub fn calculate_liquidity_change(
     })
 }
 
+/// The tick indexes of the price range, aligned to the tick spacing of the pool.
+pub fn get_tick_index_range(
+    pool: &raydium_amm_v3::states::PoolState,
+    tick_lower_price: f64,
+    tick_upper_price: f64,
+) -> Result<(i32, i32)> {
+    let tick_lower_price_x64 = clmm_math::price_to_sqrt_price_x64(
+        tick_lower_price,
+        pool.mint_decimals_0,
+        pool.mint_decimals_1,
+    );
+    let tick_upper_price_x64 = clmm_math::price_to_sqrt_price_x64(
+        tick_upper_price,
+        pool.mint_decimals_0,
+        pool.mint_decimals_1,
+    );
+    let tick_lower_index = clmm_math::tick_with_spacing(
+        tick_math::get_tick_at_sqrt_price(tick_lower_price_x64)?,
+        pool.tick_spacing.into(),
+    );
+    let tick_upper_index = clmm_math::tick_with_spacing(
+        tick_math::get_tick_at_sqrt_price(tick_upper_price_x64)?,
+        pool.tick_spacing.into(),
+    );
+    Ok((tick_lower_index, tick_upper_index))
+}
+
+/// The liquidity of the position of the owner in the price range of the pool.
+pub fn get_position_liquidity(
+    rpc_client: &RpcClient,
+    raydium_v3_program: &Pubkey,
+    owner: &Pubkey,
+    pool_id: &Pubkey,
+    tick_lower_price: f64,
+    tick_upper_price: f64,
+) -> Result<u128> {
+    let pool =
+        rpc::get_anchor_account::<raydium_amm_v3::states::PoolState>(rpc_client, pool_id)?.unwrap();
+    let (tick_lower_index, tick_upper_index) =
+        get_tick_index_range(&pool, tick_lower_price, tick_upper_price)?;
+    let (_nft_tokens, positions) =
+        get_nft_accounts_and_positions_by_owner(rpc_client, owner, raydium_v3_program);
+    let rsps = rpc_client.get_multiple_accounts(&positions)?;
+    for rsp in rsps.into_iter().flatten() {
+        let position = common_utils::deserialize_anchor_account::<
+            raydium_amm_v3::states::PersonalPositionState,
+        >(&rsp)?;
+        if position.pool_id == *pool_id
+            && position.tick_lower_index == tick_lower_index
+            && position.tick_upper_index == tick_upper_index
+        {
+            return Ok(position.liquidity);
+        }
+    }
+    Err(format_err!(
+        "personal position of pool {} in ticks [{}, {}] not exist",
+        pool_id,
+        tick_lower_index,
+        tick_upper_index
+    ))
+}
+
 pub fn calculate_swap_change(
     rpc_client: &RpcClient,
     raydium_v3_program: Pubkey,
@@ -198,7 +269,7 @@ pub fn calculate_swap_change(
     amount: u64,
     limit_price: Option<f64>,
     base_in: bool,
-    slippage_bps: u64,
+    slippage: Slippage,
 ) -> Result<ClmmSwapChangeResult> {
     let pool_state =
         rpc::get_anchor_account::<raydium_amm_v3::states::PoolState>(rpc_client, &pool_id)
@@ -218,10 +289,12 @@ pub fn calculate_swap_change(
         array_ref![rsps, 0, 5];
     let mint0_token_program = mint0_account.as_ref().unwrap().owner;
     let mint1_token_program = mint1_account.as_ref().unwrap().owner;
-    let user_input_state =
-        common_utils::unpack_token(&user_input_account.as_ref().unwrap().data).unwrap();
+    let user_input_mint =
+        common_utils::get_user_token_mint(&input_token, user_input_account.as_ref())?;
     let mint0_state = common_utils::unpack_mint(&mint0_account.as_ref().unwrap().data).unwrap();
     let mint1_state = common_utils::unpack_mint(&mint1_account.as_ref().unwrap().data).unwrap();
+    common_utils::check_transfer_hook(&pool_state.token_mint_0, &mint0_state)?;
+    common_utils::check_transfer_hook(&pool_state.token_mint_1, &mint1_state)?;
     let tickarray_bitmap_extension_state = common_utils::deserialize_anchor_account::<
         raydium_amm_v3::states::TickArrayBitmapExtension,
     >(
@@ -241,7 +314,7 @@ pub fn calculate_swap_change(
         output_vault_mint,
         input_token_program,
         output_token_program,
-    ) = if user_input_state.base.mint == pool_state.token_mint_0 {
+    ) = if user_input_mint == pool_state.token_mint_0 {
         (
             true,
             pool_state.token_vault_0,
@@ -251,7 +324,7 @@ pub fn calculate_swap_change(
             mint0_token_program,
             mint1_token_program,
         )
-    } else if user_input_state.base.mint == pool_state.token_mint_1 {
+    } else if user_input_mint == pool_state.token_mint_1 {
         (
             false,
             pool_state.token_vault_1,
@@ -306,10 +379,20 @@ pub fn calculate_swap_change(
             &mut tick_arrays,
         )
         .unwrap();
-    println!(
-        "amount:{}, other_amount_threshold:{}",
-        amount, other_amount_threshold
-    );
+    // the raw price of token 0 quoted in token 1
+    let spot_price = (pool_state.sqrt_price_x64 as f64 / (1u128 << 64) as f64).powi(2);
+    let spot_rate = if zero_for_one {
+        spot_price
+    } else {
+        1.0 / spot_price
+    };
+    let quote_rate = if base_in {
+        other_amount_threshold as f64 / amount_specified as f64
+    } else {
+        amount_specified as f64 / other_amount_threshold as f64
+    };
+    let price_impact_bps = common_utils::price_impact_bps(spot_rate, quote_rate);
+    let slippage_bps = slippage.bps(price_impact_bps);
     let remaining_tick_array_keys = tick_array_indexs
         .into_iter()
         .map(|index| {
@@ -356,6 +439,8 @@ pub fn calculate_swap_change(
         other_amount_threshold,
         sqrt_price_limit_x64,
         is_base_input: base_in,
+        price_impact_bps,
+        slippage_bps,
     })
 }
 
@@ -669,3 +754,385 @@ pub fn get_nft_accounts_and_positions_by_owner(
         .collect();
     (nft_accounts_info, user_position_account)
 }
+
+pub fn get_pool_mints(rpc_client: &RpcClient, pool_id: &Pubkey) -> Result<PoolMints> {
+    let pool_state =
+        rpc::get_anchor_account::<raydium_amm_v3::states::PoolState>(rpc_client, pool_id)?.unwrap();
+    Ok(PoolMints {
+        mint_0: pool_state.token_mint_0,
+        mint_1: pool_state.token_mint_1,
+        decimals_0: pool_state.mint_decimals_0,
+        decimals_1: pool_state.mint_decimals_1,
+    })
+}
+
+/// Decode the keys of the clmm pool for the pool index.
+pub fn decode_indexed_pool(pool_id: &Pubkey, pool_account: &Account) -> Result<IndexedPool> {
+    let pool_state = common_utils::deserialize_anchor_account::<raydium_amm_v3::states::PoolState>(
+        pool_account,
+    )?;
+    Ok(IndexedPool {
+        program: PoolProgram::Clmm,
+        pool_id: *pool_id,
+        mint_0: pool_state.token_mint_0,
+        mint_1: pool_state.token_mint_1,
+        decimals_0: pool_state.mint_decimals_0,
+        decimals_1: pool_state.mint_decimals_1,
+        fee_config: pool_state.amm_config,
+        vault_0: pool_state.token_vault_0,
+        vault_1: pool_state.token_vault_1,
+        lp_mint: Pubkey::default(),
+        observation: pool_state.observation_key,
+        amm_keys: None,
+    })
+}
+
+/// Add the new clmm pools to the index at `path` and drop the closed ones.
+pub fn refresh_pool_index(
+    rpc_client: &RpcClient,
+    config: &CommonConfig,
+    path: &str,
+    index: &mut PoolIndex,
+) -> Result<(usize, usize)> {
+    common::refresh_program_pools(
+        rpc_client,
+        path,
+        index,
+        PoolProgram::Clmm,
+        &config.clmm_program(),
+        raydium_amm_v3::states::PoolState::LEN as u64,
+        decode_indexed_pool,
+    )
+}
+
+/// The pool, the vaults and the tick arrays around the current price of the clmm pool.
+/// The tick arrays may not exist yet.
+pub fn get_watch_accounts(
+    rpc_client: &RpcClient,
+    raydium_v3_program: &Pubkey,
+    pool_id: &Pubkey,
+) -> Result<Vec<Pubkey>> {
+    let pool_state =
+        rpc::get_anchor_account::<raydium_amm_v3::states::PoolState>(rpc_client, pool_id)?.unwrap();
+    Ok(pool_watch_accounts(
+        raydium_v3_program,
+        pool_id,
+        &pool_state,
+    ))
+}
+
+fn pool_watch_accounts(
+    raydium_v3_program: &Pubkey,
+    pool_id: &Pubkey,
+    pool_state: &raydium_amm_v3::states::PoolState,
+) -> Vec<Pubkey> {
+    let mut accounts = vec![*pool_id, pool_state.token_vault_0, pool_state.token_vault_1];
+    let tick_array_start_index = raydium_amm_v3::states::TickArrayState::get_array_start_index(
+        pool_state.tick_current,
+        pool_state.tick_spacing,
+    );
+    let tick_count = raydium_amm_v3::states::TickArrayState::tick_count(pool_state.tick_spacing);
+    for start_index in [
+        tick_array_start_index - tick_count,
+        tick_array_start_index,
+        tick_array_start_index + tick_count,
+    ] {
+        accounts.push(
+            Pubkey::find_program_address(
+                &[
+                    raydium_amm_v3::states::TICK_ARRAY_SEED.as_bytes(),
+                    pool_id.to_bytes().as_ref(),
+                    &start_index.to_be_bytes(),
+                ],
+                raydium_v3_program,
+            )
+            .0,
+        );
+    }
+    accounts
+}
+
+/// Whether the current tick of the clmm pool left the watched tick arrays, then the watched
+/// accounts are recomputed and subscribed again.
+pub fn watch_accounts_changed(
+    raydium_v3_program: &Pubkey,
+    pool_id: &Pubkey,
+    watched: &[Pubkey],
+    accounts: &HashMap<Pubkey, Account>,
+) -> Result<bool> {
+    let pool_state = common_utils::deserialize_anchor_account::<raydium_amm_v3::states::PoolState>(
+        get_watched_account(accounts, pool_id)?,
+    )?;
+    Ok(pool_watch_accounts(raydium_v3_program, pool_id, &pool_state) != watched)
+}
+
+/// Recompute the price, the liquidity and the vault amounts of the clmm pool from the
+/// watched accounts, and count the initialized ticks of the watched tick arrays.
+pub fn pool_snapshot(
+    pool_id: &Pubkey,
+    accounts: &HashMap<Pubkey, Account>,
+) -> Result<PoolSnapshot> {
+    let pool_state = common_utils::deserialize_anchor_account::<raydium_amm_v3::states::PoolState>(
+        get_watched_account(accounts, pool_id)?,
+    )?;
+    let vault_0_info = common_utils::unpack_token(
+        &get_watched_account(accounts, &pool_state.token_vault_0)?.data,
+    )?;
+    let vault_1_info = common_utils::unpack_token(
+        &get_watched_account(accounts, &pool_state.token_vault_1)?.data,
+    )?;
+    let initialized_ticks = accounts
+        .values()
+        .filter_map(|account| {
+            common_utils::deserialize_anchor_account::<raydium_amm_v3::states::TickArrayState>(
+                account,
+            )
+            .ok()
+        })
+        .filter(|tick_array| tick_array.pool_id == *pool_id)
+        .map(|tick_array| tick_array.initialized_tick_count as u32)
+        .sum();
+    Ok(PoolSnapshot {
+        pool_id: *pool_id,
+        mints: PoolMints {
+            mint_0: pool_state.token_mint_0,
+            mint_1: pool_state.token_mint_1,
+            decimals_0: pool_state.mint_decimals_0,
+            decimals_1: pool_state.mint_decimals_1,
+        },
+        price: clmm_math::sqrt_price_x64_to_price(
+            pool_state.sqrt_price_x64,
+            pool_state.mint_decimals_0,
+            pool_state.mint_decimals_1,
+        ),
+        reserve_0: vault_0_info.base.amount,
+        reserve_1: vault_1_info.base.amount,
+        liquidity: Some(pool_state.liquidity),
+        tick_current: Some(pool_state.tick_current),
+        initialized_ticks: Some(initialized_ticks),
+    })
+}
+
+/// The twap of the pool over the window in seconds, computed from the tick cumulative of the
+/// observations.
+pub fn get_pool_twap(rpc_client: &RpcClient, pool_id: &Pubkey, window: u64) -> Result<PoolTwap> {
+    let pool_state =
+        rpc::get_anchor_account::<raydium_amm_v3::states::PoolState>(rpc_client, pool_id)?.unwrap();
+    let observation_state = rpc::get_anchor_account::<raydium_amm_v3::states::ObservationState>(
+        rpc_client,
+        &pool_state.observation_key,
+    )?
+    .unwrap();
+
+    // copy out of the packed account
+    let observations = observation_state.observations;
+    let newest_index = observation_state.observation_index as usize;
+    let timestamps: Vec<u64> = observations
+        .iter()
+        .map(|observation| observation.block_timestamp as u64)
+        .collect();
+    let start_index = common::observation_window_start(&timestamps, newest_index, window).ok_or(
+        format_err!("not enough observations of the pool {}", pool_id),
+    )?;
+    let newest = observations[newest_index];
+    let start = observations[start_index];
+    let duration = newest.block_timestamp - start.block_timestamp;
+    let average_tick = (newest.tick_cumulative - start.tick_cumulative) as f64 / duration as f64;
+    Ok(PoolTwap {
+        pool_id: *pool_id,
+        mints: PoolMints {
+            mint_0: pool_state.token_mint_0,
+            mint_1: pool_state.token_mint_1,
+            decimals_0: pool_state.mint_decimals_0,
+            decimals_1: pool_state.mint_decimals_1,
+        },
+        spot_price: clmm_math::sqrt_price_x64_to_price(
+            pool_state.sqrt_price_x64,
+            pool_state.mint_decimals_0,
+            pool_state.mint_decimals_1,
+        ),
+        twap_price: 1.0001f64.powf(average_tick) * clmm_math::multipler(pool_state.mint_decimals_0)
+            / clmm_math::multipler(pool_state.mint_decimals_1),
+        window_start: start.block_timestamp as u64,
+        window_end: newest.block_timestamp as u64,
+    })
+}
+
+/// The spot price of token 0 quoted in token 1 of the clmm pool.
+pub fn get_pool_price(rpc_client: &RpcClient, pool_id: &Pubkey) -> Result<f64> {
+    let pool_state =
+        rpc::get_anchor_account::<raydium_amm_v3::states::PoolState>(rpc_client, pool_id)?.unwrap();
+    Ok(clmm_math::sqrt_price_x64_to_price(
+        pool_state.sqrt_price_x64,
+        pool_state.mint_decimals_0,
+        pool_state.mint_decimals_1,
+    ))
+}
+
+/// Decode the status bits of the clmm pool into the operations, a set bit disables one.
+/// Opening a position also stands for increasing the liquidity, withdrawing for decreasing it.
+pub fn decode_pool_status(pool_state: &raydium_amm_v3::states::PoolState) -> PoolStatus {
+    let enabled = |bit: u8| pool_state.status & (1 << bit) == 0;
+    PoolStatus {
+        status: pool_state.status as u64,
+        operations: vec![
+            (PoolOperation::OpenPosition, enabled(0)),
+            (PoolOperation::Withdraw, enabled(1)),
+            (PoolOperation::CollectFee, enabled(2)),
+            (PoolOperation::CollectReward, enabled(3)),
+            (PoolOperation::Swap, enabled(4)),
+        ],
+        open_time: pool_state.open_time,
+    }
+}
+
+/// Refuse the operation the status of the clmm pool disables, or a swap before the pool opens.
+pub fn check_pool_operation(
+    rpc_client: &RpcClient,
+    pool_id: &Pubkey,
+    operation: PoolOperation,
+) -> Result<()> {
+    let pool_state =
+        rpc::get_anchor_account::<raydium_amm_v3::states::PoolState>(rpc_client, pool_id)?.unwrap();
+    common::check_pool_status(pool_id, &decode_pool_status(&pool_state), operation)
+}
+
+/// The price, the active liquidity and tick, the vault amounts without the protocol and fund
+/// fees, the fees of the amm config and the status of the clmm pool.
+pub fn get_pool_summary(
+    rpc_client: &RpcClient,
+    raydium_v3_program: &Pubkey,
+    pool_id: &Pubkey,
+) -> Result<PoolSummary> {
+    let accounts = get_watch_accounts(rpc_client, raydium_v3_program, pool_id)?;
+    let rsps = rpc_client.get_multiple_accounts(&accounts)?;
+    let account_datas: HashMap<Pubkey, Account> = accounts
+        .into_iter()
+        .zip(rsps)
+        .filter_map(|(key, account)| account.map(|account| (key, account)))
+        .collect();
+    let mut snapshot = pool_snapshot(pool_id, &account_datas)?;
+    let pool_state = common_utils::deserialize_anchor_account::<raydium_amm_v3::states::PoolState>(
+        get_watched_account(&account_datas, pool_id)?,
+    )?;
+    snapshot.reserve_0 = snapshot
+        .reserve_0
+        .saturating_sub(pool_state.protocol_fees_token_0)
+        .saturating_sub(pool_state.fund_fees_token_0);
+    snapshot.reserve_1 = snapshot
+        .reserve_1
+        .saturating_sub(pool_state.protocol_fees_token_1)
+        .saturating_sub(pool_state.fund_fees_token_1);
+    let amm_config_state = rpc::get_anchor_account::<raydium_amm_v3::states::AmmConfig>(
+        rpc_client,
+        &pool_state.amm_config,
+    )?
+    .unwrap();
+    Ok(PoolSummary {
+        snapshot,
+        lp_supply: None,
+        fee_rates: vec![
+            ("trade", amm_config_state.trade_fee_rate as u64),
+            ("protocol_share", amm_config_state.protocol_fee_rate as u64),
+            ("fund_share", amm_config_state.fund_fee_rate as u64),
+        ],
+        status: decode_pool_status(&pool_state),
+    })
+}
+
+/// The recent price movement in bps of the clmm pool, the deviation of the spot price from
+/// the twap over the window.
+pub fn get_price_movement_bps(
+    rpc_client: &RpcClient,
+    pool_id: &Pubkey,
+    window: u64,
+) -> Result<u64> {
+    let pool_twap = get_pool_twap(rpc_client, pool_id, window)?;
+    Ok(pool_twap.deviation_bps().abs().ceil() as u64)
+}
+
+/// The rent of the accounts the opening of a position creates: the position nft, the personal
+/// position and, if missing, the protocol position and the tick arrays of the range.
+pub fn open_position_rent(
+    funds: &mut FundsCheck,
+    clmm_program: &Pubkey,
+    pool_id: &Pubkey,
+    result: &ClmmLiquidityChangeResult,
+    traditional_nft: bool,
+    with_metadata: bool,
+) {
+    if traditional_nft {
+        funds.rent("position nft mint", spl_token::state::Mint::LEN);
+        funds.rent("position nft account", spl_token::state::Account::LEN);
+        if with_metadata {
+            funds.rent("position nft metadata", METADATA_ACCOUNT_LEN);
+        }
+    } else {
+        funds.rent("position nft mint", TOKEN_2022_NFT_MINT_LEN);
+        // the immutable owner extension of the Token-2022 associated token account
+        funds.rent("position nft account", spl_token::state::Account::LEN + 5);
+    }
+    funds.rent(
+        "personal position",
+        raydium_amm_v3::states::PersonalPositionState::LEN,
+    );
+    let protocol_position = Pubkey::find_program_address(
+        &[
+            raydium_amm_v3::states::POSITION_SEED.as_bytes(),
+            pool_id.to_bytes().as_ref(),
+            &result.tick_lower_index.to_be_bytes(),
+            &result.tick_upper_index.to_be_bytes(),
+        ],
+        clmm_program,
+    )
+    .0;
+    funds.rent_if_missing(
+        "protocol position",
+        protocol_position,
+        raydium_amm_v3::states::ProtocolPositionState::LEN,
+    );
+    let mut start_indexes = vec![result.tick_array_lower_start_index];
+    if result.tick_array_upper_start_index != result.tick_array_lower_start_index {
+        start_indexes.push(result.tick_array_upper_start_index);
+    }
+    for start_index in start_indexes {
+        let tick_array = Pubkey::find_program_address(
+            &[
+                raydium_amm_v3::states::TICK_ARRAY_SEED.as_bytes(),
+                pool_id.to_bytes().as_ref(),
+                &start_index.to_be_bytes(),
+            ],
+            clmm_program,
+        )
+        .0;
+        funds.rent_if_missing(
+            "tick array",
+            tick_array,
+            raydium_amm_v3::states::TickArrayState::LEN,
+        );
+    }
+}
+
+/// The personal position of the position nft mint, the cleanup keeps the accounts of the mints
+/// with one.
+pub fn personal_position_key(clmm_program: &Pubkey, nft_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            raydium_amm_v3::states::POSITION_SEED.as_bytes(),
+            nft_mint.to_bytes().as_ref(),
+        ],
+        clmm_program,
+    )
+    .0
+}
+
+/// The personal positions refund their rent to the owner when the position is closed.
+pub fn cost_accounts(config: &CommonConfig) -> CostAccounts {
+    CostAccounts {
+        fee_receivers: Vec::new(),
+        refundable: vec![(
+            config.clmm_program(),
+            raydium_amm_v3::states::PersonalPositionState::DISCRIMINATOR,
+        )],
+    }
+}
@@ -2,7 +2,7 @@ use anchor_client::ClientError;
 use anchor_lang::prelude::Pubkey;
 use anchor_lang::Discriminator;
 use anyhow::Result;
-use common::{common_types, InstructionDecodeType};
+use common::{common_types, InstructionDecodeType, ReceiptEvent, SwapQuote, SwapTrade};
 use raydium_amm_v3::instruction;
 use raydium_amm_v3::instructions::*;
 use raydium_amm_v3::states::*;
@@ -38,6 +38,140 @@ pub fn handle_program_instruction(
         }
     }
 
+    match decode_clmm_instruction(&data)? {
+        Some(instruction) => println!("{:#?}", instruction),
+        None => println!("unknow instruction: {}", instr_data),
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum ClmmInstruction {
+    CreateAmmConfig {
+        index: u16,
+        tick_spacing: u16,
+        trade_fee_rate: u32,
+        protocol_fee_rate: u32,
+        fund_fee_rate: u32,
+    },
+    UpdateAmmConfig {
+        param: u8,
+        value: u32,
+    },
+    CreatePool {
+        sqrt_price_x64: u128,
+        open_time: u64,
+    },
+    UpdatePoolStatus {
+        status: u8,
+    },
+    CreateOperationAccount,
+    UpdateOperationAccount {
+        param: u8,
+        keys: Vec<Pubkey>,
+    },
+    TransferRewardOwner {
+        new_owner: Pubkey,
+    },
+    InitializeReward {
+        param: InitializeRewardParam,
+    },
+    CollectRemainingRewards {
+        reward_index: u8,
+    },
+    UpdateRewardInfos,
+    SetRewardParams {
+        reward_index: u8,
+        emissions_per_second_x64: u128,
+        open_time: u64,
+        end_time: u64,
+    },
+    CollectProtocolFee {
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    },
+    CollectFundFee {
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    },
+    OpenPosition {
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+    },
+    OpenPositionV2 {
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+        base_flag: Option<bool>,
+        with_metadata: bool,
+    },
+    OpenPositionWithToken22Nft {
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+        with_metadata: bool,
+        base_flag: Option<bool>,
+    },
+    ClosePosition,
+    IncreaseLiquidity {
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+    },
+    IncreaseLiquidityV2 {
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+        base_flag: Option<bool>,
+    },
+    DecreaseLiquidity {
+        liquidity: u128,
+        amount_0_min: u64,
+        amount_1_min: u64,
+    },
+    DecreaseLiquidityV2 {
+        liquidity: u128,
+        amount_0_min: u64,
+        amount_1_min: u64,
+    },
+    Swap {
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+    },
+    SwapV2 {
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+    },
+    SwapRouterBaseIn {
+        amount_in: u64,
+        amount_out_minimum: u64,
+    },
+}
+
+/// Decode the instruction data, returns `None` if the instruction is unknown.
+pub fn decode_clmm_instruction(data: &[u8]) -> Result<Option<ClmmInstruction>, ClientError> {
+    if data.len() < 8 {
+        return Err(ClientError::AnchorError(
+            anchor_lang::error::ErrorCode::InstructionMissing.into(),
+        ));
+    }
     let mut ix_data: &[u8] = &data[..];
     let disc: [u8; 8] = {
         let mut disc = [0; 8];
@@ -45,442 +179,270 @@ pub fn handle_program_instruction(
         ix_data = &ix_data[8..];
         disc
     };
-    // println!("{:?}", disc);
-
-    match disc {
+    let instruction = match disc {
         instruction::CreateAmmConfig::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::CreateAmmConfig>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct CreateAmmConfig {
-                pub index: u16,
-                pub tick_spacing: u16,
-                pub trade_fee_rate: u32,
-                pub protocol_fee_rate: u32,
-                pub fund_fee_rate: u32,
+            let ix = decode_instruction::<instruction::CreateAmmConfig>(&mut ix_data)?;
+            ClmmInstruction::CreateAmmConfig {
+                index: ix.index,
+                tick_spacing: ix.tick_spacing,
+                trade_fee_rate: ix.trade_fee_rate,
+                protocol_fee_rate: ix.protocol_fee_rate,
+                fund_fee_rate: ix.fund_fee_rate,
             }
-            impl From<instruction::CreateAmmConfig> for CreateAmmConfig {
-                fn from(instr: instruction::CreateAmmConfig) -> CreateAmmConfig {
-                    CreateAmmConfig {
-                        index: instr.index,
-                        tick_spacing: instr.tick_spacing,
-                        trade_fee_rate: instr.trade_fee_rate,
-                        protocol_fee_rate: instr.protocol_fee_rate,
-                        fund_fee_rate: instr.fund_fee_rate,
-                    }
-                }
-            }
-            println!("{:#?}", CreateAmmConfig::from(ix));
         }
         instruction::UpdateAmmConfig::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::UpdateAmmConfig>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct UpdateAmmConfig {
-                pub param: u8,
-                pub value: u32,
-            }
-            impl From<instruction::UpdateAmmConfig> for UpdateAmmConfig {
-                fn from(instr: instruction::UpdateAmmConfig) -> UpdateAmmConfig {
-                    UpdateAmmConfig {
-                        param: instr.param,
-                        value: instr.value,
-                    }
-                }
+            let ix = decode_instruction::<instruction::UpdateAmmConfig>(&mut ix_data)?;
+            ClmmInstruction::UpdateAmmConfig {
+                param: ix.param,
+                value: ix.value,
             }
-            println!("{:#?}", UpdateAmmConfig::from(ix));
         }
         instruction::CreatePool::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::CreatePool>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct CreatePool {
-                pub sqrt_price_x64: u128,
-                pub open_time: u64,
+            let ix = decode_instruction::<instruction::CreatePool>(&mut ix_data)?;
+            ClmmInstruction::CreatePool {
+                sqrt_price_x64: ix.sqrt_price_x64,
+                open_time: ix.open_time,
             }
-            impl From<instruction::CreatePool> for CreatePool {
-                fn from(instr: instruction::CreatePool) -> CreatePool {
-                    CreatePool {
-                        sqrt_price_x64: instr.sqrt_price_x64,
-                        open_time: instr.open_time,
-                    }
-                }
-            }
-            println!("{:#?}", CreatePool::from(ix));
         }
         instruction::UpdatePoolStatus::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::UpdatePoolStatus>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct UpdatePoolStatus {
-                pub status: u8,
-            }
-            impl From<instruction::UpdatePoolStatus> for UpdatePoolStatus {
-                fn from(instr: instruction::UpdatePoolStatus) -> UpdatePoolStatus {
-                    UpdatePoolStatus {
-                        status: instr.status,
-                    }
-                }
-            }
-            println!("{:#?}", UpdatePoolStatus::from(ix));
+            let ix = decode_instruction::<instruction::UpdatePoolStatus>(&mut ix_data)?;
+            ClmmInstruction::UpdatePoolStatus { status: ix.status }
         }
         instruction::CreateOperationAccount::DISCRIMINATOR => {
-            let ix =
-                decode_instruction::<instruction::CreateOperationAccount>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct CreateOperationAccount;
-            impl From<instruction::CreateOperationAccount> for CreateOperationAccount {
-                fn from(_instr: instruction::CreateOperationAccount) -> CreateOperationAccount {
-                    CreateOperationAccount
-                }
-            }
-            println!("{:#?}", CreateOperationAccount::from(ix));
+            decode_instruction::<instruction::CreateOperationAccount>(&mut ix_data)?;
+            ClmmInstruction::CreateOperationAccount
         }
         instruction::UpdateOperationAccount::DISCRIMINATOR => {
-            let ix =
-                decode_instruction::<instruction::UpdateOperationAccount>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct UpdateOperationAccount {
-                pub param: u8,
-                pub keys: Vec<Pubkey>,
-            }
-            impl From<instruction::UpdateOperationAccount> for UpdateOperationAccount {
-                fn from(instr: instruction::UpdateOperationAccount) -> UpdateOperationAccount {
-                    UpdateOperationAccount {
-                        param: instr.param,
-                        keys: instr.keys,
-                    }
-                }
+            let ix = decode_instruction::<instruction::UpdateOperationAccount>(&mut ix_data)?;
+            ClmmInstruction::UpdateOperationAccount {
+                param: ix.param,
+                keys: ix.keys,
             }
-            println!("{:#?}", UpdateOperationAccount::from(ix));
         }
         instruction::TransferRewardOwner::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::TransferRewardOwner>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct TransferRewardOwner {
-                pub new_owner: Pubkey,
-            }
-            impl From<instruction::TransferRewardOwner> for TransferRewardOwner {
-                fn from(instr: instruction::TransferRewardOwner) -> TransferRewardOwner {
-                    TransferRewardOwner {
-                        new_owner: instr.new_owner,
-                    }
-                }
+            let ix = decode_instruction::<instruction::TransferRewardOwner>(&mut ix_data)?;
+            ClmmInstruction::TransferRewardOwner {
+                new_owner: ix.new_owner,
             }
-            println!("{:#?}", TransferRewardOwner::from(ix));
         }
         instruction::InitializeReward::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::InitializeReward>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct InitializeReward {
-                pub param: InitializeRewardParam,
-            }
-            impl From<instruction::InitializeReward> for InitializeReward {
-                fn from(instr: instruction::InitializeReward) -> InitializeReward {
-                    InitializeReward { param: instr.param }
-                }
-            }
-            println!("{:#?}", InitializeReward::from(ix));
+            let ix = decode_instruction::<instruction::InitializeReward>(&mut ix_data)?;
+            ClmmInstruction::InitializeReward { param: ix.param }
         }
         instruction::CollectRemainingRewards::DISCRIMINATOR => {
-            let ix =
-                decode_instruction::<instruction::CollectRemainingRewards>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct CollectRemainingRewards {
-                pub reward_index: u8,
-            }
-            impl From<instruction::CollectRemainingRewards> for CollectRemainingRewards {
-                fn from(instr: instruction::CollectRemainingRewards) -> CollectRemainingRewards {
-                    CollectRemainingRewards {
-                        reward_index: instr.reward_index,
-                    }
-                }
+            let ix = decode_instruction::<instruction::CollectRemainingRewards>(&mut ix_data)?;
+            ClmmInstruction::CollectRemainingRewards {
+                reward_index: ix.reward_index,
             }
-            println!("{:#?}", CollectRemainingRewards::from(ix));
         }
         instruction::UpdateRewardInfos::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::UpdateRewardInfos>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct UpdateRewardInfos;
-            impl From<instruction::UpdateRewardInfos> for UpdateRewardInfos {
-                fn from(_instr: instruction::UpdateRewardInfos) -> UpdateRewardInfos {
-                    UpdateRewardInfos
-                }
-            }
-            println!("{:#?}", UpdateRewardInfos::from(ix));
+            decode_instruction::<instruction::UpdateRewardInfos>(&mut ix_data)?;
+            ClmmInstruction::UpdateRewardInfos
         }
         instruction::SetRewardParams::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::SetRewardParams>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct SetRewardParams {
-                pub reward_index: u8,
-                pub emissions_per_second_x64: u128,
-                pub open_time: u64,
-                pub end_time: u64,
-            }
-            impl From<instruction::SetRewardParams> for SetRewardParams {
-                fn from(instr: instruction::SetRewardParams) -> SetRewardParams {
-                    SetRewardParams {
-                        reward_index: instr.reward_index,
-                        emissions_per_second_x64: instr.emissions_per_second_x64,
-                        open_time: instr.open_time,
-                        end_time: instr.end_time,
-                    }
-                }
+            let ix = decode_instruction::<instruction::SetRewardParams>(&mut ix_data)?;
+            ClmmInstruction::SetRewardParams {
+                reward_index: ix.reward_index,
+                emissions_per_second_x64: ix.emissions_per_second_x64,
+                open_time: ix.open_time,
+                end_time: ix.end_time,
             }
-            println!("{:#?}", SetRewardParams::from(ix));
         }
         instruction::CollectProtocolFee::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::CollectProtocolFee>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct CollectProtocolFee {
-                pub amount_0_requested: u64,
-                pub amount_1_requested: u64,
+            let ix = decode_instruction::<instruction::CollectProtocolFee>(&mut ix_data)?;
+            ClmmInstruction::CollectProtocolFee {
+                amount_0_requested: ix.amount_0_requested,
+                amount_1_requested: ix.amount_1_requested,
             }
-            impl From<instruction::CollectProtocolFee> for CollectProtocolFee {
-                fn from(instr: instruction::CollectProtocolFee) -> CollectProtocolFee {
-                    CollectProtocolFee {
-                        amount_0_requested: instr.amount_0_requested,
-                        amount_1_requested: instr.amount_1_requested,
-                    }
-                }
-            }
-            println!("{:#?}", CollectProtocolFee::from(ix));
         }
         instruction::CollectFundFee::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::CollectFundFee>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct CollectFundFee {
-                pub amount_0_requested: u64,
-                pub amount_1_requested: u64,
-            }
-            impl From<instruction::CollectFundFee> for CollectFundFee {
-                fn from(instr: instruction::CollectFundFee) -> CollectFundFee {
-                    CollectFundFee {
-                        amount_0_requested: instr.amount_0_requested,
-                        amount_1_requested: instr.amount_1_requested,
-                    }
-                }
+            let ix = decode_instruction::<instruction::CollectFundFee>(&mut ix_data)?;
+            ClmmInstruction::CollectFundFee {
+                amount_0_requested: ix.amount_0_requested,
+                amount_1_requested: ix.amount_1_requested,
             }
-            println!("{:#?}", CollectFundFee::from(ix));
         }
         instruction::OpenPosition::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::OpenPosition>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct OpenPosition {
-                pub tick_lower_index: i32,
-                pub tick_upper_index: i32,
-                pub tick_array_lower_start_index: i32,
-                pub tick_array_upper_start_index: i32,
-                pub liquidity: u128,
-                pub amount_0_max: u64,
-                pub amount_1_max: u64,
+            let ix = decode_instruction::<instruction::OpenPosition>(&mut ix_data)?;
+            ClmmInstruction::OpenPosition {
+                tick_lower_index: ix.tick_lower_index,
+                tick_upper_index: ix.tick_upper_index,
+                tick_array_lower_start_index: ix.tick_array_lower_start_index,
+                tick_array_upper_start_index: ix.tick_array_upper_start_index,
+                liquidity: ix.liquidity,
+                amount_0_max: ix.amount_0_max,
+                amount_1_max: ix.amount_1_max,
             }
-            impl From<instruction::OpenPosition> for OpenPosition {
-                fn from(instr: instruction::OpenPosition) -> OpenPosition {
-                    OpenPosition {
-                        tick_lower_index: instr.tick_lower_index,
-                        tick_upper_index: instr.tick_upper_index,
-                        tick_array_lower_start_index: instr.tick_array_lower_start_index,
-                        tick_array_upper_start_index: instr.tick_array_upper_start_index,
-                        liquidity: instr.liquidity,
-                        amount_0_max: instr.amount_0_max,
-                        amount_1_max: instr.amount_1_max,
-                    }
-                }
-            }
-            println!("{:#?}", OpenPosition::from(ix));
         }
         instruction::OpenPositionV2::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::OpenPositionV2>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct OpenPositionV2 {
-                pub tick_lower_index: i32,
-                pub tick_upper_index: i32,
-                pub tick_array_lower_start_index: i32,
-                pub tick_array_upper_start_index: i32,
-                pub liquidity: u128,
-                pub amount_0_max: u64,
-                pub amount_1_max: u64,
-                pub base_flag: Option<bool>,
-                pub with_metadata: bool,
+            let ix = decode_instruction::<instruction::OpenPositionV2>(&mut ix_data)?;
+            ClmmInstruction::OpenPositionV2 {
+                tick_lower_index: ix.tick_lower_index,
+                tick_upper_index: ix.tick_upper_index,
+                tick_array_lower_start_index: ix.tick_array_lower_start_index,
+                tick_array_upper_start_index: ix.tick_array_upper_start_index,
+                liquidity: ix.liquidity,
+                amount_0_max: ix.amount_0_max,
+                amount_1_max: ix.amount_1_max,
+                base_flag: ix.base_flag,
+                with_metadata: ix.with_metadata,
             }
-            impl From<instruction::OpenPositionV2> for OpenPositionV2 {
-                fn from(instr: instruction::OpenPositionV2) -> OpenPositionV2 {
-                    OpenPositionV2 {
-                        tick_lower_index: instr.tick_lower_index,
-                        tick_upper_index: instr.tick_upper_index,
-                        tick_array_lower_start_index: instr.tick_array_lower_start_index,
-                        tick_array_upper_start_index: instr.tick_array_upper_start_index,
-                        liquidity: instr.liquidity,
-                        amount_0_max: instr.amount_0_max,
-                        amount_1_max: instr.amount_1_max,
-                        base_flag: instr.base_flag,
-                        with_metadata: instr.with_metadata,
-                    }
-                }
+        }
+        instruction::OpenPositionWithToken22Nft::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::OpenPositionWithToken22Nft>(&mut ix_data)?;
+            ClmmInstruction::OpenPositionWithToken22Nft {
+                tick_lower_index: ix.tick_lower_index,
+                tick_upper_index: ix.tick_upper_index,
+                tick_array_lower_start_index: ix.tick_array_lower_start_index,
+                tick_array_upper_start_index: ix.tick_array_upper_start_index,
+                liquidity: ix.liquidity,
+                amount_0_max: ix.amount_0_max,
+                amount_1_max: ix.amount_1_max,
+                with_metadata: ix.with_metadata,
+                base_flag: ix.base_flag,
             }
-            println!("{:#?}", OpenPositionV2::from(ix));
         }
         instruction::ClosePosition::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::ClosePosition>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct ClosePosition;
-            impl From<instruction::ClosePosition> for ClosePosition {
-                fn from(_instr: instruction::ClosePosition) -> ClosePosition {
-                    ClosePosition
-                }
-            }
-            println!("{:#?}", ClosePosition::from(ix));
+            decode_instruction::<instruction::ClosePosition>(&mut ix_data)?;
+            ClmmInstruction::ClosePosition
         }
         instruction::IncreaseLiquidity::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::IncreaseLiquidity>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct IncreaseLiquidity {
-                pub liquidity: u128,
-                pub amount_0_max: u64,
-                pub amount_1_max: u64,
-            }
-            impl From<instruction::IncreaseLiquidity> for IncreaseLiquidity {
-                fn from(instr: instruction::IncreaseLiquidity) -> IncreaseLiquidity {
-                    IncreaseLiquidity {
-                        liquidity: instr.liquidity,
-                        amount_0_max: instr.amount_0_max,
-                        amount_1_max: instr.amount_1_max,
-                    }
-                }
+            let ix = decode_instruction::<instruction::IncreaseLiquidity>(&mut ix_data)?;
+            ClmmInstruction::IncreaseLiquidity {
+                liquidity: ix.liquidity,
+                amount_0_max: ix.amount_0_max,
+                amount_1_max: ix.amount_1_max,
             }
-            println!("{:#?}", IncreaseLiquidity::from(ix));
         }
         instruction::IncreaseLiquidityV2::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::IncreaseLiquidityV2>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct IncreaseLiquidityV2 {
-                pub liquidity: u128,
-                pub amount_0_max: u64,
-                pub amount_1_max: u64,
-                pub base_flag: Option<bool>,
+            let ix = decode_instruction::<instruction::IncreaseLiquidityV2>(&mut ix_data)?;
+            ClmmInstruction::IncreaseLiquidityV2 {
+                liquidity: ix.liquidity,
+                amount_0_max: ix.amount_0_max,
+                amount_1_max: ix.amount_1_max,
+                base_flag: ix.base_flag,
             }
-            impl From<instruction::IncreaseLiquidityV2> for IncreaseLiquidityV2 {
-                fn from(instr: instruction::IncreaseLiquidityV2) -> IncreaseLiquidityV2 {
-                    IncreaseLiquidityV2 {
-                        liquidity: instr.liquidity,
-                        amount_0_max: instr.amount_0_max,
-                        amount_1_max: instr.amount_1_max,
-                        base_flag: instr.base_flag,
-                    }
-                }
-            }
-            println!("{:#?}", IncreaseLiquidityV2::from(ix));
         }
         instruction::DecreaseLiquidity::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::DecreaseLiquidity>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct DecreaseLiquidity {
-                pub liquidity: u128,
-                pub amount_0_min: u64,
-                pub amount_1_min: u64,
-            }
-            impl From<instruction::DecreaseLiquidity> for DecreaseLiquidity {
-                fn from(instr: instruction::DecreaseLiquidity) -> DecreaseLiquidity {
-                    DecreaseLiquidity {
-                        liquidity: instr.liquidity,
-                        amount_0_min: instr.amount_0_min,
-                        amount_1_min: instr.amount_1_min,
-                    }
-                }
+            let ix = decode_instruction::<instruction::DecreaseLiquidity>(&mut ix_data)?;
+            ClmmInstruction::DecreaseLiquidity {
+                liquidity: ix.liquidity,
+                amount_0_min: ix.amount_0_min,
+                amount_1_min: ix.amount_1_min,
             }
-            println!("{:#?}", DecreaseLiquidity::from(ix));
         }
         instruction::DecreaseLiquidityV2::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::DecreaseLiquidityV2>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct DecreaseLiquidityV2 {
-                pub liquidity: u128,
-                pub amount_0_min: u64,
-                pub amount_1_min: u64,
+            let ix = decode_instruction::<instruction::DecreaseLiquidityV2>(&mut ix_data)?;
+            ClmmInstruction::DecreaseLiquidityV2 {
+                liquidity: ix.liquidity,
+                amount_0_min: ix.amount_0_min,
+                amount_1_min: ix.amount_1_min,
             }
-            impl From<instruction::DecreaseLiquidityV2> for DecreaseLiquidityV2 {
-                fn from(instr: instruction::DecreaseLiquidityV2) -> DecreaseLiquidityV2 {
-                    DecreaseLiquidityV2 {
-                        liquidity: instr.liquidity,
-                        amount_0_min: instr.amount_0_min,
-                        amount_1_min: instr.amount_1_min,
-                    }
-                }
-            }
-            println!("{:#?}", DecreaseLiquidityV2::from(ix));
         }
         instruction::Swap::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::Swap>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct Swap {
-                pub amount: u64,
-                pub other_amount_threshold: u64,
-                pub sqrt_price_limit_x64: u128,
-                pub is_base_input: bool,
-            }
-            impl From<instruction::Swap> for Swap {
-                fn from(instr: instruction::Swap) -> Swap {
-                    Swap {
-                        amount: instr.amount,
-                        other_amount_threshold: instr.other_amount_threshold,
-                        sqrt_price_limit_x64: instr.sqrt_price_limit_x64,
-                        is_base_input: instr.is_base_input,
-                    }
-                }
+            let ix = decode_instruction::<instruction::Swap>(&mut ix_data)?;
+            ClmmInstruction::Swap {
+                amount: ix.amount,
+                other_amount_threshold: ix.other_amount_threshold,
+                sqrt_price_limit_x64: ix.sqrt_price_limit_x64,
+                is_base_input: ix.is_base_input,
             }
-            println!("{:#?}", Swap::from(ix));
         }
         instruction::SwapV2::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::SwapV2>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct SwapV2 {
-                pub amount: u64,
-                pub other_amount_threshold: u64,
-                pub sqrt_price_limit_x64: u128,
-                pub is_base_input: bool,
+            let ix = decode_instruction::<instruction::SwapV2>(&mut ix_data)?;
+            ClmmInstruction::SwapV2 {
+                amount: ix.amount,
+                other_amount_threshold: ix.other_amount_threshold,
+                sqrt_price_limit_x64: ix.sqrt_price_limit_x64,
+                is_base_input: ix.is_base_input,
             }
-            impl From<instruction::SwapV2> for SwapV2 {
-                fn from(instr: instruction::SwapV2) -> SwapV2 {
-                    SwapV2 {
-                        amount: instr.amount,
-                        other_amount_threshold: instr.other_amount_threshold,
-                        sqrt_price_limit_x64: instr.sqrt_price_limit_x64,
-                        is_base_input: instr.is_base_input,
-                    }
-                }
-            }
-            println!("{:#?}", SwapV2::from(ix));
         }
         instruction::SwapRouterBaseIn::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::SwapRouterBaseIn>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct SwapRouterBaseIn {
-                pub amount_in: u64,
-                pub amount_out_minimum: u64,
-            }
-            impl From<instruction::SwapRouterBaseIn> for SwapRouterBaseIn {
-                fn from(instr: instruction::SwapRouterBaseIn) -> SwapRouterBaseIn {
-                    SwapRouterBaseIn {
-                        amount_in: instr.amount_in,
-                        amount_out_minimum: instr.amount_out_minimum,
-                    }
-                }
+            let ix = decode_instruction::<instruction::SwapRouterBaseIn>(&mut ix_data)?;
+            ClmmInstruction::SwapRouterBaseIn {
+                amount_in: ix.amount_in,
+                amount_out_minimum: ix.amount_out_minimum,
             }
-            println!("{:#?}", SwapRouterBaseIn::from(ix));
         }
-        _ => {
-            println!("unknow instruction: {}", instr_data);
-        }
-    }
-    Ok(())
+        _ => return Ok(None),
+    };
+    Ok(Some(instruction))
 }
 
 fn decode_instruction<T: anchor_lang::AnchorDeserialize>(
     slice: &mut &[u8],
-) -> Result<T, anchor_lang::error::ErrorCode> {
-    let instruction: T = anchor_lang::AnchorDeserialize::deserialize(slice)
-        .map_err(|_| anchor_lang::error::ErrorCode::InstructionDidNotDeserialize)?;
+) -> Result<T, ClientError> {
+    let instruction: T = anchor_lang::AnchorDeserialize::deserialize(slice).map_err(|_| {
+        ClientError::AnchorError(anchor_lang::error::ErrorCode::InstructionDidNotDeserialize.into())
+    })?;
     Ok(instruction)
 }
 
+#[derive(Debug)]
+pub enum ClmmEvent {
+    ConfigChange(ConfigChangeEvent),
+    CollectPersonalFee(CollectPersonalFeeEvent),
+    CollectProtocolFee(CollectProtocolFeeEvent),
+    CreatePersonalPosition(CreatePersonalPositionEvent),
+    DecreaseLiquidity(DecreaseLiquidityEvent),
+    IncreaseLiquidity(IncreaseLiquidityEvent),
+    LiquidityCalculate(LiquidityCalculateEvent),
+    LiquidityChange(LiquidityChangeEvent),
+    Swap(SwapEvent),
+    PoolCreated(PoolCreatedEvent),
+}
+
+/// Decode the base64 data of a `Program data:` log, returns `None` if the event is unknown.
+pub fn decode_clmm_event(log: &str) -> Result<Option<ClmmEvent>, ClientError> {
+    let borsh_bytes = anchor_lang::__private::base64::decode(log)
+        .map_err(|e| ClientError::LogParseError(e.to_string()))?;
+    if borsh_bytes.len() < 8 {
+        return Err(ClientError::LogParseError(format!(
+            "event data is too short: {}",
+            log
+        )));
+    }
+    let mut slice: &[u8] = &borsh_bytes[..];
+    let disc: [u8; 8] = {
+        let mut disc = [0; 8];
+        disc.copy_from_slice(&borsh_bytes[..8]);
+        slice = &slice[8..];
+        disc
+    };
+    let event = match disc {
+        ConfigChangeEvent::DISCRIMINATOR => {
+            ClmmEvent::ConfigChange(decode_event::<ConfigChangeEvent>(&mut slice)?)
+        }
+        CollectPersonalFeeEvent::DISCRIMINATOR => {
+            ClmmEvent::CollectPersonalFee(decode_event::<CollectPersonalFeeEvent>(&mut slice)?)
+        }
+        CollectProtocolFeeEvent::DISCRIMINATOR => {
+            ClmmEvent::CollectProtocolFee(decode_event::<CollectProtocolFeeEvent>(&mut slice)?)
+        }
+        CreatePersonalPositionEvent::DISCRIMINATOR => ClmmEvent::CreatePersonalPosition(
+            decode_event::<CreatePersonalPositionEvent>(&mut slice)?,
+        ),
+        DecreaseLiquidityEvent::DISCRIMINATOR => {
+            ClmmEvent::DecreaseLiquidity(decode_event::<DecreaseLiquidityEvent>(&mut slice)?)
+        }
+        IncreaseLiquidityEvent::DISCRIMINATOR => {
+            ClmmEvent::IncreaseLiquidity(decode_event::<IncreaseLiquidityEvent>(&mut slice)?)
+        }
+        LiquidityCalculateEvent::DISCRIMINATOR => {
+            ClmmEvent::LiquidityCalculate(decode_event::<LiquidityCalculateEvent>(&mut slice)?)
+        }
+        LiquidityChangeEvent::DISCRIMINATOR => {
+            ClmmEvent::LiquidityChange(decode_event::<LiquidityChangeEvent>(&mut slice)?)
+        }
+        SwapEvent::DISCRIMINATOR => ClmmEvent::Swap(decode_event::<SwapEvent>(&mut slice)?),
+        PoolCreatedEvent::DISCRIMINATOR => {
+            ClmmEvent::PoolCreated(decode_event::<PoolCreatedEvent>(&mut slice)?)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(event))
+}
+
 pub fn handle_program_event(log_event: &str, with_prefix: bool) -> Result<(), ClientError> {
     // Log emitted from the current program.
     if let Some(log) = if with_prefix {
@@ -490,75 +452,12 @@ pub fn handle_program_event(log_event: &str, with_prefix: bool) -> Result<(), Cl
     } else {
         Some(log_event)
     } {
-        let borsh_bytes = match anchor_lang::__private::base64::decode(log) {
-            Ok(borsh_bytes) => borsh_bytes,
-            _ => {
-                println!("Could not base64 decode log: {}", log);
-                return Ok(());
-            }
-        };
-
-        let mut slice: &[u8] = &borsh_bytes[..];
-        let disc: [u8; 8] = {
-            let mut disc = [0; 8];
-            disc.copy_from_slice(&borsh_bytes[..8]);
-            slice = &slice[8..];
-            disc
-        };
-        match disc {
-            ConfigChangeEvent::DISCRIMINATOR => {
-                println!("{:#?}", decode_event::<ConfigChangeEvent>(&mut slice)?);
-            }
-            CollectPersonalFeeEvent::DISCRIMINATOR => {
-                println!(
-                    "{:#?}",
-                    decode_event::<CollectPersonalFeeEvent>(&mut slice)?
-                );
-            }
-            CollectProtocolFeeEvent::DISCRIMINATOR => {
-                println!(
-                    "{:#?}",
-                    decode_event::<CollectProtocolFeeEvent>(&mut slice)?
-                );
-            }
-            CreatePersonalPositionEvent::DISCRIMINATOR => {
-                println!(
-                    "{:#?}",
-                    decode_event::<CreatePersonalPositionEvent>(&mut slice)?
-                );
-            }
-            DecreaseLiquidityEvent::DISCRIMINATOR => {
-                println!("{:#?}", decode_event::<DecreaseLiquidityEvent>(&mut slice)?);
-            }
-            IncreaseLiquidityEvent::DISCRIMINATOR => {
-                println!("{:#?}", decode_event::<IncreaseLiquidityEvent>(&mut slice)?);
-            }
-            LiquidityCalculateEvent::DISCRIMINATOR => {
-                println!(
-                    "{:#?}",
-                    decode_event::<LiquidityCalculateEvent>(&mut slice)?
-                );
-            }
-            LiquidityChangeEvent::DISCRIMINATOR => {
-                println!("{:#?}", decode_event::<LiquidityChangeEvent>(&mut slice)?);
-            }
-            // PriceChangeEvent::DISCRIMINATOR => {
-            //     println!("{:#?}", decode_event::<PriceChangeEvent>(&mut slice)?);
-            // }
-            SwapEvent::DISCRIMINATOR => {
-                println!("{:#?}", decode_event::<SwapEvent>(&mut slice)?);
-            }
-            PoolCreatedEvent::DISCRIMINATOR => {
-                println!("{:#?}", decode_event::<PoolCreatedEvent>(&mut slice)?);
-            }
-            _ => {
-                println!("unknow event: {}", log_event);
-            }
+        match decode_clmm_event(log)? {
+            Some(event) => println!("{:#?}", event),
+            None => println!("unknow event: {}", log_event),
         }
-        return Ok(());
-    } else {
-        return Ok(());
     }
+    Ok(())
 }
 
 fn decode_event<T: anchor_lang::Event + anchor_lang::AnchorDeserialize>(
@@ -568,3 +467,436 @@ fn decode_event<T: anchor_lang::Event + anchor_lang::AnchorDeserialize>(
         .map_err(|e| ClientError::LogParseError(e.to_string()))?;
     Ok(event)
 }
+
+/// The name of the event.
+pub fn event_type(event: &ClmmEvent) -> &'static str {
+    match event {
+        ClmmEvent::ConfigChange(_) => "ConfigChange",
+        ClmmEvent::CollectPersonalFee(_) => "CollectPersonalFee",
+        ClmmEvent::CollectProtocolFee(_) => "CollectProtocolFee",
+        ClmmEvent::CreatePersonalPosition(_) => "CreatePersonalPosition",
+        ClmmEvent::DecreaseLiquidity(_) => "DecreaseLiquidity",
+        ClmmEvent::IncreaseLiquidity(_) => "IncreaseLiquidity",
+        ClmmEvent::LiquidityCalculate(_) => "LiquidityCalculate",
+        ClmmEvent::LiquidityChange(_) => "LiquidityChange",
+        ClmmEvent::Swap(_) => "Swap",
+        ClmmEvent::PoolCreated(_) => "PoolCreated",
+    }
+}
+
+/// The pool of the event, the position events only contain the position, the pool is the
+/// `pool_state` account of the instruction.
+pub fn event_pool_id(event: &ClmmEvent) -> Option<Pubkey> {
+    match event {
+        ClmmEvent::CollectProtocolFee(event) => Some(event.pool_state),
+        ClmmEvent::CreatePersonalPosition(event) => Some(event.pool_state),
+        ClmmEvent::LiquidityChange(event) => Some(event.pool_state),
+        ClmmEvent::Swap(event) => Some(event.pool_state),
+        ClmmEvent::PoolCreated(event) => Some(event.pool_state),
+        _ => None,
+    }
+}
+
+/// The swap and liquidity amounts of the event.
+pub fn receipt_event(event: &ClmmEvent) -> Option<ReceiptEvent> {
+    match event {
+        ClmmEvent::Swap(event) => {
+            let (input_amount, output_amount) = if event.zero_for_one {
+                (event.amount_0, event.amount_1)
+            } else {
+                (event.amount_1, event.amount_0)
+            };
+            Some(ReceiptEvent::Swap {
+                pool_id: Some(event.pool_state),
+                input_amount,
+                output_amount,
+            })
+        }
+        ClmmEvent::CreatePersonalPosition(event) => Some(ReceiptEvent::Deposit {
+            pool_id: Some(event.pool_state),
+            amount_0: event.deposit_amount_0,
+            amount_1: event.deposit_amount_1,
+        }),
+        ClmmEvent::IncreaseLiquidity(event) => Some(ReceiptEvent::Deposit {
+            pool_id: None,
+            amount_0: event.amount_0,
+            amount_1: event.amount_1,
+        }),
+        ClmmEvent::DecreaseLiquidity(event) => Some(ReceiptEvent::Withdraw {
+            pool_id: None,
+            amount_0: event.decrease_amount_0,
+            amount_1: event.decrease_amount_1,
+        }),
+        _ => None,
+    }
+}
+
+/// The swap amounts of the swap instruction.
+pub fn swap_quote(instruction: &ClmmInstruction) -> Option<SwapQuote> {
+    match instruction {
+        ClmmInstruction::Swap {
+            amount,
+            other_amount_threshold,
+            is_base_input,
+            ..
+        }
+        | ClmmInstruction::SwapV2 {
+            amount,
+            other_amount_threshold,
+            is_base_input,
+            ..
+        } => Some(SwapQuote {
+            base_in: *is_base_input,
+            amount_specified: *amount,
+            other_amount_threshold: *other_amount_threshold,
+        }),
+        _ => None,
+    }
+}
+
+/// The swap of the event, the accounts of the instruction emitted it are not needed.
+pub fn swap_trade(event: &ClmmEvent, _accounts: &[Pubkey]) -> Option<SwapTrade> {
+    if let ClmmEvent::Swap(event) = event {
+        Some(SwapTrade {
+            pool_id: event.pool_state,
+            zero_for_one: event.zero_for_one,
+            amount_0: event.amount_0,
+            amount_1: event.amount_1,
+        })
+    } else {
+        None
+    }
+}
+
+/// The name of the clmm `ErrorCode`, or of the anchor framework error below the
+/// `ERROR_CODE_OFFSET`, of the custom program error code. The variants are compared by the code
+/// anchor derives for each of them, so the order of the table doesn't matter, and a variant
+/// missing from it has no name.
+pub fn clmm_error_name(code: u32) -> Option<String> {
+    if code < anchor_lang::error::ERROR_CODE_OFFSET {
+        return common::anchor_lang_error_name(code);
+    }
+    use raydium_amm_v3::error::ErrorCode::*;
+    [
+        LOK,
+        NotApproved,
+        InvalidUpdateConfigFlag,
+        AccountLack,
+        ClosePositionErr,
+        ZeroMintAmount,
+        InvaildTickIndex,
+        TickInvaildOrder,
+        TickLowerOverflow,
+        TickUpperOverflow,
+        TickAndSpacingNotMatch,
+        InvalidTickArray,
+        InvalidTickArrayBoundary,
+        SqrtPriceLimitOverflow,
+        SqrtPriceX64,
+        LiquiditySubValueErr,
+        LiquidityAddValueErr,
+        InvaildLiquidity,
+        ForbidBothZeroForSupplyLiquidity,
+        LiquidityInsufficient,
+        TransactionTooOld,
+        PriceSlippageCheck,
+        TooLittleOutputReceived,
+        TooMuchInputPaid,
+        InvalidInputPoolVault,
+        TooSmallInputOrOutputAmount,
+        NotEnoughTickArrayAccount,
+        InvalidFirstTickArrayAccount,
+        InvalidRewardIndex,
+        FullRewardInfo,
+        RewardTokenAlreadyInUse,
+        ExceptPoolVaultMint,
+        InvalidRewardInitParam,
+        InvalidRewardDesiredAmount,
+        InvalidRewardInputAccountNumber,
+        InvalidRewardPeriod,
+        NotApproveUpdateRewardEmissiones,
+        UnInitializedRewardInfo,
+        NotSupportMint,
+        MissingTickArrayBitmapExtensionAccount,
+        InsufficientLiquidityForDirection,
+    ]
+    .into_iter()
+    .find(|error| u32::from(*error) == code)
+    .map(|error| error.name())
+}
+
+/// The account names of the instruction in the order of the account metas,
+/// the remaining accounts such as tick arrays are not named.
+pub fn instruction_account_names(instr_data: &[u8]) -> Vec<&'static str> {
+    if instr_data.len() < 8 {
+        return Vec::new();
+    }
+    let mut disc = [0; 8];
+    disc.copy_from_slice(&instr_data[..8]);
+    match disc {
+        instruction::CreateAmmConfig::DISCRIMINATOR => {
+            vec!["owner", "amm_config", "system_program"]
+        }
+        instruction::UpdateAmmConfig::DISCRIMINATOR => vec!["owner", "amm_config"],
+        instruction::CreatePool::DISCRIMINATOR => vec![
+            "pool_creator",
+            "amm_config",
+            "pool_state",
+            "token_mint_0",
+            "token_mint_1",
+            "token_vault_0",
+            "token_vault_1",
+            "observation_state",
+            "tick_array_bitmap",
+            "token_program_0",
+            "token_program_1",
+            "system_program",
+            "rent",
+        ],
+        instruction::UpdatePoolStatus::DISCRIMINATOR => vec!["authority", "pool_state"],
+        instruction::CreateOperationAccount::DISCRIMINATOR
+        | instruction::UpdateOperationAccount::DISCRIMINATOR => {
+            vec!["owner", "operation_state", "system_program"]
+        }
+        instruction::TransferRewardOwner::DISCRIMINATOR => vec!["authority", "pool_state"],
+        instruction::InitializeReward::DISCRIMINATOR => vec![
+            "reward_funder",
+            "funder_token_account",
+            "amm_config",
+            "pool_state",
+            "operation_state",
+            "reward_token_mint",
+            "reward_token_vault",
+            "reward_token_program",
+            "system_program",
+            "rent",
+        ],
+        instruction::CollectRemainingRewards::DISCRIMINATOR => vec![
+            "reward_funder",
+            "funder_token_account",
+            "pool_state",
+            "reward_token_vault",
+            "reward_vault_mint",
+            "token_program",
+            "token_program_2022",
+            "memo_program",
+        ],
+        instruction::UpdateRewardInfos::DISCRIMINATOR => vec!["pool_state"],
+        instruction::SetRewardParams::DISCRIMINATOR => vec![
+            "authority",
+            "amm_config",
+            "pool_state",
+            "operation_state",
+            "token_program",
+            "token_program_2022",
+        ],
+        instruction::CollectProtocolFee::DISCRIMINATOR
+        | instruction::CollectFundFee::DISCRIMINATOR => vec![
+            "owner",
+            "pool_state",
+            "amm_config",
+            "token_vault_0",
+            "token_vault_1",
+            "vault_0_mint",
+            "vault_1_mint",
+            "recipient_token_account_0",
+            "recipient_token_account_1",
+            "token_program",
+            "token_program_2022",
+        ],
+        instruction::OpenPosition::DISCRIMINATOR => vec![
+            "payer",
+            "position_nft_owner",
+            "position_nft_mint",
+            "position_nft_account",
+            "metadata_account",
+            "pool_state",
+            "protocol_position",
+            "tick_array_lower",
+            "tick_array_upper",
+            "personal_position",
+            "token_account_0",
+            "token_account_1",
+            "token_vault_0",
+            "token_vault_1",
+            "rent",
+            "system_program",
+            "token_program",
+            "associated_token_program",
+            "metadata_program",
+        ],
+        instruction::OpenPositionV2::DISCRIMINATOR => vec![
+            "payer",
+            "position_nft_owner",
+            "position_nft_mint",
+            "position_nft_account",
+            "metadata_account",
+            "pool_state",
+            "protocol_position",
+            "tick_array_lower",
+            "tick_array_upper",
+            "personal_position",
+            "token_account_0",
+            "token_account_1",
+            "token_vault_0",
+            "token_vault_1",
+            "rent",
+            "system_program",
+            "token_program",
+            "associated_token_program",
+            "metadata_program",
+            "token_program_2022",
+            "vault_0_mint",
+            "vault_1_mint",
+        ],
+        instruction::OpenPositionWithToken22Nft::DISCRIMINATOR => vec![
+            "payer",
+            "position_nft_owner",
+            "position_nft_mint",
+            "position_nft_account",
+            "pool_state",
+            "protocol_position",
+            "tick_array_lower",
+            "tick_array_upper",
+            "personal_position",
+            "token_account_0",
+            "token_account_1",
+            "token_vault_0",
+            "token_vault_1",
+            "rent",
+            "system_program",
+            "token_program",
+            "associated_token_program",
+            "token_program_2022",
+            "vault_0_mint",
+            "vault_1_mint",
+        ],
+        instruction::ClosePosition::DISCRIMINATOR => vec![
+            "nft_owner",
+            "position_nft_mint",
+            "position_nft_account",
+            "personal_position",
+            "system_program",
+            "token_program",
+        ],
+        instruction::IncreaseLiquidity::DISCRIMINATOR => vec![
+            "nft_owner",
+            "nft_account",
+            "pool_state",
+            "protocol_position",
+            "personal_position",
+            "tick_array_lower",
+            "tick_array_upper",
+            "token_account_0",
+            "token_account_1",
+            "token_vault_0",
+            "token_vault_1",
+            "token_program",
+        ],
+        instruction::IncreaseLiquidityV2::DISCRIMINATOR => vec![
+            "nft_owner",
+            "nft_account",
+            "pool_state",
+            "protocol_position",
+            "personal_position",
+            "tick_array_lower",
+            "tick_array_upper",
+            "token_account_0",
+            "token_account_1",
+            "token_vault_0",
+            "token_vault_1",
+            "token_program",
+            "token_program_2022",
+            "vault_0_mint",
+            "vault_1_mint",
+        ],
+        instruction::DecreaseLiquidity::DISCRIMINATOR => vec![
+            "nft_owner",
+            "nft_account",
+            "personal_position",
+            "pool_state",
+            "protocol_position",
+            "token_vault_0",
+            "token_vault_1",
+            "tick_array_lower",
+            "tick_array_upper",
+            "recipient_token_account_0",
+            "recipient_token_account_1",
+            "token_program",
+        ],
+        instruction::DecreaseLiquidityV2::DISCRIMINATOR => vec![
+            "nft_owner",
+            "nft_account",
+            "personal_position",
+            "pool_state",
+            "protocol_position",
+            "token_vault_0",
+            "token_vault_1",
+            "tick_array_lower",
+            "tick_array_upper",
+            "recipient_token_account_0",
+            "recipient_token_account_1",
+            "token_program",
+            "token_program_2022",
+            "memo_program",
+            "vault_0_mint",
+            "vault_1_mint",
+        ],
+        instruction::Swap::DISCRIMINATOR => vec![
+            "payer",
+            "amm_config",
+            "pool_state",
+            "input_token_account",
+            "output_token_account",
+            "input_vault",
+            "output_vault",
+            "observation_state",
+            "token_program",
+            "tick_array",
+        ],
+        instruction::SwapV2::DISCRIMINATOR => vec![
+            "payer",
+            "amm_config",
+            "pool_state",
+            "input_token_account",
+            "output_token_account",
+            "input_vault",
+            "output_vault",
+            "observation_state",
+            "token_program",
+            "token_program_2022",
+            "memo_program",
+            "input_vault_mint",
+            "output_vault_mint",
+        ],
+        instruction::SwapRouterBaseIn::DISCRIMINATOR => vec![
+            "payer",
+            "input_token_account",
+            "input_token_mint",
+            "token_program",
+            "token_program_2022",
+            "memo_program",
+        ],
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clmm_error_name() {
+        assert_eq!(
+            clmm_error_name(6022).as_deref(),
+            Some("TooLittleOutputReceived")
+        );
+        assert_eq!(clmm_error_name(6023).as_deref(), Some("TooMuchInputPaid"));
+        assert_eq!(clmm_error_name(6000).as_deref(), Some("LOK"));
+        assert_eq!(
+            clmm_error_name(3012).as_deref(),
+            Some("AccountNotInitialized")
+        );
+        assert_eq!(clmm_error_name(6999), None);
+    }
+}
@@ -1,29 +1,28 @@
 use crate::{clmm_instructions, clmm_utils, decode_clmm_ix_event};
-use anyhow::Result;
+use anyhow::{format_err, Result};
 use clap::Parser;
-use common::{common_types, common_utils, rpc, token};
+use common::{
+    common_types, common_utils, rpc, token, AmountInput, MintInput, PoolOperation, PoolProgram,
+};
 use rand::rngs::OsRng;
 use solana_client::{
     rpc_client::RpcClient,
     rpc_filter::{Memcmp, RpcFilterType},
 };
 use solana_sdk::{
-    instruction::{AccountMeta, Instruction},
-    pubkey::Pubkey,
-    signature::Signer,
-    signer::keypair::Keypair,
+    instruction::AccountMeta, pubkey::Pubkey, signature::Signer, signer::keypair::Keypair,
 };
 use std::sync::Arc;
 
-#[derive(Debug, Parser)]
+#[derive(Clone, Debug, Parser)]
 pub enum ClmmCommands {
     CreatePool {
-        /// The pool's mint0
+        /// The pool's mint0, or its symbol
         #[arg(long)]
-        mint0: Pubkey,
-        /// The pool's mint1
+        mint0: MintInput,
+        /// The pool's mint1, or its symbol
         #[arg(long)]
-        mint1: Pubkey,
+        mint1: MintInput,
         /// The clmm pool config account stored tick_spaceing and the fee infos.
         #[arg(short, long)]
         amm_config: Pubkey,
@@ -40,10 +39,12 @@ pub enum ClmmCommands {
         pool_id: Pubkey,
         /// The specified token0 of the user deposit.
         /// If none is given, the account will be ATA account.
+        /// For the native mint, a temporary wsol account is used and closed after.
         #[clap(long)]
         deposit_token0: Option<Pubkey>,
         /// The specified token1 of the user deposit.
         /// If none is given, the account will be ATA account.
+        /// For the native mint, a temporary wsol account is used and closed after.
         #[clap(long)]
         deposit_token1: Option<Pubkey>,
         /// The float price of token mint0 relative to token mint1
@@ -55,8 +56,9 @@ pub enum ClmmCommands {
         #[arg(long)]
         tick_upper_price: f64,
         /// The amount of the specified token to deposit.
+        /// Accepts ui units like `1.5`, base units like `1500000raw`, a percentage of the balance like `50%` or `max`.
         #[arg(long)]
-        amount_specified: u64,
+        amount_specified: AmountInput,
         /// Indicates which token is specified of the `amount_specified`.
         #[clap(short, long, action)]
         base_token1: bool,
@@ -73,10 +75,12 @@ pub enum ClmmCommands {
         pool_id: Pubkey,
         /// The specified token0 of the user deposit.
         /// If none is given, the account will be ATA account.
+        /// For the native mint, a temporary wsol account is used and closed after.
         #[clap(long)]
         deposit_token0: Option<Pubkey>,
         /// The specified token1 of the user deposit.
         /// If none is given, the account will be ATA account.
+        /// For the native mint, a temporary wsol account is used and closed after.
         #[clap(long)]
         deposit_token1: Option<Pubkey>,
         /// The float price of token mint0 relative to token mint1
@@ -86,8 +90,9 @@ pub enum ClmmCommands {
         /// The position upper price
         tick_upper_price: f64,
         /// The amount of the specified token to deposit.
+        /// Accepts ui units like `1.5`, base units like `1500000raw`, a percentage of the balance like `50%` or `max`.
         #[arg(long)]
-        amount_specified: u64,
+        amount_specified: AmountInput,
         /// Indicates which token is specified of the `amount_specified`.
         #[clap(short, long, action)]
         base_token1: bool,
@@ -98,10 +103,12 @@ pub enum ClmmCommands {
         pool_id: Pubkey,
         /// The specified token0 of the user will receive.
         /// If none is given, the account will be ATA account.
+        /// For the native mint, a temporary wsol account is used and closed after.
         #[clap(long)]
         recipient_token0: Option<Pubkey>,
         /// The specified token1 of the user will receive.
         /// If none is given, the account will be ATA account.
+        /// For the native mint, a temporary wsol account is used and closed after.
         #[clap(long)]
         recipient_token1: Option<Pubkey>,
         /// The float price of token mint0 relative to token mint1
@@ -111,8 +118,9 @@ pub enum ClmmCommands {
         /// The position upper price
         tick_upper_price: f64,
         /// The amount of the specified token to withdraw.
+        /// Accepts ui units like `1.5`, base units like `1500000raw`, a percentage of the position liquidity like `50%` or `max`.
         #[arg(long)]
-        amount_specified: u64,
+        amount_specified: AmountInput,
         /// Indicates which token is specified of the `amount_specified`.
         #[clap(short, long, action)]
         base_token1: bool,
@@ -122,15 +130,20 @@ pub enum ClmmCommands {
         #[clap(short, long)]
         pool_id: Pubkey,
         /// The token of user want to swap from.
+        /// Pass the native mint to pay with sol, which is wrapped into a temporary wsol account.
+        /// A token symbol like `SOL` or `USDC` stands for the sol of the wallet or the ATA account.
         #[clap(long)]
-        user_input_token: Pubkey,
+        user_input_token: MintInput,
         /// The token of user want to swap to.
         /// If none is given, the account will be ATA account.
+        /// For the native mint, a temporary wsol account is used and closed after.
+        /// A token symbol like `USDC` is the same as none given.
         #[clap(long)]
-        user_output_token: Option<Pubkey>,
+        user_output_token: Option<MintInput>,
         /// The amount specified of user want to swap from or to token.
+        /// Accepts ui units like `1.5`, base units like `1500000raw`, and for the input a percentage like `50%` or `max`.
         #[clap(short, long)]
-        amount_specified: u64,
+        amount_specified: AmountInput,
         /// The float price of the pool that can be swaped to.
         #[clap(short, long)]
         limit_price: Option<f64>,
@@ -143,12 +156,15 @@ pub enum ClmmCommands {
         /// If the pool_id is specified, mint0 and mint1 will be ignored.
         #[clap(long)]
         pool_id: Option<Pubkey>,
-        /// Fetch pools by specified mint0.
+        /// Fetch pools by specified mint0, or its symbol.
         #[clap(long)]
-        mint0: Option<Pubkey>,
-        /// Fetch pools by specified mint1.
+        mint0: Option<MintInput>,
+        /// Fetch pools by specified mint1, or its symbol.
         #[clap(long)]
-        mint1: Option<Pubkey>,
+        mint1: Option<MintInput>,
+        /// Print the raw pool state instead of the summary of the specified pool.
+        #[clap(long, action)]
+        raw: bool,
     },
     FetchConfig {
         /// The specified clmm config to fetch. If none is given, fetch all configs.
@@ -171,13 +187,15 @@ pub fn process_clmm_commands(
     command: ClmmCommands,
     config: &common_types::CommonConfig,
     signing_keypairs: &mut Vec<Arc<dyn Signer>>,
-) -> Result<Option<Vec<Instruction>>> {
+) -> Result<Option<common_types::CommandInstructions>> {
     let rpc_client = RpcClient::new(config.cluster().url());
-    let wallet_keypair = common_utils::read_keypair_file(&config.wallet())?;
-    let payer_pubkey = wallet_keypair.pubkey();
-    let payer: Arc<dyn Signer> = Arc::new(wallet_keypair);
-    if !signing_keypairs.contains(&payer) {
-        signing_keypairs.push(payer);
+    let payer_pubkey = config.wallet_pubkey()?;
+    // the wallet signs offline in the sign-only mode
+    if !config.sign_only() {
+        let payer: Arc<dyn Signer> = Arc::new(common_utils::read_keypair_file(&config.wallet())?);
+        if !signing_keypairs.contains(&payer) {
+            signing_keypairs.push(payer);
+        }
     }
 
     match command {
@@ -188,6 +206,8 @@ pub fn process_clmm_commands(
             price,
             open_time,
         } => {
+            let mint0 = mint0.resolve(config)?;
+            let mint1 = mint1.resolve(config)?;
             let result = clmm_utils::create_pool_price(&rpc_client, mint0, mint1, price)?;
             let create_pool_instr = clmm_instructions::create_pool_instr(
                 &config,
@@ -199,7 +219,7 @@ pub fn process_clmm_commands(
                 result.sqrt_price_x64,
                 open_time,
             )?;
-            return Ok(Some(create_pool_instr));
+            return Ok(Some(create_pool_instr.into()));
         }
         ClmmCommands::OpenPosition {
             pool_id,
@@ -212,20 +232,69 @@ pub fn process_clmm_commands(
             without_metadata,
             traditional_nft,
         } => {
+            clmm_utils::check_pool_operation(&rpc_client, &pool_id, PoolOperation::OpenPosition)?;
             let base_token0 = !base_token1;
             let with_metadata = !without_metadata;
+            let pool_mints = common::get_indexed_pool_mints(config, &pool_id, || {
+                clmm_utils::get_pool_mints(&rpc_client, &pool_id)
+            })?;
+            let amount_specified = if base_token0 {
+                common::resolve_token_amount(
+                    &rpc_client,
+                    &amount_specified,
+                    &payer_pubkey,
+                    &pool_mints.mint_0,
+                    deposit_token0.as_ref(),
+                )?
+            } else {
+                common::resolve_token_amount(
+                    &rpc_client,
+                    &amount_specified,
+                    &payer_pubkey,
+                    &pool_mints.mint_1,
+                    deposit_token1.as_ref(),
+                )?
+            };
             let result = clmm_utils::calculate_liquidity_change(
                 &rpc_client,
                 pool_id,
                 tick_lower_price,
                 tick_upper_price,
                 amount_specified,
+                None,
                 config.slippage(),
                 false,
                 base_token0,
             )?;
+            println!(
+                "ui amount_0:{}, ui amount_1:{}",
+                pool_mints.ui_amount(&result.mint0, result.amount_0),
+                pool_mints.ui_amount(&result.mint1, result.amount_1)
+            );
+            let mut funds = common::FundsCheck::default();
+            funds.debit(deposit_token0, result.mint0, result.amount_0);
+            funds.debit(deposit_token1, result.mint1, result.amount_1);
+            clmm_utils::open_position_rent(
+                &mut funds,
+                &config.clmm_program(),
+                &pool_id,
+                &result,
+                traditional_nft,
+                with_metadata,
+            );
+            common::check_user_funds(&rpc_client, &payer_pubkey, &funds)?;
+            let mut instructions = Vec::new();
+            // the sol is wrapped into a temporary wsol account, which is closed at the end
+            let mut close_wsol_instructions = Vec::new();
             let deposit_token0 = if let Some(deposit_token0) = deposit_token0 {
                 deposit_token0
+            } else if result.mint0 == spl_token::native_mint::id() {
+                token::wrap_sol_temp_account(
+                    &payer_pubkey,
+                    result.amount_0,
+                    &mut instructions,
+                    &mut close_wsol_instructions,
+                )
             } else {
                 spl_associated_token_account::get_associated_token_address_with_program_id(
                     &payer_pubkey,
@@ -235,6 +304,13 @@ pub fn process_clmm_commands(
             };
             let deposit_token1 = if let Some(deposit_token1) = deposit_token1 {
                 deposit_token1
+            } else if result.mint1 == spl_token::native_mint::id() {
+                token::wrap_sol_temp_account(
+                    &payer_pubkey,
+                    result.amount_1,
+                    &mut instructions,
+                    &mut close_wsol_instructions,
+                )
             } else {
                 spl_associated_token_account::get_associated_token_address_with_program_id(
                     &payer_pubkey,
@@ -337,7 +413,9 @@ pub fn process_clmm_commands(
                         with_metadata,
                     )?
                 };
-                return Ok(Some(open_position_instr));
+                instructions.extend(open_position_instr);
+                instructions.extend(close_wsol_instructions);
+                return Ok(Some(instructions.into()));
             } else {
                 // personal position exist
                 panic!("personal position exist:{:?}", find_position);
@@ -352,19 +430,60 @@ pub fn process_clmm_commands(
             amount_specified,
             base_token1,
         } => {
+            clmm_utils::check_pool_operation(&rpc_client, &pool_id, PoolOperation::OpenPosition)?;
             let base_token0 = !base_token1;
+            let pool_mints = common::get_indexed_pool_mints(config, &pool_id, || {
+                clmm_utils::get_pool_mints(&rpc_client, &pool_id)
+            })?;
+            let amount_specified = if base_token0 {
+                common::resolve_token_amount(
+                    &rpc_client,
+                    &amount_specified,
+                    &payer_pubkey,
+                    &pool_mints.mint_0,
+                    deposit_token0.as_ref(),
+                )?
+            } else {
+                common::resolve_token_amount(
+                    &rpc_client,
+                    &amount_specified,
+                    &payer_pubkey,
+                    &pool_mints.mint_1,
+                    deposit_token1.as_ref(),
+                )?
+            };
             let result = clmm_utils::calculate_liquidity_change(
                 &rpc_client,
                 pool_id,
                 tick_lower_price,
                 tick_upper_price,
                 amount_specified,
+                None,
                 config.slippage(),
                 false,
                 base_token0,
             )?;
+            println!(
+                "ui amount_0:{}, ui amount_1:{}",
+                pool_mints.ui_amount(&result.mint0, result.amount_0),
+                pool_mints.ui_amount(&result.mint1, result.amount_1)
+            );
+            let mut funds = common::FundsCheck::default();
+            funds.debit(deposit_token0, result.mint0, result.amount_0);
+            funds.debit(deposit_token1, result.mint1, result.amount_1);
+            common::check_user_funds(&rpc_client, &payer_pubkey, &funds)?;
+            let mut instructions = Vec::new();
+            // the sol is wrapped into a temporary wsol account, which is closed at the end
+            let mut close_wsol_instructions = Vec::new();
             let deposit_token0 = if let Some(deposit_token0) = deposit_token0 {
                 deposit_token0
+            } else if result.mint0 == spl_token::native_mint::id() {
+                token::wrap_sol_temp_account(
+                    &payer_pubkey,
+                    result.amount_0,
+                    &mut instructions,
+                    &mut close_wsol_instructions,
+                )
             } else {
                 spl_associated_token_account::get_associated_token_address_with_program_id(
                     &payer_pubkey,
@@ -374,6 +493,13 @@ pub fn process_clmm_commands(
             };
             let deposit_token1 = if let Some(deposit_token1) = deposit_token1 {
                 deposit_token1
+            } else if result.mint1 == spl_token::native_mint::id() {
+                token::wrap_sol_temp_account(
+                    &payer_pubkey,
+                    result.amount_1,
+                    &mut instructions,
+                    &mut close_wsol_instructions,
+                )
             } else {
                 spl_associated_token_account::get_associated_token_address_with_program_id(
                     &payer_pubkey,
@@ -441,7 +567,9 @@ pub fn process_clmm_commands(
                     result.tick_array_lower_start_index,
                     result.tick_array_upper_start_index,
                 )?;
-                return Ok(Some(increase_instr));
+                instructions.extend(increase_instr);
+                instructions.extend(close_wsol_instructions);
+                return Ok(Some(instructions.into()));
             } else {
                 // personal position not exist
                 panic!("personal position exist:{:?}", find_position);
@@ -456,17 +584,51 @@ pub fn process_clmm_commands(
             amount_specified,
             base_token1,
         } => {
+            clmm_utils::check_pool_operation(&rpc_client, &pool_id, PoolOperation::Withdraw)?;
             let base_token0 = !base_token1;
+            let pool_mints = common::get_indexed_pool_mints(config, &pool_id, || {
+                clmm_utils::get_pool_mints(&rpc_client, &pool_id)
+            })?;
+            // the percentage and max refer to the liquidity of the position
+            let (amount_specified, liquidity) = if amount_specified.is_relative() {
+                let position_liquidity = clmm_utils::get_position_liquidity(
+                    &rpc_client,
+                    &config.clmm_program(),
+                    &payer_pubkey,
+                    &pool_id,
+                    tick_lower_price,
+                    tick_upper_price,
+                )?;
+                (0, amount_specified.share_of(position_liquidity))
+            } else {
+                let decimals = if base_token0 {
+                    pool_mints.decimals_0
+                } else {
+                    pool_mints.decimals_1
+                };
+                let amount = amount_specified.resolve(decimals, || {
+                    Err(format_err!(
+                        "the relative amounts refer to the position liquidity"
+                    ))
+                })?;
+                (amount, None)
+            };
             let result = clmm_utils::calculate_liquidity_change(
                 &rpc_client,
                 pool_id,
                 tick_lower_price,
                 tick_upper_price,
                 amount_specified,
+                liquidity,
                 config.slippage(),
                 true,
                 base_token0,
             )?;
+            println!(
+                "ui amount_0:{}, ui amount_1:{}",
+                pool_mints.ui_amount(&result.mint0, result.amount_0),
+                pool_mints.ui_amount(&result.mint1, result.amount_1)
+            );
             // load position
             let (_nft_tokens, positions) = clmm_utils::get_nft_accounts_and_positions_by_owner(
                 &rpc_client,
@@ -497,8 +659,17 @@ pub fn process_clmm_commands(
             }
             if find_position.nft_mint != Pubkey::default() && find_position.pool_id == pool_id {
                 let mut instructions = Vec::new();
+                // the sol is received by a temporary wsol account, which is closed at the end
+                let mut close_wsol_instructions = Vec::new();
                 let recipient_token0 = if let Some(recipient_token0) = recipient_token0 {
                     recipient_token0
+                } else if result.mint0 == spl_token::native_mint::id() {
+                    token::wrap_sol_temp_account(
+                        &payer_pubkey,
+                        0,
+                        &mut instructions,
+                        &mut close_wsol_instructions,
+                    )
                 } else {
                     // mint0 maybe token22
                     let create_user_token0_instr = token::create_ata_token_or_not(
@@ -517,6 +688,13 @@ pub fn process_clmm_commands(
                 };
                 let recipient_token1 = if let Some(recipient_token1) = recipient_token1 {
                     recipient_token1
+                } else if result.mint1 == spl_token::native_mint::id() {
+                    token::wrap_sol_temp_account(
+                        &payer_pubkey,
+                        0,
+                        &mut instructions,
+                        &mut close_wsol_instructions,
+                    )
                 } else {
                     // mint1 maybe token22
                     let create_user_token1_instr = token::create_ata_token_or_not(
@@ -596,7 +774,8 @@ pub fn process_clmm_commands(
                     result.tick_array_upper_start_index,
                 )?;
                 instructions.extend(decrease_instr);
-                return Ok(Some(instructions));
+                instructions.extend(close_wsol_instructions);
+                return Ok(Some(instructions.into()));
             } else {
                 // personal position not exist
                 panic!("personal position exist:{:?}", find_position);
@@ -610,7 +789,25 @@ pub fn process_clmm_commands(
             limit_price,
             base_out,
         } => {
+            clmm_utils::check_pool_operation(&rpc_client, &pool_id, PoolOperation::Swap)?;
             let base_in = !base_out;
+            if let Some(max_deviation_bps) = config.oracle_max_deviation_bps() {
+                let (price, reference_price) = match config.oracle_source() {
+                    common_types::OracleSource::Twap => {
+                        let pool_twap = clmm_utils::get_pool_twap(
+                            &rpc_client,
+                            &pool_id,
+                            config.oracle_twap_window(),
+                        )?;
+                        (pool_twap.spot_price, pool_twap.twap_price)
+                    }
+                    source => (
+                        clmm_utils::get_pool_price(&rpc_client, &pool_id)?,
+                        common::get_reference_price(&rpc_client, &source)?,
+                    ),
+                };
+                common::check_price_deviation(&pool_id, price, reference_price, max_deviation_bps)?;
+            }
             let tickarray_bitmap_extension = Pubkey::find_program_address(
                 &[
                     raydium_amm_v3::states::POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
@@ -619,6 +816,32 @@ pub fn process_clmm_commands(
                 &config.clmm_program(),
             )
             .0;
+            let slippage = config.swap_slippage(|| {
+                clmm_utils::get_price_movement_bps(
+                    &rpc_client,
+                    &pool_id,
+                    common_types::AUTO_SLIPPAGE_WINDOW,
+                )
+            });
+            let user_input_token = common::resolve_user_input_token(
+                &rpc_client,
+                config,
+                &user_input_token,
+                &payer_pubkey,
+            )?;
+            let pool_mints = common::get_indexed_pool_mints(config, &pool_id, || {
+                clmm_utils::get_pool_mints(&rpc_client, &pool_id)
+            })?;
+            let user_output_token =
+                common::resolve_user_output_token(config, user_output_token.as_ref(), &pool_mints)?;
+            let amount_specified = common::resolve_swap_amount(
+                &rpc_client,
+                &amount_specified,
+                &payer_pubkey,
+                &user_input_token,
+                &pool_mints,
+                base_out,
+            )?;
             let result = clmm_utils::calculate_swap_change(
                 &rpc_client,
                 config.clmm_program(),
@@ -628,12 +851,76 @@ pub fn process_clmm_commands(
                 amount_specified,
                 limit_price,
                 base_in,
-                config.slippage(),
+                slippage,
+            )?;
+            let symbols = common::get_token_symbols(
+                &rpc_client,
+                config,
+                &[result.input_vault_mint, result.output_vault_mint],
             )?;
+            println!(
+                "swap {} to {}",
+                common::mint_label(&symbols, &result.input_vault_mint),
+                common::mint_label(&symbols, &result.output_vault_mint)
+            );
+            println!(
+                "amount_specified:{}, other_amount_threshold:{}, price_impact_bps:{}, slippage_bps:{}, auto_slippage:{}",
+                result.amount,
+                result.other_amount_threshold,
+                result.price_impact_bps,
+                result.slippage_bps,
+                config.auto_slippage()
+            );
+            let (specified_mint, other_mint) = if base_in {
+                (result.input_vault_mint, result.output_vault_mint)
+            } else {
+                (result.output_vault_mint, result.input_vault_mint)
+            };
+            println!(
+                "ui amount_specified:{}, ui other_amount_threshold:{}",
+                pool_mints.ui_amount(&specified_mint, result.amount),
+                pool_mints.ui_amount(&other_mint, result.other_amount_threshold)
+            );
+            let mut funds = common::FundsCheck::default();
+            funds.debit(
+                Some(result.user_input_token),
+                result.input_vault_mint,
+                if base_in {
+                    result.amount
+                } else {
+                    result.other_amount_threshold
+                },
+            );
+            funds.credit(user_output_token, result.output_vault_mint);
+            common::check_user_funds(&rpc_client, &payer_pubkey, &funds)?;
 
             let mut instructions = Vec::new();
+            // the sol is wrapped into a temporary wsol account, which is closed at the end
+            let mut close_wsol_instructions = Vec::new();
+            let user_input_token = if result.user_input_token == spl_token::native_mint::id() {
+                let amount = if base_in {
+                    result.amount
+                } else {
+                    result.other_amount_threshold
+                };
+                token::wrap_sol_temp_account(
+                    &payer_pubkey,
+                    amount,
+                    &mut instructions,
+                    &mut close_wsol_instructions,
+                )
+            } else {
+                result.user_input_token
+            };
             let user_output_token = if let Some(user_output_token) = user_output_token {
                 user_output_token
+            } else if result.output_vault_mint == spl_token::native_mint::id() {
+                token::wrap_sol_temp_account(
+                    &payer_pubkey,
+                    0,
+                    &mut instructions,
+                    &mut close_wsol_instructions,
+                )
             } else {
                 let create_user_output_token_instr = token::create_ata_token_or_not(
                     &payer_pubkey,
@@ -665,7 +952,7 @@ pub fn process_clmm_commands(
                 result.input_vault,
                 result.output_vault,
                 result.pool_observation,
-                result.user_input_token,
+                user_input_token,
                 user_output_token,
                 result.input_vault_mint,
                 result.output_vault_mint,
@@ -676,14 +963,38 @@ pub fn process_clmm_commands(
                 result.is_base_input,
             )?;
             instructions.extend(swap_instr);
-            return Ok(Some(instructions));
+            instructions.extend(close_wsol_instructions);
+            return Ok(Some(common_types::CommandInstructions {
+                instructions,
+                // the receipt recovers the quote with the applied slippage
+                slippage_bps: Some(result.slippage_bps),
+            }));
         }
         ClmmCommands::FetchPool {
             pool_id,
             mint0,
             mint1,
+            raw,
         } => {
+            let mint0 = mint0.map(|mint| mint.resolve(config)).transpose()?;
+            let mint1 = mint1.map(|mint| mint.resolve(config)).transpose()?;
             if let Some(pool_id) = pool_id {
+                if !raw {
+                    // summarize specified pool
+                    let summary = clmm_utils::get_pool_summary(
+                        &rpc_client,
+                        &config.clmm_program(),
+                        &pool_id,
+                    )?;
+                    let mints = summary.snapshot.mints;
+                    let symbols = common::get_token_symbols(
+                        &rpc_client,
+                        config,
+                        &[mints.mint_0, mints.mint_1],
+                    )?;
+                    common::print_pool_summary(&summary, &symbols);
+                    return Ok(None);
+                }
                 // fetch specified pool
                 let pool_state = rpc::get_anchor_account::<raydium_amm_v3::states::PoolState>(
                     &rpc_client,
@@ -692,9 +1003,22 @@ pub fn process_clmm_commands(
                 .unwrap()
                 .unwrap();
                 println!("{:#?}", pool_state);
+                let symbols = common::get_token_symbols(
+                    &rpc_client,
+                    config,
+                    &[pool_state.token_mint_0, pool_state.token_mint_1],
+                )?;
+                println!(
+                    "mint0:{}, mint1:{}",
+                    common::mint_label(&symbols, &pool_state.token_mint_0),
+                    common::mint_label(&symbols, &pool_state.token_mint_1)
+                );
             } else {
-                // fetch pools by filters
+                // fetch pools by filters, or from the pool index in either order of the mints
                 let pool_len = raydium_amm_v3::states::PoolState::LEN as u64;
+                let pool_index = config
+                    .pool_index()
+                    .filter(|_| mint0.is_some() || mint1.is_some());
                 let filters = match (mint0, mint1) {
                     (None, None) => Some(vec![RpcFilterType::DataSize(pool_len)]),
                     (Some(mint0), None) => Some(vec![
@@ -723,20 +1047,50 @@ pub fn process_clmm_commands(
                         RpcFilterType::DataSize(pool_len),
                     ]),
                 };
-                let pools = rpc::get_program_accounts_with_filters(
-                    &rpc_client,
-                    config.clmm_program(),
-                    filters,
-                )
-                .unwrap();
-                for pool in pools {
+                let pools = if let Some(path) = pool_index {
+                    common::fetch_indexed_pools(
+                        &rpc_client,
+                        config,
+                        &path,
+                        PoolProgram::Clmm,
+                        mint0.as_ref(),
+                        mint1.as_ref(),
+                        |index| clmm_utils::refresh_pool_index(&rpc_client, config, &path, index),
+                    )?
+                } else {
+                    rpc::get_program_accounts_with_filters(
+                        &rpc_client,
+                        config.clmm_program(),
+                        filters,
+                    )
+                    .unwrap()
+                };
+                let pool_states: Vec<_> =
+                    pools
+                        .iter()
+                        .map(|pool| {
+                            common_utils::deserialize_anchor_account::<
+                                raydium_amm_v3::states::PoolState,
+                            >(&pool.1)
+                        })
+                        .collect();
+                // the symbols of all the pools are fetched in batches
+                let mut mints = Vec::new();
+                for pool_state in pool_states.iter().flatten() {
+                    mints.push(pool_state.token_mint_0);
+                    mints.push(pool_state.token_mint_1);
+                }
+                let symbols = common::get_token_symbols(&rpc_client, config, &mints)?;
+                for (pool, pool_state) in pools.iter().zip(pool_states) {
                     println!("pool_id:{}", pool.0);
-                    println!(
-                        "{:#?}",
-                        common_utils::deserialize_anchor_account::<raydium_amm_v3::states::PoolState>(
-                            &pool.1
-                        )
-                    );
+                    println!("{:#?}", pool_state);
+                    if let Some(pool_state) = pool_state.ok() {
+                        println!(
+                            "mint0:{}, mint1:{}",
+                            common::mint_label(&symbols, &pool_state.token_mint_0),
+                            common::mint_label(&symbols, &pool_state.token_mint_1)
+                        );
+                    }
                 }
             }
             return Ok(None);
@@ -0,0 +1,320 @@
+use crate::{common_utils, indexer::PoolMints};
+use anyhow::{format_err, Result};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use std::str::FromStr;
+
+/// The lamports left in the wallet for fees and rent when spending the balance of sol.
+pub const SOL_BALANCE_RESERVE: u64 = 10_000_000;
+
+/// The amount argument of the commands.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AmountInput {
+    /// The amount in base units, marked by the `raw` suffix, e.g. `1500000raw`.
+    Raw(u64),
+    /// The amount in ui units scaled by the decimals of the mint, e.g. `1.5` or `2`.
+    Ui(String),
+    /// The percentage in bps of the balance or liquidity, e.g. `50%`.
+    Percent(u64),
+    /// The whole balance or liquidity.
+    Max,
+}
+
+impl FromStr for AmountInput {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("max") {
+            return Ok(AmountInput::Max);
+        }
+        if let Some(percent) = s.strip_suffix('%') {
+            let bps = ui_amount_to_raw(percent.trim(), 2)?;
+            if bps == 0 || bps > 10000 {
+                return Err(format_err!("percentage {} out of range (0%, 100%]", s));
+            }
+            return Ok(AmountInput::Percent(bps));
+        }
+        if let Some(raw) = s.strip_suffix("raw") {
+            return raw
+                .trim()
+                .parse::<u64>()
+                .map(AmountInput::Raw)
+                .map_err(|_| format_err!("invalid raw amount {}, expect 1500000raw", s));
+        }
+        // the decimals are known once the mint is fetched
+        split_decimal(s)
+            .map_err(|_| format_err!("invalid amount {}, expect 1.5, 1500000raw, 50% or max", s))?;
+        Ok(AmountInput::Ui(s.to_string()))
+    }
+}
+
+impl AmountInput {
+    /// Percentage and max refer to the balance or liquidity.
+    pub fn is_relative(&self) -> bool {
+        matches!(self, AmountInput::Percent(_) | AmountInput::Max)
+    }
+
+    /// The share of `total` for the percentage and max, none for the other amounts.
+    pub fn share_of(&self, total: u128) -> Option<u128> {
+        match self {
+            AmountInput::Percent(bps) => Some(total * (*bps as u128) / 10000),
+            AmountInput::Max => Some(total),
+            _ => None,
+        }
+    }
+
+    /// Resolve into base units, `total` is only called for the percentage and max.
+    pub fn resolve(&self, decimals: u8, total: impl FnOnce() -> Result<u64>) -> Result<u64> {
+        let amount = match self {
+            AmountInput::Raw(amount) => *amount,
+            AmountInput::Ui(amount) => ui_amount_to_raw(amount, decimals)?,
+            AmountInput::Percent(_) | AmountInput::Max => {
+                self.share_of(total()? as u128).unwrap() as u64
+            }
+        };
+        if amount == 0 {
+            return Err(format_err!("amount {:?} resolves to zero", self));
+        }
+        Ok(amount)
+    }
+}
+
+/// Split the decimal string into the integer and the fraction without trailing zeros.
+fn split_decimal(ui_amount: &str) -> Result<(&str, &str)> {
+    let (integer, fraction) = ui_amount.split_once('.').unwrap_or((ui_amount, ""));
+    if integer.is_empty() && fraction.is_empty()
+        || !integer
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(format_err!("invalid amount {}", ui_amount));
+    }
+    Ok((integer, fraction.trim_end_matches('0')))
+}
+
+/// Convert the decimal string into base units without the rounding of floats.
+pub fn ui_amount_to_raw(ui_amount: &str, decimals: u8) -> Result<u64> {
+    let (integer, fraction) = split_decimal(ui_amount)?;
+    if fraction.len() > decimals as usize {
+        return Err(format_err!(
+            "amount {} has more than {} decimals",
+            ui_amount,
+            decimals
+        ));
+    }
+    let digits = format!(
+        "{}{:0<width$}",
+        integer,
+        fraction,
+        width = decimals as usize
+    );
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    digits
+        .parse::<u64>()
+        .map_err(|_| format_err!("amount {} overflows u64", ui_amount))
+}
+
+/// Format the base units as a decimal string, e.g. `1500000` with 6 decimals is `1.5`.
+pub fn raw_amount_to_ui(amount: u64, decimals: u8) -> String {
+    let decimals = decimals as usize;
+    let digits = format!("{:0>width$}", amount, width = decimals + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        integer.to_string()
+    } else {
+        format!("{}.{}", integer, fraction)
+    }
+}
+
+impl PoolMints {
+    /// Format the amount of the pool mint in ui units.
+    pub fn ui_amount(&self, mint: &Pubkey, amount: u64) -> String {
+        let decimals = if *mint == self.mint_0 {
+            self.decimals_0
+        } else {
+            self.decimals_1
+        };
+        raw_amount_to_ui(amount, decimals)
+    }
+}
+
+pub fn get_mint_decimals(rpc_client: &RpcClient, mint: &Pubkey) -> Result<u8> {
+    let account = rpc_client.get_account(mint)?;
+    Ok(common_utils::unpack_mint(&account.data)?.base.decimals)
+}
+
+/// The balance of the token account, the associated token account of the owner if not given,
+/// or the sol of the wallet less `SOL_BALANCE_RESERVE` for the native mint.
+pub fn get_user_balance(
+    rpc_client: &RpcClient,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_account: Option<&Pubkey>,
+) -> Result<u64> {
+    let native_mint = spl_token::native_mint::id();
+    let token_account = match token_account {
+        Some(token_account) if *token_account != native_mint => *token_account,
+        None if *mint != native_mint => {
+            let token_program = rpc_client.get_account(mint)?.owner;
+            get_associated_token_address_with_program_id(owner, mint, &token_program)
+        }
+        _ => {
+            let lamports = rpc_client.get_balance(owner)?;
+            return Ok(lamports.saturating_sub(SOL_BALANCE_RESERVE));
+        }
+    };
+    let account = rpc_client
+        .get_account(&token_account)
+        .map_err(|_| format_err!("token account {} not found", token_account))?;
+    Ok(common_utils::unpack_token(&account.data)?.base.amount)
+}
+
+/// Resolve the amount of the mint, the percentage and max refer to the balance of the user.
+pub fn resolve_token_amount(
+    rpc_client: &RpcClient,
+    amount: &AmountInput,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_account: Option<&Pubkey>,
+) -> Result<u64> {
+    if let AmountInput::Raw(amount) = amount {
+        return Ok(*amount);
+    }
+    let decimals = get_mint_decimals(rpc_client, mint)?;
+    let amount = amount.resolve(decimals, || {
+        get_user_balance(rpc_client, owner, mint, token_account)
+    })?;
+    println!(
+        "resolved amount:{} ({} ui) of mint {}",
+        amount,
+        raw_amount_to_ui(amount, decimals),
+        mint
+    );
+    Ok(amount)
+}
+
+/// Resolve the amount of the swap, specified in the input token or, for `base_out`, the output token.
+/// The percentage and max refer to the balance of the input token, so only apply to `base_in`.
+pub fn resolve_swap_amount(
+    rpc_client: &RpcClient,
+    amount: &AmountInput,
+    owner: &Pubkey,
+    user_input_token: &Pubkey,
+    pool_mints: &PoolMints,
+    base_out: bool,
+) -> Result<u64> {
+    if let AmountInput::Raw(amount) = amount {
+        return Ok(*amount);
+    }
+    if base_out && amount.is_relative() {
+        return Err(format_err!(
+            "percentage and max amounts only apply to the input of the swap"
+        ));
+    }
+    let input_account = if *user_input_token == spl_token::native_mint::id() {
+        None
+    } else {
+        Some(rpc_client.get_account(user_input_token)?)
+    };
+    let input_mint = common_utils::get_user_token_mint(user_input_token, input_account.as_ref())?;
+    let specified_mint = if !base_out {
+        input_mint
+    } else if input_mint == pool_mints.mint_0 {
+        pool_mints.mint_1
+    } else {
+        pool_mints.mint_0
+    };
+    resolve_token_amount(
+        rpc_client,
+        amount,
+        owner,
+        &specified_mint,
+        Some(user_input_token),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ui_amount_to_raw() {
+        assert_eq!(ui_amount_to_raw("1.5", 6).unwrap(), 1_500_000);
+        assert_eq!(ui_amount_to_raw("2", 9).unwrap(), 2_000_000_000);
+        assert_eq!(ui_amount_to_raw(".25", 2).unwrap(), 25);
+        assert_eq!(ui_amount_to_raw("1.", 0).unwrap(), 1);
+        assert_eq!(ui_amount_to_raw("0.100", 1).unwrap(), 1);
+        assert_eq!(ui_amount_to_raw("0", 6).unwrap(), 0);
+        assert!(ui_amount_to_raw("1.234", 2).is_err());
+        assert!(ui_amount_to_raw("18446744073709551616", 0).is_err());
+        assert!(ui_amount_to_raw("1e6", 6).is_err());
+        assert!(ui_amount_to_raw("-1", 6).is_err());
+        assert!(ui_amount_to_raw(".", 6).is_err());
+    }
+
+    #[test]
+    fn test_raw_amount_to_ui() {
+        assert_eq!(raw_amount_to_ui(1_500_000, 6), "1.5");
+        assert_eq!(raw_amount_to_ui(2_000_000_000, 9), "2");
+        assert_eq!(raw_amount_to_ui(25, 2), "0.25");
+        assert_eq!(raw_amount_to_ui(1, 9), "0.000000001");
+        assert_eq!(raw_amount_to_ui(0, 6), "0");
+        assert_eq!(raw_amount_to_ui(123, 0), "123");
+    }
+
+    #[test]
+    fn test_amount_input_from_str() {
+        assert_eq!(
+            AmountInput::from_str("1").unwrap(),
+            AmountInput::Ui("1".to_string())
+        );
+        assert_eq!(
+            AmountInput::from_str("1.5").unwrap(),
+            AmountInput::Ui("1.5".to_string())
+        );
+        assert_eq!(
+            AmountInput::from_str("1500000raw").unwrap(),
+            AmountInput::Raw(1_500_000)
+        );
+        assert_eq!(
+            AmountInput::from_str("50%").unwrap(),
+            AmountInput::Percent(5000)
+        );
+        assert_eq!(
+            AmountInput::from_str("0.5%").unwrap(),
+            AmountInput::Percent(50)
+        );
+        assert_eq!(AmountInput::from_str("MAX").unwrap(), AmountInput::Max);
+        assert!(AmountInput::from_str("1.5raw").is_err());
+        assert!(AmountInput::from_str("0%").is_err());
+        assert!(AmountInput::from_str("101%").is_err());
+        assert!(AmountInput::from_str("abc").is_err());
+        assert!(AmountInput::from_str("").is_err());
+    }
+
+    #[test]
+    fn test_amount_input_resolve() {
+        let unused = || -> Result<u64> { Err(format_err!("no balance")) };
+        assert_eq!(
+            AmountInput::from_str("1")
+                .unwrap()
+                .resolve(6, unused)
+                .unwrap(),
+            1_000_000
+        );
+        assert_eq!(AmountInput::Raw(1000).resolve(6, unused).unwrap(), 1000);
+        assert_eq!(
+            AmountInput::Percent(2500).resolve(6, || Ok(400)).unwrap(),
+            100
+        );
+        assert_eq!(AmountInput::Max.resolve(6, || Ok(400)).unwrap(), 400);
+        assert!(AmountInput::Max.resolve(6, unused).is_err());
+        assert!(AmountInput::Raw(0).resolve(6, unused).is_err());
+    }
+}
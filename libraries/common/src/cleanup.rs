@@ -0,0 +1,167 @@
+use crate::{amount::raw_amount_to_ui, common_utils, rpc, token};
+use anyhow::Result;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::Instruction, message::Message, packet::PACKET_DATA_SIZE, pubkey::Pubkey,
+    transaction::Transaction,
+};
+use spl_token_2022::{
+    extension::transfer_fee::TransferFeeAmount, state::Account as TokenAccount, state::AccountState,
+};
+
+/// A token account of the wallet that can be closed, the wsol accounts with their sol.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClosableTokenAccount {
+    pub key: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    /// The lamports reclaimed by closing the account, the rent and the sol of the wsol accounts.
+    pub lamports: u64,
+}
+
+/// A token account of the wallet that is not closed, with the reason.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SkippedTokenAccount {
+    pub key: Pubkey,
+    pub reason: String,
+}
+
+/// The empty token accounts of the owner under the spl token and Token-2022 programs, and the
+/// wsol accounts which are closed with their sol. The accounts of the position nfts, whose mint
+/// has a personal position at `personal_position_key`, and the accounts the owner can not close
+/// are skipped with the reason.
+pub fn get_closable_token_accounts(
+    rpc_client: &RpcClient,
+    owner: &Pubkey,
+    personal_position_key: impl Fn(&Pubkey) -> Pubkey,
+) -> Result<(Vec<ClosableTokenAccount>, Vec<SkippedTokenAccount>)> {
+    let mut candidates = Vec::new();
+    let mut skipped = Vec::new();
+    for token_program in [spl_token::id(), spl_token_2022::id()] {
+        for (key, account) in rpc::get_token_accounts_by_owner(rpc_client, owner, &token_program)? {
+            let token_state = common_utils::unpack_token(&account.data)?;
+            let is_native = token_state.base.is_native();
+            if token_state.base.amount > 0 && !is_native {
+                continue;
+            }
+            if token_state.base.state == AccountState::Frozen {
+                skipped.push(SkippedTokenAccount {
+                    key,
+                    reason: "frozen".to_string(),
+                });
+                continue;
+            }
+            if let Some(close_authority) = Option::<Pubkey>::from(token_state.base.close_authority)
+            {
+                if close_authority != *owner {
+                    skipped.push(SkippedTokenAccount {
+                        key,
+                        reason: format!("close authority {}", close_authority),
+                    });
+                    continue;
+                }
+            }
+            if let Some(transfer_fee_amount) =
+                common_utils::find_extension::<TokenAccount, TransferFeeAmount>(&token_state)
+            {
+                let withheld_amount = u64::from(transfer_fee_amount.withheld_amount);
+                if withheld_amount > 0 {
+                    skipped.push(SkippedTokenAccount {
+                        key,
+                        reason: format!("withheld transfer fee {} to harvest", withheld_amount),
+                    });
+                    continue;
+                }
+            }
+            candidates.push(ClosableTokenAccount {
+                key,
+                mint: token_state.base.mint,
+                token_program,
+                lamports: account.lamports,
+            });
+        }
+    }
+
+    let mut mints: Vec<Pubkey> = candidates.iter().map(|account| account.mint).collect();
+    mints.sort();
+    mints.dedup();
+    let mut nft_mints = Vec::new();
+    for chunk in mints.chunks(100) {
+        let position_keys: Vec<Pubkey> = chunk.iter().map(&personal_position_key).collect();
+        for (mint, position_account) in chunk
+            .iter()
+            .zip(rpc_client.get_multiple_accounts(&position_keys)?)
+        {
+            if position_account.is_some() {
+                nft_mints.push(*mint);
+            }
+        }
+    }
+    let (nft_accounts, closable): (Vec<_>, Vec<_>) = candidates
+        .into_iter()
+        .partition(|account| nft_mints.contains(&account.mint));
+    skipped.extend(nft_accounts.into_iter().map(|account| SkippedTokenAccount {
+        key: account.key,
+        reason: format!("position nft mint {}", account.mint),
+    }));
+    Ok((closable, skipped))
+}
+
+/// The instructions closing the token accounts, the lamports go to the owner.
+pub fn close_token_account_instructions(
+    owner: &Pubkey,
+    accounts: &[ClosableTokenAccount],
+) -> Vec<Instruction> {
+    accounts
+        .iter()
+        .flat_map(|account| {
+            token::close_spl_account(&account.key, owner, owner, Some(&account.token_program))
+        })
+        .collect()
+}
+
+/// Split the instructions into as few transactions of the fee payer as the packet size allows.
+pub fn batch_instructions(
+    instructions: &[Instruction],
+    fee_payer: &Pubkey,
+) -> Vec<Vec<Instruction>> {
+    let mut batches: Vec<Vec<Instruction>> = Vec::new();
+    let mut batch: Vec<Instruction> = Vec::new();
+    for instruction in instructions {
+        batch.push(instruction.clone());
+        let txn = Transaction::new_unsigned(Message::new(&batch, Some(fee_payer)));
+        if bincode::serialized_size(&txn).unwrap() as usize > PACKET_DATA_SIZE && batch.len() > 1 {
+            let instruction = batch.pop().unwrap();
+            batches.push(batch);
+            batch = vec![instruction];
+        }
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+    batches
+}
+
+pub fn print_closable_token_accounts(
+    accounts: &[ClosableTokenAccount],
+    skipped: &[SkippedTokenAccount],
+) {
+    for account in skipped {
+        println!("skip token account:{}, {}", account.key, account.reason);
+    }
+    for account in accounts {
+        println!(
+            "close token account:{}, mint:{}, reclaim:{} sol",
+            account.key,
+            account.mint,
+            raw_amount_to_ui(account.lamports, 9)
+        );
+    }
+    let lamports: u64 = accounts.iter().map(|account| account.lamports).sum();
+    println!(
+        "{} token accounts to close, {} sol to reclaim, {} skipped",
+        accounts.len(),
+        raw_amount_to_ui(lamports, 9),
+        skipped.len()
+    );
+}
@@ -1,19 +1,25 @@
+use crate::common_utils;
 use anchor_client::Cluster;
-use anyhow::Result;
-use clap::Parser;
-use solana_sdk::pubkey::Pubkey;
+use anyhow::{format_err, Result};
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signer::Signer};
 use spl_token_2022::extension::{
     confidential_transfer::{ConfidentialTransferAccount, ConfidentialTransferMint},
+    confidential_transfer_fee::{ConfidentialTransferFeeAmount, ConfidentialTransferFeeConfig},
     cpi_guard::CpiGuard,
     default_account_state::DefaultAccountState,
     immutable_owner::ImmutableOwner,
     interest_bearing_mint::InterestBearingConfig,
     memo_transfer::MemoTransfer,
+    metadata_pointer::MetadataPointer,
     mint_close_authority::MintCloseAuthority,
     non_transferable::{NonTransferable, NonTransferableAccount},
     permanent_delegate::PermanentDelegate,
     transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+    transfer_hook::{TransferHook, TransferHookAccount},
 };
+use spl_token_metadata_interface::state::TokenMetadata;
 use std::{convert::TryInto, str::FromStr};
 use toml::Value;
 
@@ -41,6 +47,64 @@ pub enum ExtensionStruct {
     PermanentDelegate(PermanentDelegate),
     TransferFeeConfig(TransferFeeConfig),
     TransferFeeAmount(TransferFeeAmount),
+    TransferHook(TransferHook),
+    TransferHookAccount(TransferHookAccount),
+    ConfidentialTransferFeeConfig(ConfidentialTransferFeeConfig),
+    ConfidentialTransferFeeAmount(ConfidentialTransferFeeAmount),
+    MetadataPointer(MetadataPointer),
+    TokenMetadata(TokenMetadata),
+    GroupPointer(GroupPointer),
+    TokenGroup(TokenGroup),
+    GroupMemberPointer(GroupMemberPointer),
+    TokenGroupMember(TokenGroupMember),
+    ScaledUiAmount(ScaledUiAmountConfig),
+    /// The extension type newer than the supported ones.
+    Unknown(u16),
+}
+
+/// The extension types added to token 2022 after the spl-token-2022 version in use,
+/// which are decoded from their raw tlv entries.
+pub const EXTENSION_TYPE_GROUP_POINTER: u16 = 20;
+pub const EXTENSION_TYPE_TOKEN_GROUP: u16 = 21;
+pub const EXTENSION_TYPE_GROUP_MEMBER_POINTER: u16 = 22;
+pub const EXTENSION_TYPE_TOKEN_GROUP_MEMBER: u16 = 23;
+pub const EXTENSION_TYPE_SCALED_UI_AMOUNT: u16 = 25;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GroupPointer {
+    pub authority: Option<Pubkey>,
+    pub group_address: Option<Pubkey>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TokenGroup {
+    pub update_authority: Option<Pubkey>,
+    pub mint: Pubkey,
+    pub size: u64,
+    pub max_size: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GroupMemberPointer {
+    pub authority: Option<Pubkey>,
+    pub member_address: Option<Pubkey>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TokenGroupMember {
+    pub mint: Pubkey,
+    pub group: Pubkey,
+    pub member_number: u64,
+}
+
+/// The ui amount is the raw amount scaled by the multiplier, which is replaced by the new
+/// multiplier from the timestamp.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScaledUiAmountConfig {
+    pub authority: Option<Pubkey>,
+    pub multiplier: f64,
+    pub new_multiplier_effective_timestamp: i64,
+    pub new_multiplier: f64,
 }
 
 pub const TEN_THOUSAND: u128 = 10000;
@@ -56,6 +120,95 @@ pub enum InstructionDecodeType {
     Base64,
     Base58,
 }
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum TxnEncoding {
+    Base58,
+    Base64,
+}
+
+/// The reference price source of the oracle guard.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OracleSource {
+    /// The observation twap of the pool itself.
+    Twap,
+    /// The pyth price accounts of token 0 and token 1, token 1 is priced 1 if none is given.
+    Pyth(Pubkey, Option<Pubkey>),
+    /// A file containing the price of token 0 quoted in token 1.
+    File(String),
+}
+
+impl FromStr for OracleSource {
+    type Err = anyhow::Error;
+
+    /// Parse `twap`, `pyth:<ACCOUNT_0>[,<ACCOUNT_1>]` or `file:<PATH>`.
+    fn from_str(s: &str) -> Result<Self> {
+        if s == "twap" {
+            return Ok(OracleSource::Twap);
+        }
+        if let Some(accounts) = s.strip_prefix("pyth:") {
+            let mut accounts = accounts.split(',');
+            let account_0 = Pubkey::from_str(accounts.next().unwrap_or_default())?;
+            let account_1 = accounts.next().map(Pubkey::from_str).transpose()?;
+            return Ok(OracleSource::Pyth(account_0, account_1));
+        }
+        if let Some(path) = s.strip_prefix("file:") {
+            return Ok(OracleSource::File(path.to_string()));
+        }
+        Err(format_err!("invalid oracle source {}", s))
+    }
+}
+
+/// The floor of the auto slippage in bps.
+pub const MIN_AUTO_SLIPPAGE_BPS: u64 = 10;
+/// The window in seconds of the recent price movement the auto slippage is derived from.
+pub const AUTO_SLIPPAGE_WINDOW: u64 = 300;
+
+/// The slippage applied to the quote of a swap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Slippage {
+    /// The `slippage_bps` of the config.
+    Fixed(u64),
+    /// The price impact of the trade plus the recent price movement of the pool, bounded by
+    /// `MIN_AUTO_SLIPPAGE_BPS` and `max_slippage_bps`.
+    Auto {
+        price_movement_bps: u64,
+        max_slippage_bps: u64,
+    },
+}
+
+impl Slippage {
+    /// The slippage in bps of the trade with the price impact.
+    pub fn bps(&self, price_impact_bps: u64) -> u64 {
+        match *self {
+            Slippage::Fixed(slippage_bps) => slippage_bps,
+            Slippage::Auto {
+                price_movement_bps,
+                max_slippage_bps,
+            } => price_impact_bps
+                .saturating_add(price_movement_bps)
+                .max(MIN_AUTO_SLIPPAGE_BPS)
+                .min(max_slippage_bps),
+        }
+    }
+}
+
+/// The instructions of a command, with the slippage in bps applied to the quote if it swaps.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommandInstructions {
+    pub instructions: Vec<Instruction>,
+    pub slippage_bps: Option<u64>,
+}
+
+impl From<Vec<Instruction>> for CommandInstructions {
+    fn from(instructions: Vec<Instruction>) -> Self {
+        CommandInstructions {
+            instructions,
+            slippage_bps: None,
+        }
+    }
+}
+
 pub const PROGRAM_LOG: &str = "Program log: ";
 pub const PROGRAM_DATA: &str = "Program data: ";
 pub const RAY_LOG: &str = "ray_log: ";
@@ -78,8 +231,84 @@ pub struct CommonConfig {
     openbook_program: Option<Pubkey>,
     #[clap(global = true, long = "config.slippage")]
     slippage_bps: Option<u64>,
+    /// Derive the slippage of each swap from its price impact and the recent price movement
+    /// of the pool instead of the fixed slippage.
+    #[clap(global = true, long, action)]
+    auto_slippage: bool,
+    /// The cap of the auto slippage.
+    #[clap(global = true, long = "config.max_slippage")]
+    max_slippage_bps: Option<u64>,
     #[clap(global = true, short, long, action)]
     simulate: bool,
+    /// Build the transaction without the wallet signature and export it for offline signing.
+    #[clap(global = true, long, action)]
+    sign_only: bool,
+    /// The encoding of the exported transaction.
+    #[clap(global = true, long = "config.encoding", value_enum)]
+    encoding: Option<TxnEncoding>,
+    /// The file the exported transaction written to. If none is given, print to stdout.
+    #[clap(global = true, long = "config.output")]
+    output_path: Option<String>,
+    /// The durable nonce account used instead of the latest blockhash.
+    #[clap(global = true, long = "config.nonce_account")]
+    nonce_account: Option<Pubkey>,
+    /// The keypair file of the nonce authority. If none is given, the wallet is the nonce authority.
+    #[clap(global = true, long = "config.nonce_authority")]
+    nonce_authority: Option<String>,
+    /// The pubkey of the wallet in the sign-only mode, the wallet keypair is not read and the
+    /// fee payer signs offline.
+    #[clap(global = true, long = "config.fee_payer")]
+    fee_payer: Option<Pubkey>,
+    /// Send the transaction without the preflight simulation.
+    #[clap(global = true, long, action)]
+    skip_preflight: bool,
+    /// How many times the transaction is re-quoted and re-signed after the blockhash expired.
+    #[clap(global = true, long = "config.max_retries")]
+    max_retries: Option<u64>,
+    /// The reference price of the oracle guard: `twap`, `pyth:<ACCOUNT_0>[,<ACCOUNT_1>]` or `file:<PATH>`.
+    #[clap(global = true, long = "config.oracle")]
+    oracle_source: Option<OracleSource>,
+    /// Refuse to swap when the pool price deviates more than the bps from the reference price.
+    /// If none is given, the oracle guard is disabled.
+    #[clap(global = true, long = "config.oracle_max_deviation")]
+    oracle_max_deviation_bps: Option<u64>,
+    /// The window in seconds of the twap reference price.
+    #[clap(global = true, long = "config.oracle_twap_window")]
+    oracle_twap_window: Option<u64>,
+    /// Ask for the confirmation before trading the mints with risky configurations.
+    #[clap(global = true, long, action)]
+    confirm_risky_mint: bool,
+    /// The transfer fee in bps above which the mint is flagged as risky.
+    #[clap(global = true, long = "config.max_transfer_fee")]
+    max_transfer_fee_bps: Option<u64>,
+    /// The toml file of the token symbols overriding the well-known ones, e.g. `USDC = "<MINT>"`.
+    #[clap(global = true, long = "config.token_list")]
+    token_list_path: Option<String>,
+    /// The csv file of the local pool index. If none is given, the pools are fetched from the chain.
+    #[clap(global = true, long = "config.pool_index")]
+    pool_index_path: Option<String>,
+    /// The seconds after which the pools of a program in the index are refreshed.
+    #[clap(global = true, long = "config.pool_index_ttl")]
+    pool_index_ttl: Option<u64>,
+    /// Print the rent, creation fees and network fees of the transaction before sending it.
+    #[clap(global = true, long, action)]
+    estimate_cost: bool,
+    /// Preview the token balance changes, the rent and the fees of the transaction and ask for the
+    /// confirmation before sending it.
+    #[clap(global = true, long, action)]
+    confirm: bool,
+    /// Send without asking for the confirmation, for the non-interactive runs.
+    #[clap(global = true, long, action)]
+    yes: bool,
+    /// Simulate the swap before sending it and re-quote, or abort after the retries, when the
+    /// simulated amount is off the quote by more than the bps. If none is given, the quote is not
+    /// verified.
+    #[clap(global = true, long = "config.quote_tolerance")]
+    quote_tolerance_bps: Option<u64>,
+    /// The recent price movement of the swap pool read once per command, so the re-quotes of the
+    /// auto slippage don't read it again.
+    #[clap(skip)]
+    price_movement_bps: Option<u64>,
 }
 
 impl Default for CommonConfig {
@@ -104,7 +333,30 @@ impl Default for CommonConfig {
                 Pubkey::from_str("srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX").unwrap(),
             ),
             slippage_bps: Some(100),
+            auto_slippage: false,
+            max_slippage_bps: Some(300),
             simulate: false,
+            sign_only: false,
+            encoding: Some(TxnEncoding::Base64),
+            output_path: None,
+            nonce_account: None,
+            nonce_authority: None,
+            fee_payer: None,
+            skip_preflight: false,
+            max_retries: Some(3),
+            oracle_source: Some(OracleSource::Twap),
+            oracle_max_deviation_bps: None,
+            oracle_twap_window: Some(300),
+            confirm_risky_mint: false,
+            max_transfer_fee_bps: Some(100),
+            token_list_path: None,
+            pool_index_path: None,
+            pool_index_ttl: Some(3600),
+            estimate_cost: false,
+            confirm: false,
+            yes: false,
+            quote_tolerance_bps: None,
+            price_movement_bps: None,
         }
     }
     #[cfg(feature = "devnet")]
@@ -128,7 +380,30 @@ impl Default for CommonConfig {
                 Pubkey::from_str("EoTcMgcDRTJVZDMZWBoU6rhYHZfkNTVEAfz3uUJRcYGj").unwrap(),
             ),
             slippage_bps: Some(100),
+            auto_slippage: false,
+            max_slippage_bps: Some(300),
             simulate: false,
+            sign_only: false,
+            encoding: Some(TxnEncoding::Base64),
+            output_path: None,
+            nonce_account: None,
+            nonce_authority: None,
+            fee_payer: None,
+            skip_preflight: false,
+            max_retries: Some(3),
+            oracle_source: Some(OracleSource::Twap),
+            oracle_max_deviation_bps: None,
+            oracle_twap_window: Some(300),
+            confirm_risky_mint: false,
+            max_transfer_fee_bps: Some(100),
+            token_list_path: None,
+            pool_index_path: None,
+            pool_index_ttl: Some(3600),
+            estimate_cost: false,
+            confirm: false,
+            yes: false,
+            quote_tolerance_bps: None,
+            price_movement_bps: None,
         }
     }
 }
@@ -200,6 +475,81 @@ impl CommonConfig {
             if let Some(slippage_bps) = info.get("slippage_bps").and_then(Value::as_integer) {
                 self.slippage_bps = Some(slippage_bps.try_into().unwrap());
             }
+            if let Some(auto_slippage) = info.get("auto_slippage").and_then(Value::as_bool) {
+                self.auto_slippage = auto_slippage;
+            }
+            if let Some(max_slippage_bps) = info.get("max_slippage_bps").and_then(Value::as_integer)
+            {
+                self.max_slippage_bps = Some(max_slippage_bps.try_into().unwrap());
+            }
+            if let Some(nonce_account) = info.get("nonce_account").and_then(Value::as_str) {
+                if !nonce_account.is_empty() {
+                    self.nonce_account = Some(Pubkey::from_str(nonce_account).unwrap());
+                }
+            }
+            if let Some(nonce_authority) = info.get("nonce_authority").and_then(Value::as_str) {
+                if !nonce_authority.is_empty() {
+                    self.nonce_authority = Some(nonce_authority.to_string());
+                }
+            }
+            if let Some(fee_payer) = info.get("fee_payer").and_then(Value::as_str) {
+                if !fee_payer.is_empty() {
+                    self.fee_payer = Some(Pubkey::from_str(fee_payer).unwrap());
+                }
+            }
+            if let Some(max_retries) = info.get("max_retries").and_then(Value::as_integer) {
+                self.max_retries = Some(max_retries.try_into().unwrap());
+            }
+            if let Some(oracle_source) = info.get("oracle_source").and_then(Value::as_str) {
+                if !oracle_source.is_empty() {
+                    self.oracle_source = Some(OracleSource::from_str(oracle_source).unwrap());
+                }
+            }
+            if let Some(oracle_max_deviation_bps) = info
+                .get("oracle_max_deviation_bps")
+                .and_then(Value::as_integer)
+            {
+                self.oracle_max_deviation_bps = Some(oracle_max_deviation_bps.try_into().unwrap());
+            }
+            if let Some(oracle_twap_window) =
+                info.get("oracle_twap_window").and_then(Value::as_integer)
+            {
+                self.oracle_twap_window = Some(oracle_twap_window.try_into().unwrap());
+            }
+            if let Some(confirm_risky_mint) =
+                info.get("confirm_risky_mint").and_then(Value::as_bool)
+            {
+                self.confirm_risky_mint = confirm_risky_mint;
+            }
+            if let Some(max_transfer_fee_bps) =
+                info.get("max_transfer_fee_bps").and_then(Value::as_integer)
+            {
+                self.max_transfer_fee_bps = Some(max_transfer_fee_bps.try_into().unwrap());
+            }
+            if let Some(token_list_path) = info.get("token_list_path").and_then(Value::as_str) {
+                if !token_list_path.is_empty() {
+                    self.token_list_path = Some(token_list_path.to_string());
+                }
+            }
+            if let Some(pool_index_path) = info.get("pool_index_path").and_then(Value::as_str) {
+                if !pool_index_path.is_empty() {
+                    self.pool_index_path = Some(pool_index_path.to_string());
+                }
+            }
+            if let Some(pool_index_ttl) = info.get("pool_index_ttl").and_then(Value::as_integer) {
+                self.pool_index_ttl = Some(pool_index_ttl.try_into().unwrap());
+            }
+            if let Some(estimate_cost) = info.get("estimate_cost").and_then(Value::as_bool) {
+                self.estimate_cost = estimate_cost;
+            }
+            if let Some(confirm) = info.get("confirm").and_then(Value::as_bool) {
+                self.confirm = confirm;
+            }
+            if let Some(quote_tolerance_bps) =
+                info.get("quote_tolerance_bps").and_then(Value::as_integer)
+            {
+                self.quote_tolerance_bps = Some(quote_tolerance_bps.try_into().unwrap());
+            }
         }
         return Ok(());
     }
@@ -229,7 +579,69 @@ impl CommonConfig {
         if command.slippage_bps.is_some() {
             self.slippage_bps = command.slippage_bps;
         }
+        if command.auto_slippage {
+            self.auto_slippage = true;
+        }
+        if command.max_slippage_bps.is_some() {
+            self.max_slippage_bps = command.max_slippage_bps;
+        }
         self.simulate = command.simulate;
+        self.sign_only = command.sign_only;
+        if command.encoding.is_some() {
+            self.encoding = command.encoding;
+        }
+        if command.output_path.is_some() {
+            self.output_path = command.output_path;
+        }
+        if command.nonce_account.is_some() {
+            self.nonce_account = command.nonce_account;
+        }
+        if command.nonce_authority.is_some() {
+            self.nonce_authority = command.nonce_authority;
+        }
+        if command.fee_payer.is_some() {
+            self.fee_payer = command.fee_payer;
+        }
+        self.skip_preflight = command.skip_preflight;
+        if command.max_retries.is_some() {
+            self.max_retries = command.max_retries;
+        }
+        if command.oracle_source.is_some() {
+            self.oracle_source = command.oracle_source;
+        }
+        if command.oracle_max_deviation_bps.is_some() {
+            self.oracle_max_deviation_bps = command.oracle_max_deviation_bps;
+        }
+        if command.oracle_twap_window.is_some() {
+            self.oracle_twap_window = command.oracle_twap_window;
+        }
+        if command.confirm_risky_mint {
+            self.confirm_risky_mint = true;
+        }
+        if command.max_transfer_fee_bps.is_some() {
+            self.max_transfer_fee_bps = command.max_transfer_fee_bps;
+        }
+        if command.token_list_path.is_some() {
+            self.token_list_path = command.token_list_path;
+        }
+        if command.pool_index_path.is_some() {
+            self.pool_index_path = command.pool_index_path;
+        }
+        if command.pool_index_ttl.is_some() {
+            self.pool_index_ttl = command.pool_index_ttl;
+        }
+        if command.estimate_cost {
+            self.estimate_cost = true;
+        }
+        if command.confirm {
+            self.confirm = true;
+        }
+        if command.yes {
+            self.yes = true;
+        }
+        if command.quote_tolerance_bps.is_some() {
+            self.quote_tolerance_bps = command.quote_tolerance_bps;
+        }
     }
 
     pub fn cluster(&self) -> Cluster {
@@ -306,6 +718,52 @@ impl CommonConfig {
         self.slippage_bps = Some(slippage_bps);
     }
 
+    pub fn auto_slippage(&self) -> bool {
+        self.auto_slippage
+    }
+
+    pub fn set_auto_slippage(&mut self, auto_slippage: bool) {
+        self.auto_slippage = auto_slippage;
+    }
+
+    pub fn max_slippage(&self) -> u64 {
+        self.max_slippage_bps.unwrap_or(0)
+    }
+
+    pub fn set_max_slippage(&mut self, max_slippage_bps: u64) {
+        self.max_slippage_bps = Some(max_slippage_bps);
+    }
+
+    pub fn price_movement_bps(&self) -> Option<u64> {
+        self.price_movement_bps
+    }
+
+    pub fn set_price_movement_bps(&mut self, price_movement_bps: u64) {
+        self.price_movement_bps = Some(price_movement_bps);
+    }
+
+    /// The slippage of the swap, the recent price movement of the pool is only read in the
+    /// auto mode if it is not set, the cap is used if it can't be read.
+    pub fn swap_slippage<F>(&self, price_movement_bps: F) -> Slippage
+    where
+        F: FnOnce() -> Result<u64>,
+    {
+        if !self.auto_slippage {
+            return Slippage::Fixed(self.slippage());
+        }
+        let price_movement_bps = match self.price_movement_bps.map_or_else(price_movement_bps, Ok) {
+            Ok(price_movement_bps) => price_movement_bps,
+            Err(e) => {
+                println!("failed to read the recent price movement: {}", e);
+                self.max_slippage()
+            }
+        };
+        Slippage::Auto {
+            price_movement_bps,
+            max_slippage_bps: self.max_slippage(),
+        }
+    }
+
     pub fn simulate(&self) -> bool {
         self.simulate
     }
@@ -313,4 +771,173 @@ impl CommonConfig {
     pub fn set_simulate(&mut self, simulate: bool) {
         self.simulate = simulate;
     }
+
+    pub fn sign_only(&self) -> bool {
+        self.sign_only
+    }
+
+    pub fn set_sign_only(&mut self, sign_only: bool) {
+        self.sign_only = sign_only;
+    }
+
+    pub fn encoding(&self) -> TxnEncoding {
+        self.encoding.unwrap_or(TxnEncoding::Base64)
+    }
+
+    pub fn set_encoding(&mut self, encoding: TxnEncoding) {
+        self.encoding = Some(encoding);
+    }
+
+    pub fn output_path(&self) -> Option<String> {
+        self.output_path.clone()
+    }
+
+    pub fn set_output_path(&mut self, output_path: &str) {
+        self.output_path = Some(output_path.to_string());
+    }
+
+    pub fn nonce_account(&self) -> Option<Pubkey> {
+        self.nonce_account
+    }
+
+    pub fn set_nonce_account(&mut self, nonce_account: Pubkey) {
+        self.nonce_account = Some(nonce_account);
+    }
+
+    pub fn nonce_authority(&self) -> Option<String> {
+        self.nonce_authority.clone()
+    }
+
+    pub fn set_nonce_authority(&mut self, nonce_authority_path: &str) {
+        self.nonce_authority = Some(nonce_authority_path.to_string());
+    }
+
+    pub fn fee_payer(&self) -> Option<Pubkey> {
+        self.fee_payer
+    }
+
+    pub fn set_fee_payer(&mut self, fee_payer: Pubkey) {
+        self.fee_payer = Some(fee_payer);
+    }
+
+    /// The pubkey of the wallet, the fee payer in the sign-only mode if it's given, or else the
+    /// pubkey of the wallet keypair.
+    pub fn wallet_pubkey(&self) -> Result<Pubkey> {
+        if let Some(fee_payer) = self.fee_payer.filter(|_| self.sign_only) {
+            return Ok(fee_payer);
+        }
+        Ok(common_utils::read_keypair_file(&self.wallet())?.pubkey())
+    }
+
+    pub fn skip_preflight(&self) -> bool {
+        self.skip_preflight
+    }
+
+    pub fn set_skip_preflight(&mut self, skip_preflight: bool) {
+        self.skip_preflight = skip_preflight;
+    }
+
+    pub fn max_retries(&self) -> u64 {
+        self.max_retries.unwrap_or(0)
+    }
+
+    pub fn set_max_retries(&mut self, max_retries: u64) {
+        self.max_retries = Some(max_retries);
+    }
+
+    pub fn oracle_source(&self) -> OracleSource {
+        self.oracle_source.clone().unwrap_or(OracleSource::Twap)
+    }
+
+    pub fn set_oracle_source(&mut self, oracle_source: OracleSource) {
+        self.oracle_source = Some(oracle_source);
+    }
+
+    pub fn oracle_max_deviation_bps(&self) -> Option<u64> {
+        self.oracle_max_deviation_bps
+    }
+
+    pub fn set_oracle_max_deviation_bps(&mut self, oracle_max_deviation_bps: u64) {
+        self.oracle_max_deviation_bps = Some(oracle_max_deviation_bps);
+    }
+
+    pub fn oracle_twap_window(&self) -> u64 {
+        self.oracle_twap_window.unwrap_or(300)
+    }
+
+    pub fn set_oracle_twap_window(&mut self, oracle_twap_window: u64) {
+        self.oracle_twap_window = Some(oracle_twap_window);
+    }
+
+    pub fn confirm_risky_mint(&self) -> bool {
+        self.confirm_risky_mint
+    }
+
+    pub fn set_confirm_risky_mint(&mut self, confirm_risky_mint: bool) {
+        self.confirm_risky_mint = confirm_risky_mint;
+    }
+
+    pub fn max_transfer_fee(&self) -> u64 {
+        self.max_transfer_fee_bps.unwrap_or(100)
+    }
+
+    pub fn set_max_transfer_fee(&mut self, max_transfer_fee_bps: u64) {
+        self.max_transfer_fee_bps = Some(max_transfer_fee_bps);
+    }
+
+    pub fn token_list(&self) -> Option<String> {
+        self.token_list_path.clone()
+    }
+
+    pub fn set_token_list(&mut self, token_list_path: &str) {
+        self.token_list_path = Some(token_list_path.to_string());
+    }
+
+    pub fn pool_index(&self) -> Option<String> {
+        self.pool_index_path.clone()
+    }
+
+    pub fn set_pool_index(&mut self, pool_index_path: &str) {
+        self.pool_index_path = Some(pool_index_path.to_string());
+    }
+
+    pub fn pool_index_ttl(&self) -> u64 {
+        self.pool_index_ttl.unwrap_or(3600)
+    }
+
+    pub fn set_pool_index_ttl(&mut self, pool_index_ttl: u64) {
+        self.pool_index_ttl = Some(pool_index_ttl);
+    }
+
+    pub fn estimate_cost(&self) -> bool {
+        self.estimate_cost
+    }
+
+    pub fn set_estimate_cost(&mut self, estimate_cost: bool) {
+        self.estimate_cost = estimate_cost;
+    }
+
+    pub fn confirm(&self) -> bool {
+        self.confirm
+    }
+
+    pub fn set_confirm(&mut self, confirm: bool) {
+        self.confirm = confirm;
+    }
+
+    pub fn yes(&self) -> bool {
+        self.yes
+    }
+
+    pub fn set_yes(&mut self, yes: bool) {
+        self.yes = yes;
+    }
+
+    pub fn quote_tolerance_bps(&self) -> Option<u64> {
+        self.quote_tolerance_bps
+    }
+
+    pub fn set_quote_tolerance_bps(&mut self, quote_tolerance_bps: u64) {
+        self.quote_tolerance_bps = Some(quote_tolerance_bps);
+    }
 }
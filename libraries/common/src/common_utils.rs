@@ -1,29 +1,30 @@
-use crate::common_types::{ExtensionStruct, TokenInfo, TransferFeeInfo, TEN_THOUSAND};
+use crate::common_types::{
+    ExtensionStruct, GroupMemberPointer, GroupPointer, ScaledUiAmountConfig, TokenGroup,
+    TokenGroupMember, TokenInfo, TransferFeeInfo, EXTENSION_TYPE_GROUP_MEMBER_POINTER,
+    EXTENSION_TYPE_GROUP_POINTER, EXTENSION_TYPE_SCALED_UI_AMOUNT, EXTENSION_TYPE_TOKEN_GROUP,
+    EXTENSION_TYPE_TOKEN_GROUP_MEMBER, TEN_THOUSAND,
+};
 use anchor_lang::AccountDeserialize;
 use anyhow::{format_err, Result};
+use bytemuck::Pod;
 use solana_account_decoder::{
     parse_token::{TokenAccountType, UiAccountState},
     UiAccountData,
 };
 use solana_client::{rpc_client::RpcClient, rpc_request::TokenAccountsFilter};
 use solana_sdk::{account::Account as CliAccount, pubkey::Pubkey, signer::keypair::Keypair};
+use spl_pod::bytemuck::pod_from_bytes;
 use spl_token_2022::{
     extension::{
-        confidential_transfer::{ConfidentialTransferAccount, ConfidentialTransferMint},
-        cpi_guard::CpiGuard,
-        default_account_state::DefaultAccountState,
-        immutable_owner::ImmutableOwner,
-        interest_bearing_mint::InterestBearingConfig,
-        memo_transfer::MemoTransfer,
-        mint_close_authority::MintCloseAuthority,
-        non_transferable::{NonTransferable, NonTransferableAccount},
-        permanent_delegate::PermanentDelegate,
-        transfer_fee::{TransferFeeAmount, TransferFeeConfig, MAX_FEE_BASIS_POINTS},
-        BaseState, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+        transfer_fee::{TransferFeeConfig, MAX_FEE_BASIS_POINTS},
+        transfer_hook::TransferHook,
+        BaseState, BaseStateWithExtensions, Extension, ExtensionType, StateWithExtensions,
     },
     state::{Account, Mint},
 };
-use std::convert::TryFrom;
+use spl_token_metadata_interface::state::TokenMetadata;
+use spl_type_length_value::variable_len_pack::VariableLenPack;
+use std::convert::{TryFrom, TryInto};
 
 pub fn amount_with_slippage(amount: u64, slippage_bps: u64, up_towards: bool) -> Result<u64> {
     let amount = amount as u128;
@@ -45,11 +46,40 @@ pub fn amount_with_slippage(amount: u64, slippage_bps: u64, up_towards: bool) ->
         .map_err(|_| format_err!("failed to read keypair from {}", amount_with_slippage))
 }
 
+/// The price impact in bps of the trade, the rates are the output amount per input amount of
+/// the spot price and of the quote.
+pub fn price_impact_bps(spot_rate: f64, quote_rate: f64) -> u64 {
+    if spot_rate <= 0.0 || quote_rate >= spot_rate {
+        return 0;
+    }
+    ((1.0 - quote_rate / spot_rate) * TEN_THOUSAND as f64).ceil() as u64
+}
+
+/// The range in bps of the prices versus the lowest one.
+pub fn price_movement_bps(prices: &[f64]) -> u64 {
+    let min = prices.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = prices.iter().cloned().fold(0.0, f64::max);
+    if prices.is_empty() || min <= 0.0 {
+        return 0;
+    }
+    ((max - min) / min * TEN_THOUSAND as f64).ceil() as u64
+}
+
 pub fn read_keypair_file(s: &str) -> Result<Keypair> {
     solana_sdk::signature::read_keypair_file(s)
         .map_err(|_| format_err!("failed to read keypair from {}", s))
 }
 
+/// The mint of the user token account, the native mint itself stands for the sol of the
+/// wallet, which is wrapped into a temporary wsol account.
+pub fn get_user_token_mint(user_token: &Pubkey, account: Option<&CliAccount>) -> Result<Pubkey> {
+    if *user_token == spl_token::native_mint::id() {
+        return Ok(*user_token);
+    }
+    let account = account.ok_or(format_err!("token account {} not found", user_token))?;
+    Ok(unpack_token(&account.data)?.base.mint)
+}
+
 pub fn unpack_token(token_data: &[u8]) -> Result<StateWithExtensions<Account>> {
     let token = StateWithExtensions::<Account>::unpack(&token_data)?;
     Ok(token)
@@ -135,18 +165,19 @@ pub fn get_transfer_inverse_fee<'data, S: BaseState>(
     epoch: u64,
     post_fee_amount: u64,
 ) -> u64 {
-    let fee = if let Ok(transfer_fee_config) = account_state.get_extension::<TransferFeeConfig>() {
-        let transfer_fee = transfer_fee_config.get_epoch_fee(epoch);
-        if u16::from(transfer_fee.transfer_fee_basis_points) == MAX_FEE_BASIS_POINTS {
-            u64::from(transfer_fee.maximum_fee)
+    let fee =
+        if let Some(transfer_fee_config) = find_extension::<S, TransferFeeConfig>(account_state) {
+            let transfer_fee = transfer_fee_config.get_epoch_fee(epoch);
+            if u16::from(transfer_fee.transfer_fee_basis_points) == MAX_FEE_BASIS_POINTS {
+                u64::from(transfer_fee.maximum_fee)
+            } else {
+                transfer_fee_config
+                    .calculate_inverse_epoch_fee(epoch, post_fee_amount)
+                    .unwrap()
+            }
         } else {
-            transfer_fee_config
-                .calculate_inverse_epoch_fee(epoch, post_fee_amount)
-                .unwrap()
-        }
-    } else {
-        0
-    };
+            0
+        };
     fee
 }
 
@@ -156,13 +187,14 @@ pub fn get_transfer_fee<'data, S: BaseState>(
     epoch: u64,
     pre_fee_amount: u64,
 ) -> u64 {
-    let fee = if let Ok(transfer_fee_config) = account_state.get_extension::<TransferFeeConfig>() {
-        transfer_fee_config
-            .calculate_epoch_fee(epoch, pre_fee_amount)
-            .unwrap()
-    } else {
-        0
-    };
+    let fee =
+        if let Some(transfer_fee_config) = find_extension::<S, TransferFeeConfig>(account_state) {
+            transfer_fee_config
+                .calculate_epoch_fee(epoch, pre_fee_amount)
+                .unwrap()
+        } else {
+            0
+        };
     fee
 }
 
@@ -218,80 +250,194 @@ pub fn get_nft_accounts_by_owner_with_specified_program(
     nft_accounts_info
 }
 
+/// The raw tlv entries of the extensions as the extension type and the value.
+/// Unlike `get_extension_types`, the extension types newer than spl-token-2022 don't stop the walk.
+pub fn get_tlv_entries(tlv_data: &[u8]) -> Vec<(u16, &[u8])> {
+    let mut entries = Vec::new();
+    let mut start_index = 0;
+    while start_index + 4 <= tlv_data.len() {
+        let extension_type = u16::from_le_bytes([tlv_data[start_index], tlv_data[start_index + 1]]);
+        if extension_type == u16::from(ExtensionType::Uninitialized) {
+            // nothing is written after an uninitialized entry
+            break;
+        }
+        let length =
+            u16::from_le_bytes([tlv_data[start_index + 2], tlv_data[start_index + 3]]) as usize;
+        let value_end_index = start_index + 4 + length;
+        if value_end_index > tlv_data.len() {
+            break;
+        }
+        entries.push((extension_type, &tlv_data[start_index + 4..value_end_index]));
+        start_index = value_end_index;
+    }
+    entries
+}
+
+/// Find the extension in the raw tlv entries.
+pub fn find_extension<'data, S: BaseState, V: Extension + Pod>(
+    account_state: &StateWithExtensions<'data, S>,
+) -> Option<V> {
+    get_tlv_entries(account_state.get_tlv_data())
+        .into_iter()
+        .find(|(extension_type, _)| *extension_type == u16::from(V::TYPE))
+        .and_then(|(_, value)| pod_from_bytes::<V>(value).ok().copied())
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Option<Pubkey> {
+    data.get(offset..offset + 32)
+        .map(|bytes| Pubkey::try_from(bytes).unwrap())
+}
+
+fn read_optional_pubkey(data: &[u8], offset: usize) -> Option<Option<Pubkey>> {
+    read_pubkey(data, offset).map(|key| Some(key).filter(|key| *key != Pubkey::default()))
+}
+
+/// Read the little endian u64 or, for the earlier layout of the token group, u32.
+fn read_size(data: &[u8], offset: usize, wide: bool) -> Option<u64> {
+    if wide {
+        let bytes = data.get(offset..offset + 8)?;
+        Some(u64::from_le_bytes(bytes.try_into().unwrap()))
+    } else {
+        let bytes = data.get(offset..offset + 4)?;
+        Some(u32::from_le_bytes(bytes.try_into().unwrap()) as u64)
+    }
+}
+
+fn read_f64(data: &[u8], offset: usize) -> Option<f64> {
+    let bytes = data.get(offset..offset + 8)?;
+    Some(f64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+/// Decode the extensions newer than spl-token-2022 from their layouts.
+fn decode_newer_extension(extension_type: u16, value: &[u8]) -> Option<ExtensionStruct> {
+    let extension = match extension_type {
+        EXTENSION_TYPE_GROUP_POINTER => ExtensionStruct::GroupPointer(GroupPointer {
+            authority: read_optional_pubkey(value, 0)?,
+            group_address: read_optional_pubkey(value, 32)?,
+        }),
+        EXTENSION_TYPE_TOKEN_GROUP => {
+            let wide = value.len() >= 80;
+            let size_len = if wide { 8 } else { 4 };
+            ExtensionStruct::TokenGroup(TokenGroup {
+                update_authority: read_optional_pubkey(value, 0)?,
+                mint: read_pubkey(value, 32)?,
+                size: read_size(value, 64, wide)?,
+                max_size: read_size(value, 64 + size_len, wide)?,
+            })
+        }
+        EXTENSION_TYPE_GROUP_MEMBER_POINTER => {
+            ExtensionStruct::GroupMemberPointer(GroupMemberPointer {
+                authority: read_optional_pubkey(value, 0)?,
+                member_address: read_optional_pubkey(value, 32)?,
+            })
+        }
+        EXTENSION_TYPE_TOKEN_GROUP_MEMBER => ExtensionStruct::TokenGroupMember(TokenGroupMember {
+            mint: read_pubkey(value, 0)?,
+            group: read_pubkey(value, 32)?,
+            member_number: read_size(value, 64, value.len() >= 72)?,
+        }),
+        EXTENSION_TYPE_SCALED_UI_AMOUNT => ExtensionStruct::ScaledUiAmount(ScaledUiAmountConfig {
+            authority: read_optional_pubkey(value, 0)?,
+            multiplier: read_f64(value, 32)?,
+            new_multiplier_effective_timestamp: read_size(value, 40, true)? as i64,
+            new_multiplier: read_f64(value, 48)?,
+        }),
+        _ => ExtensionStruct::Unknown(extension_type),
+    };
+    Some(extension)
+}
+
+fn decode_extension(extension_type: u16, value: &[u8]) -> Option<ExtensionStruct> {
+    let extension_type = match ExtensionType::try_from(extension_type) {
+        Ok(extension_type) => extension_type,
+        Err(_) => return decode_newer_extension(extension_type, value),
+    };
+    let extension = match extension_type {
+        ExtensionType::ConfidentialTransferAccount => {
+            ExtensionStruct::ConfidentialTransferAccount(*pod_from_bytes(value).ok()?)
+        }
+        ExtensionType::ConfidentialTransferMint => {
+            ExtensionStruct::ConfidentialTransferMint(*pod_from_bytes(value).ok()?)
+        }
+        ExtensionType::CpiGuard => ExtensionStruct::CpiGuard(*pod_from_bytes(value).ok()?),
+        ExtensionType::DefaultAccountState => {
+            ExtensionStruct::DefaultAccountState(*pod_from_bytes(value).ok()?)
+        }
+        ExtensionType::ImmutableOwner => {
+            ExtensionStruct::ImmutableOwner(*pod_from_bytes(value).ok()?)
+        }
+        ExtensionType::InterestBearingConfig => {
+            ExtensionStruct::InterestBearingConfig(*pod_from_bytes(value).ok()?)
+        }
+        ExtensionType::MemoTransfer => ExtensionStruct::MemoTransfer(*pod_from_bytes(value).ok()?),
+        ExtensionType::MintCloseAuthority => {
+            ExtensionStruct::MintCloseAuthority(*pod_from_bytes(value).ok()?)
+        }
+        ExtensionType::NonTransferable => {
+            ExtensionStruct::NonTransferable(*pod_from_bytes(value).ok()?)
+        }
+        ExtensionType::NonTransferableAccount => {
+            ExtensionStruct::NonTransferableAccount(*pod_from_bytes(value).ok()?)
+        }
+        ExtensionType::PermanentDelegate => {
+            ExtensionStruct::PermanentDelegate(*pod_from_bytes(value).ok()?)
+        }
+        ExtensionType::TransferFeeConfig => {
+            ExtensionStruct::TransferFeeConfig(*pod_from_bytes(value).ok()?)
+        }
+        ExtensionType::TransferFeeAmount => {
+            ExtensionStruct::TransferFeeAmount(*pod_from_bytes(value).ok()?)
+        }
+        ExtensionType::TransferHook => ExtensionStruct::TransferHook(*pod_from_bytes(value).ok()?),
+        ExtensionType::TransferHookAccount => {
+            ExtensionStruct::TransferHookAccount(*pod_from_bytes(value).ok()?)
+        }
+        ExtensionType::ConfidentialTransferFeeConfig => {
+            ExtensionStruct::ConfidentialTransferFeeConfig(*pod_from_bytes(value).ok()?)
+        }
+        ExtensionType::ConfidentialTransferFeeAmount => {
+            ExtensionStruct::ConfidentialTransferFeeAmount(*pod_from_bytes(value).ok()?)
+        }
+        ExtensionType::MetadataPointer => {
+            ExtensionStruct::MetadataPointer(*pod_from_bytes(value).ok()?)
+        }
+        ExtensionType::TokenMetadata => {
+            ExtensionStruct::TokenMetadata(TokenMetadata::unpack_from_slice(value).ok()?)
+        }
+        _ => ExtensionStruct::Unknown(extension_type.into()),
+    };
+    Some(extension)
+}
+
 pub fn get_account_extensions<'data, S: BaseState>(
     account_state: &StateWithExtensions<'data, S>,
 ) -> Vec<ExtensionStruct> {
-    let mut extensions: Vec<ExtensionStruct> = Vec::new();
-    let extension_types = account_state.get_extension_types().unwrap();
-    println!("extension_types:{:?}", extension_types);
-    for extension_type in extension_types {
-        match extension_type {
-            ExtensionType::ConfidentialTransferAccount => {
-                let extension = account_state
-                    .get_extension::<ConfidentialTransferAccount>()
-                    .unwrap();
-                extensions.push(ExtensionStruct::ConfidentialTransferAccount(*extension));
-            }
-            ExtensionType::ConfidentialTransferMint => {
-                let extension = account_state
-                    .get_extension::<ConfidentialTransferMint>()
-                    .unwrap();
-                extensions.push(ExtensionStruct::ConfidentialTransferMint(*extension));
-            }
-            ExtensionType::CpiGuard => {
-                let extension = account_state.get_extension::<CpiGuard>().unwrap();
-                extensions.push(ExtensionStruct::CpiGuard(*extension));
-            }
-            ExtensionType::DefaultAccountState => {
-                let extension = account_state
-                    .get_extension::<DefaultAccountState>()
-                    .unwrap();
-                extensions.push(ExtensionStruct::DefaultAccountState(*extension));
-            }
-            ExtensionType::ImmutableOwner => {
-                let extension = account_state.get_extension::<ImmutableOwner>().unwrap();
-                extensions.push(ExtensionStruct::ImmutableOwner(*extension));
-            }
-            ExtensionType::InterestBearingConfig => {
-                let extension = account_state
-                    .get_extension::<InterestBearingConfig>()
-                    .unwrap();
-                extensions.push(ExtensionStruct::InterestBearingConfig(*extension));
-            }
-            ExtensionType::MemoTransfer => {
-                let extension = account_state.get_extension::<MemoTransfer>().unwrap();
-                extensions.push(ExtensionStruct::MemoTransfer(*extension));
-            }
-            ExtensionType::MintCloseAuthority => {
-                let extension = account_state.get_extension::<MintCloseAuthority>().unwrap();
-                extensions.push(ExtensionStruct::MintCloseAuthority(*extension));
-            }
-            ExtensionType::NonTransferable => {
-                let extension = account_state.get_extension::<NonTransferable>().unwrap();
-                extensions.push(ExtensionStruct::NonTransferable(*extension));
-            }
-            ExtensionType::NonTransferableAccount => {
-                let extension = account_state
-                    .get_extension::<NonTransferableAccount>()
-                    .unwrap();
-                extensions.push(ExtensionStruct::NonTransferableAccount(*extension));
-            }
-            ExtensionType::PermanentDelegate => {
-                let extension = account_state.get_extension::<PermanentDelegate>().unwrap();
-                extensions.push(ExtensionStruct::PermanentDelegate(*extension));
-            }
-            ExtensionType::TransferFeeConfig => {
-                let extension = account_state.get_extension::<TransferFeeConfig>().unwrap();
-                extensions.push(ExtensionStruct::TransferFeeConfig(*extension));
-            }
-            ExtensionType::TransferFeeAmount => {
-                let extension = account_state.get_extension::<TransferFeeAmount>().unwrap();
-                extensions.push(ExtensionStruct::TransferFeeAmount(*extension));
-            }
-            _ => {
-                println!("unkonwn extension:{:#?}", extension_type);
-            }
-        }
+    get_tlv_entries(account_state.get_tlv_data())
+        .into_iter()
+        .filter_map(|(extension_type, value)| decode_extension(extension_type, value))
+        .collect()
+}
+
+/// The program of the transfer hook of the mint.
+pub fn get_transfer_hook_program<'data>(
+    mint_state: &StateWithExtensions<'data, Mint>,
+) -> Option<Pubkey> {
+    find_extension::<Mint, TransferHook>(mint_state)
+        .and_then(|transfer_hook| Option::<Pubkey>::from(transfer_hook.program_id))
+}
+
+/// The raydium programs do not pass the extra accounts of a transfer hook to the token program,
+/// so the transfers of a mint with a hook fail.
+pub fn check_transfer_hook<'data>(
+    mint: &Pubkey,
+    mint_state: &StateWithExtensions<'data, Mint>,
+) -> Result<()> {
+    match get_transfer_hook_program(mint_state) {
+        Some(hook_program) => Err(format_err!(
+            "mint {} has the transfer hook {}, which the raydium programs do not support",
+            mint,
+            hook_program
+        )),
+        None => Ok(()),
     }
-    extensions
 }
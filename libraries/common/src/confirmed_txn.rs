@@ -0,0 +1,240 @@
+use anyhow::{format_err, Result};
+use solana_client::{rpc_client::RpcClient, rpc_config::RpcTransactionConfig};
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature,
+    transaction::VersionedTransaction,
+};
+use solana_transaction_status::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
+    UiInnerInstructions, UiInstruction, UiTransactionEncoding, UiTransactionStatusMeta,
+    UiTransactionTokenBalance,
+};
+use std::{str::FromStr, thread, time::Duration};
+
+/// An instruction executed in the transaction, the index of an inner instruction is
+/// `<top level index>.<inner index>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfirmedInstruction {
+    pub index: String,
+    pub program_id: Pubkey,
+    pub accounts: Vec<Pubkey>,
+    pub data: Vec<u8>,
+}
+
+/// A log line with the program emitted it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProgramLog {
+    pub program_id: Pubkey,
+    /// The position in `get_confirmed_instructions` of the instruction emitted the log,
+    /// counted by the invoke logs, it's not reliable if the logs are truncated.
+    pub invoke_index: usize,
+    pub log: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenBalanceChange {
+    pub account: Pubkey,
+    pub mint: String,
+    pub owner: Option<String>,
+    pub pre_amount: u64,
+    pub post_amount: u64,
+}
+
+/// Fetch the confirmed transaction in a single attempt, for the best-effort reads.
+pub fn get_confirmed_txn_once(
+    client: &RpcClient,
+    signature: &Signature,
+) -> Result<EncodedConfirmedTransactionWithStatusMeta> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+    Ok(client.get_transaction_with_config(signature, config)?)
+}
+
+/// Fetch the landed transaction, the rpc node may need a moment to serve it after confirmation.
+pub fn get_confirmed_txn(
+    client: &RpcClient,
+    signature: &Signature,
+) -> Result<EncodedConfirmedTransactionWithStatusMeta> {
+    let mut retries = 0;
+    loop {
+        match get_confirmed_txn_once(client, signature) {
+            Ok(confirmed_txn) => return Ok(confirmed_txn),
+            Err(e) => {
+                retries += 1;
+                if retries >= 10 {
+                    return Err(e);
+                }
+                thread::sleep(Duration::from_secs(1));
+            }
+        }
+    }
+}
+
+pub fn decode_confirmed_txn(
+    confirmed_txn: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Result<(VersionedTransaction, UiTransactionStatusMeta)> {
+    let meta = confirmed_txn
+        .transaction
+        .meta
+        .clone()
+        .ok_or(format_err!("transaction meta is not available"))?;
+    let versioned_txn = confirmed_txn
+        .transaction
+        .transaction
+        .decode()
+        .ok_or(format_err!("failed to decode transaction"))?;
+    Ok((versioned_txn, meta))
+}
+
+/// The static account keys followed by the keys loaded from the address lookup tables.
+pub fn get_account_keys(
+    versioned_txn: &VersionedTransaction,
+    meta: &UiTransactionStatusMeta,
+) -> Result<Vec<Pubkey>> {
+    let mut account_keys = versioned_txn.message.static_account_keys().to_vec();
+    if let OptionSerializer::Some(loaded_addresses) = &meta.loaded_addresses {
+        for address in loaded_addresses
+            .writable
+            .iter()
+            .chain(loaded_addresses.readonly.iter())
+        {
+            account_keys.push(Pubkey::from_str(address)?);
+        }
+    }
+    Ok(account_keys)
+}
+
+/// The top level instructions, each followed by its inner instructions in execution order.
+pub fn get_confirmed_instructions(
+    versioned_txn: &VersionedTransaction,
+    meta: &UiTransactionStatusMeta,
+    account_keys: &[Pubkey],
+) -> Result<Vec<ConfirmedInstruction>> {
+    let inner_instructions: Vec<UiInnerInstructions> =
+        Option::from(meta.inner_instructions.clone()).unwrap_or_default();
+    let mut instructions = Vec::new();
+    for (index, ix) in versioned_txn.message.instructions().iter().enumerate() {
+        instructions.push(ConfirmedInstruction {
+            index: index.to_string(),
+            program_id: account_keys[ix.program_id_index as usize],
+            accounts: ix
+                .accounts
+                .iter()
+                .map(|account| account_keys[*account as usize])
+                .collect(),
+            data: ix.data.clone(),
+        });
+        for inner in inner_instructions
+            .iter()
+            .filter(|inner| inner.index as usize == index)
+        {
+            for (inner_index, inner_ix) in inner.instructions.iter().enumerate() {
+                if let UiInstruction::Compiled(inner_ix) = inner_ix {
+                    instructions.push(ConfirmedInstruction {
+                        index: format!("{}.{}", index, inner_index + 1),
+                        program_id: account_keys[inner_ix.program_id_index as usize],
+                        accounts: inner_ix
+                            .accounts
+                            .iter()
+                            .map(|account| account_keys[*account as usize])
+                            .collect(),
+                        data: bs58::decode(&inner_ix.data).into_vec()?,
+                    });
+                }
+            }
+        }
+    }
+    Ok(instructions)
+}
+
+/// The balance changes of all token accounts in the transaction.
+pub fn get_token_balance_changes(
+    meta: &UiTransactionStatusMeta,
+    account_keys: &[Pubkey],
+) -> Result<Vec<TokenBalanceChange>> {
+    let pre_token_balances: Vec<UiTransactionTokenBalance> =
+        Option::from(meta.pre_token_balances.clone()).unwrap_or_default();
+    let post_token_balances: Vec<UiTransactionTokenBalance> =
+        Option::from(meta.post_token_balances.clone()).unwrap_or_default();
+    let mut token_balance_changes: Vec<TokenBalanceChange> = Vec::new();
+    // accounts created in the transaction have no pre balance, closed accounts have no post balance
+    for balance in pre_token_balances.iter().chain(post_token_balances.iter()) {
+        if token_balance_changes
+            .iter()
+            .any(|change| change.account == account_keys[balance.account_index as usize])
+        {
+            continue;
+        }
+        let amount_of = |balances: &[UiTransactionTokenBalance]| -> Result<u64> {
+            match balances
+                .iter()
+                .find(|b| b.account_index == balance.account_index)
+            {
+                Some(b) => b.ui_token_amount.amount.parse::<u64>().map_err(|e| {
+                    format_err!("invalid token amount {}: {}", b.ui_token_amount.amount, e)
+                }),
+                None => Ok(0),
+            }
+        };
+        token_balance_changes.push(TokenBalanceChange {
+            account: account_keys[balance.account_index as usize],
+            mint: balance.mint.clone(),
+            owner: Option::from(balance.owner.clone()),
+            pre_amount: amount_of(&pre_token_balances)?,
+            post_amount: amount_of(&post_token_balances)?,
+        });
+    }
+    Ok(token_balance_changes)
+}
+
+pub fn print_token_balance_changes(token_balance_changes: &[TokenBalanceChange]) {
+    for change in token_balance_changes {
+        println!(
+            "account:{}, mint:{}, owner:{:?}, pre_amount:{}, post_amount:{}, change:{}",
+            change.account,
+            change.mint,
+            change.owner,
+            change.pre_amount,
+            change.post_amount,
+            change.post_amount as i128 - change.pre_amount as i128
+        );
+    }
+}
+
+/// Attribute each `Program log:` and `Program data:` line to the program emitted it
+/// by tracking the invoke stack.
+pub fn get_program_logs(log_messages: &[String]) -> Vec<ProgramLog> {
+    let mut invoke_stack: Vec<(Pubkey, usize)> = Vec::new();
+    let mut invoke_count = 0;
+    let mut program_logs = Vec::new();
+    for log in log_messages {
+        let mut words = log.split_whitespace();
+        if let (Some("Program"), Some(program_id), Some(action)) =
+            (words.next(), words.next(), words.next())
+        {
+            if let Ok(program_id) = Pubkey::from_str(program_id) {
+                if action == "invoke" {
+                    invoke_stack.push((program_id, invoke_count));
+                    invoke_count += 1;
+                    continue;
+                } else if action == "success" || action.starts_with("failed") {
+                    invoke_stack.pop();
+                    continue;
+                } else if action == "consumed" {
+                    continue;
+                }
+            }
+        }
+        if let Some((program_id, invoke_index)) = invoke_stack.last() {
+            program_logs.push(ProgramLog {
+                program_id: *program_id,
+                invoke_index: *invoke_index,
+                log: log.clone(),
+            });
+        }
+    }
+    program_logs
+}
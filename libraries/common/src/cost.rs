@@ -0,0 +1,245 @@
+use crate::{amount::raw_amount_to_ui, common_utils, simulation::simulate_txn};
+use anyhow::{format_err, Result};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    account::Account, compute_budget, message::Message, pubkey::Pubkey, transaction::Transaction,
+};
+
+/// The compute unit limit of an instruction without a compute budget instruction.
+const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u64 = 200_000;
+/// The max compute unit limit of a transaction.
+const MAX_COMPUTE_UNIT_LIMIT: u64 = 1_400_000;
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+/// The accounts of a program the cost estimate needs to know.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CostAccounts {
+    /// The accounts receiving the creation fees of the program.
+    pub fee_receivers: Vec<Pubkey>,
+    /// The program and the anchor discriminator of the accounts the user closes to reclaim the rent.
+    pub refundable: Vec<(Pubkey, [u8; 8])>,
+}
+
+/// An account the transaction creates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NewAccount {
+    pub address: Pubkey,
+    pub owner: Pubkey,
+    pub space: usize,
+    pub lamports: u64,
+    /// Whether the user can close the account to reclaim the lamports.
+    pub refundable: bool,
+}
+
+/// The lamports the transaction costs the fee payer besides the tokens it swaps or deposits.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CostEstimate {
+    pub new_accounts: Vec<NewAccount>,
+    /// The lamports paid to the fee receivers of the programs.
+    pub creation_fees: Vec<(Pubkey, u64)>,
+    pub network_fee: u64,
+    pub priority_fee: u64,
+    pub units_consumed: Option<u64>,
+}
+
+impl CostEstimate {
+    pub fn rent(&self) -> u64 {
+        self.new_accounts
+            .iter()
+            .map(|account| account.lamports)
+            .sum()
+    }
+
+    pub fn refundable_rent(&self) -> u64 {
+        self.new_accounts
+            .iter()
+            .filter(|account| account.refundable)
+            .map(|account| account.lamports)
+            .sum()
+    }
+
+    pub fn creation_fee(&self) -> u64 {
+        self.creation_fees
+            .iter()
+            .map(|(_, lamports)| lamports)
+            .sum()
+    }
+
+    pub fn total(&self) -> u64 {
+        self.rent() + self.creation_fee() + self.network_fee + self.priority_fee
+    }
+}
+
+/// The priority fee of the compute budget instructions of the message, the compute unit price
+/// times the compute unit limit.
+pub fn get_priority_fee(message: &Message) -> u64 {
+    let mut compute_unit_limit = None;
+    let mut compute_unit_price = 0u64;
+    let mut instruction_count = 0u64;
+    for instruction in message.instructions.iter() {
+        if message.account_keys[instruction.program_id_index as usize] != compute_budget::id() {
+            instruction_count += 1;
+            continue;
+        }
+        // the discriminator of SetComputeUnitLimit is 2 and of SetComputeUnitPrice 3
+        match instruction.data.split_first() {
+            Some((2, data)) if data.len() >= 4 => {
+                compute_unit_limit = Some(u32::from_le_bytes(data[..4].try_into().unwrap()) as u64);
+            }
+            Some((3, data)) if data.len() >= 8 => {
+                compute_unit_price = u64::from_le_bytes(data[..8].try_into().unwrap());
+            }
+            _ => {}
+        }
+    }
+    let compute_unit_limit = compute_unit_limit
+        .unwrap_or(instruction_count * DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT)
+        .min(MAX_COMPUTE_UNIT_LIMIT);
+    (compute_unit_price as u128 * compute_unit_limit as u128).div_ceil(MICRO_LAMPORTS_PER_LAMPORT)
+        as u64
+}
+
+fn is_refundable(account: &Account, owner: &Pubkey, cost_accounts: &[CostAccounts]) -> bool {
+    if account.owner == spl_token::id() || account.owner == spl_token_2022::id() {
+        return common_utils::unpack_token(&account.data)
+            .map(|token_state| token_state.base.owner == *owner)
+            .unwrap_or(false);
+    }
+    cost_accounts
+        .iter()
+        .flat_map(|cost_accounts| cost_accounts.refundable.iter())
+        .any(|(program, discriminator)| {
+            account.owner == *program && account.data.starts_with(discriminator)
+        })
+}
+
+/// The writable accounts of the transaction before and after its simulation.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccountChanges {
+    pub keys: Vec<Pubkey>,
+    pub pre_accounts: Vec<Option<Account>>,
+    /// None for the accounts missing or closed after the transaction.
+    pub post_accounts: Vec<Option<Account>>,
+    pub units_consumed: Option<u64>,
+}
+
+/// Simulate the transaction and return the writable accounts before and after it.
+pub fn simulate_account_changes(
+    rpc_client: &RpcClient,
+    txn: &Transaction,
+) -> Result<AccountChanges> {
+    let simulation = simulate_txn(rpc_client, txn)?;
+    if let Some(err) = simulation.err {
+        return Err(format_err!(
+            "the simulation of the transaction failed: {}, logs: {:#?}",
+            err,
+            simulation.logs
+        ));
+    }
+    Ok(simulation.changes)
+}
+
+/// Estimate the cost of the transaction from its simulated account changes: the accounts it
+/// creates and the rent they lock, the creation fees paid to the fee receivers, and the network
+/// and priority fees.
+pub fn estimate_cost(
+    rpc_client: &RpcClient,
+    txn: &Transaction,
+    changes: &AccountChanges,
+    owner: &Pubkey,
+    cost_accounts: &[CostAccounts],
+) -> Result<CostEstimate> {
+    let mut estimate = CostEstimate {
+        network_fee: rpc_client.get_fee_for_message(&txn.message)?,
+        priority_fee: get_priority_fee(&txn.message),
+        units_consumed: changes.units_consumed,
+        ..CostEstimate::default()
+    };
+    let fee_receivers: Vec<&Pubkey> = cost_accounts
+        .iter()
+        .flat_map(|cost_accounts| cost_accounts.fee_receivers.iter())
+        .collect();
+    for ((address, pre_account), post_account) in changes
+        .keys
+        .iter()
+        .zip(changes.pre_accounts.iter())
+        .zip(changes.post_accounts.iter())
+    {
+        let post_account = match post_account {
+            Some(post_account) => post_account,
+            // the temporary accounts are closed by the transaction
+            None => continue,
+        };
+        match pre_account {
+            None => estimate.new_accounts.push(NewAccount {
+                address: *address,
+                owner: post_account.owner,
+                space: post_account.data.len(),
+                lamports: post_account.lamports,
+                refundable: is_refundable(post_account, owner, cost_accounts),
+            }),
+            Some(pre_account) if fee_receivers.contains(&address) => {
+                if post_account.lamports > pre_account.lamports {
+                    estimate
+                        .creation_fees
+                        .push((*address, post_account.lamports - pre_account.lamports));
+                }
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(estimate)
+}
+
+/// Estimate the cost of the transaction by simulating it.
+pub fn estimate_txn_cost(
+    rpc_client: &RpcClient,
+    txn: &Transaction,
+    owner: &Pubkey,
+    cost_accounts: &[CostAccounts],
+) -> Result<CostEstimate> {
+    let changes = simulate_account_changes(rpc_client, txn)?;
+    estimate_cost(rpc_client, txn, &changes, owner, cost_accounts)
+}
+
+fn sol(lamports: u64) -> String {
+    raw_amount_to_ui(lamports, 9)
+}
+
+pub fn print_cost_estimate(estimate: &CostEstimate) {
+    for account in estimate.new_accounts.iter() {
+        println!(
+            "new account:{}, owner:{}, space:{}, rent:{} sol{}",
+            account.address,
+            account.owner,
+            account.space,
+            sol(account.lamports),
+            if account.refundable {
+                " (refundable)"
+            } else {
+                ""
+            }
+        );
+    }
+    println!(
+        "rent:{} sol, refundable:{} sol",
+        sol(estimate.rent()),
+        sol(estimate.refundable_rent())
+    );
+    for (fee_receiver, lamports) in estimate.creation_fees.iter() {
+        println!("creation fee:{} sol to {}", sol(*lamports), fee_receiver);
+    }
+    println!(
+        "network fee:{} sol, priority fee:{} sol, units_consumed:{}",
+        sol(estimate.network_fee),
+        sol(estimate.priority_fee),
+        estimate
+            .units_consumed
+            .map_or("unknown".to_string(), |units| units.to_string())
+    );
+    println!(
+        "total cost:{} sol, {} sol after closing the refundable accounts",
+        sol(estimate.total()),
+        sol(estimate.total() - estimate.refundable_rent())
+    );
+}
@@ -0,0 +1,220 @@
+use anyhow::{format_err, Result};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+    str::FromStr,
+};
+
+const TRADES_HEADER: &str = "signature,slot,block_time,pool_id,zero_for_one,amount_0,amount_1";
+const CANDLES_HEADER: &str = "open_time,open,high,low,close,volume_0,volume_1,trades";
+
+/// A swap of the pool, token 0 is the coin of the amm pool.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SwapTrade {
+    pub pool_id: Pubkey,
+    pub zero_for_one: bool,
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trade {
+    pub signature: Signature,
+    pub slot: u64,
+    pub block_time: i64,
+    pub swap: SwapTrade,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PoolMints {
+    pub mint_0: Pubkey,
+    pub mint_1: Pubkey,
+    pub decimals_0: u8,
+    pub decimals_1: u8,
+}
+
+/// The range of the signatures indexed, the signatures between `oldest` and `newest` are
+/// all indexed. `backfilled` is set once the first signature of the pool is reached.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IndexCursor {
+    pub newest: Option<Signature>,
+    pub oldest: Option<Signature>,
+    pub backfilled: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candle {
+    pub open_time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume_0: f64,
+    pub volume_1: f64,
+    pub trades: u64,
+}
+
+fn cursor_path(store: &str) -> String {
+    format!("{}.cursor", store)
+}
+
+pub fn load_cursor(store: &str) -> Result<IndexCursor> {
+    let mut cursor = IndexCursor::default();
+    let path = cursor_path(store);
+    if !Path::new(&path).exists() {
+        return Ok(cursor);
+    }
+    for line in fs::read_to_string(&path)?.lines() {
+        match line.split_once('=') {
+            Some(("newest", value)) => cursor.newest = Some(Signature::from_str(value)?),
+            Some(("oldest", value)) => cursor.oldest = Some(Signature::from_str(value)?),
+            Some(("backfilled", value)) => cursor.backfilled = value == "true",
+            _ => {}
+        }
+    }
+    Ok(cursor)
+}
+
+pub fn save_cursor(store: &str, cursor: &IndexCursor) -> Result<()> {
+    let mut content = String::new();
+    if let Some(newest) = cursor.newest {
+        content.push_str(&format!("newest={}\n", newest));
+    }
+    if let Some(oldest) = cursor.oldest {
+        content.push_str(&format!("oldest={}\n", oldest));
+    }
+    content.push_str(&format!("backfilled={}\n", cursor.backfilled));
+    // the rename replaces the cursor atomically, an interrupted write leaves the old one
+    let path = cursor_path(store);
+    let temp_path = format!("{}.tmp", path);
+    fs::write(&temp_path, content)?;
+    fs::rename(&temp_path, &path)?;
+    Ok(())
+}
+
+pub fn load_trades(store: &str) -> Result<Vec<Trade>> {
+    if !Path::new(store).exists() {
+        return Ok(Vec::new());
+    }
+    let mut trades = Vec::new();
+    for line in fs::read_to_string(store)?.lines().skip(1) {
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.len() != 7 {
+            return Err(format_err!("invalid trade record: {}", line));
+        }
+        trades.push(Trade {
+            signature: Signature::from_str(fields[0])?,
+            slot: fields[1].parse()?,
+            block_time: fields[2].parse()?,
+            swap: SwapTrade {
+                pool_id: Pubkey::from_str(fields[3])?,
+                zero_for_one: fields[4].parse()?,
+                amount_0: fields[5].parse()?,
+                amount_1: fields[6].parse()?,
+            },
+        });
+    }
+    Ok(trades)
+}
+
+pub fn append_trades(store: &str, trades: &[Trade]) -> Result<()> {
+    let exists = Path::new(store).exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(store)?;
+    if !exists {
+        writeln!(file, "{}", TRADES_HEADER)?;
+    }
+    for trade in trades {
+        writeln!(
+            file,
+            "{},{},{},{},{},{},{}",
+            trade.signature,
+            trade.slot,
+            trade.block_time,
+            trade.swap.pool_id,
+            trade.swap.zero_for_one,
+            trade.swap.amount_0,
+            trade.swap.amount_1
+        )?;
+    }
+    Ok(())
+}
+
+/// The price of token 0 quoted in token 1, adjusted with the decimals of the mints.
+pub fn trade_price(swap: &SwapTrade, mints: &PoolMints) -> Option<f64> {
+    if swap.amount_0 == 0 || swap.amount_1 == 0 {
+        return None;
+    }
+    Some(
+        swap.amount_1 as f64 / swap.amount_0 as f64
+            * 10f64.powi(mints.decimals_0 as i32 - mints.decimals_1 as i32),
+    )
+}
+
+/// Aggregate the trades into candles of `interval` seconds, intervals without trades are skipped.
+pub fn build_candles(trades: &[Trade], mints: &PoolMints, interval: i64) -> Vec<Candle> {
+    let mut trades = trades.to_vec();
+    trades.sort_by_key(|trade| (trade.block_time, trade.slot));
+    let mut candles: Vec<Candle> = Vec::new();
+    for trade in trades {
+        let price = match trade_price(&trade.swap, mints) {
+            Some(price) => price,
+            None => continue,
+        };
+        let volume_0 = trade.swap.amount_0 as f64 / 10f64.powi(mints.decimals_0 as i32);
+        let volume_1 = trade.swap.amount_1 as f64 / 10f64.powi(mints.decimals_1 as i32);
+        let open_time = trade.block_time - trade.block_time.rem_euclid(interval);
+        match candles.last_mut() {
+            Some(candle) if candle.open_time == open_time => {
+                candle.high = candle.high.max(price);
+                candle.low = candle.low.min(price);
+                candle.close = price;
+                candle.volume_0 += volume_0;
+                candle.volume_1 += volume_1;
+                candle.trades += 1;
+            }
+            _ => candles.push(Candle {
+                open_time,
+                open: price,
+                high: price,
+                low: price,
+                close: price,
+                volume_0,
+                volume_1,
+                trades: 1,
+            }),
+        }
+    }
+    candles
+}
+
+fn candle_record(candle: &Candle) -> String {
+    format!(
+        "{},{},{},{},{},{},{},{}",
+        candle.open_time,
+        candle.open,
+        candle.high,
+        candle.low,
+        candle.close,
+        candle.volume_0,
+        candle.volume_1,
+        candle.trades
+    )
+}
+
+pub fn write_candles(path: &str, candles: &[Candle]) -> Result<()> {
+    let mut file = fs::File::create(path)?;
+    writeln!(file, "{}", CANDLES_HEADER)?;
+    for candle in candles {
+        writeln!(file, "{}", candle_record(candle))?;
+    }
+    Ok(())
+}
+
+pub fn print_candles(candles: &[Candle]) {
+    println!("{}", CANDLES_HEADER);
+    for candle in candles {
+        println!("{}", candle_record(candle));
+    }
+}
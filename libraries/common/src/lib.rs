@@ -8,3 +8,39 @@ pub mod common_utils;
 pub use common_utils::*;
 pub mod common_types;
 pub use common_types::*;
+pub mod offline;
+pub use offline::*;
+pub mod process_nonce_commands;
+pub use process_nonce_commands::*;
+pub mod confirmed_txn;
+pub use confirmed_txn::*;
+pub mod receipt;
+pub use receipt::*;
+pub mod indexer;
+pub use indexer::*;
+pub mod watch;
+pub use watch::*;
+pub mod oracle;
+pub use oracle::*;
+pub mod mint_check;
+pub use mint_check::*;
+pub mod amount;
+pub use amount::*;
+pub mod token_symbol;
+pub use token_symbol::*;
+pub mod pool_index;
+pub use pool_index::*;
+pub mod pool_summary;
+pub use pool_summary::*;
+pub mod preflight;
+pub use preflight::*;
+pub mod cost;
+pub use cost::*;
+pub mod cleanup;
+pub use cleanup::*;
+pub mod preview;
+pub use preview::*;
+pub mod simulation;
+pub use simulation::*;
+pub mod quote_check;
+pub use quote_check::*;
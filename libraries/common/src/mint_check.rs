@@ -0,0 +1,271 @@
+use crate::{
+    common_types::{CommonConfig, ExtensionStruct},
+    common_utils,
+};
+use anyhow::{format_err, Result};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{program_option::COption, pubkey::Pubkey};
+use spl_token_2022::{extension::transfer_fee::TransferFee, state::AccountState};
+use std::{
+    fmt,
+    io::{self, Write},
+};
+
+/// A configuration of the mint that lets its authorities take, lock or tax the tokens of the holders.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MintRisk {
+    /// The delegate can transfer or burn the tokens of any holder.
+    PermanentDelegate(Pubkey),
+    /// The authority can freeze the token account of any holder.
+    FreezeAuthority(Pubkey),
+    /// The new token accounts are frozen until the freeze authority thaws them.
+    DefaultFrozen,
+    /// The tokens can not be transferred out of the token account.
+    NonTransferable,
+    /// The authority can close the mint once the supply is zero.
+    MintCloseAuthority(Pubkey),
+    /// The program is invoked on every transfer and can reject it.
+    TransferHook(Pubkey),
+    /// The transfer fee of the current epoch is above the configured max.
+    HighTransferFee {
+        transfer_fee_bps: u16,
+        maximum_fee: u64,
+    },
+    /// The transfer fee is updated from the epoch.
+    ChangingTransferFee {
+        epoch: u64,
+        transfer_fee_bps: u16,
+        maximum_fee: u64,
+    },
+}
+
+impl fmt::Display for MintRisk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MintRisk::PermanentDelegate(delegate) => {
+                write!(f, "permanent delegate {} can move any balance", delegate)
+            }
+            MintRisk::FreezeAuthority(authority) => {
+                write!(f, "freeze authority {} can freeze any account", authority)
+            }
+            MintRisk::DefaultFrozen => write!(f, "new token accounts are frozen by default"),
+            MintRisk::NonTransferable => write!(f, "tokens are non-transferable"),
+            MintRisk::MintCloseAuthority(authority) => {
+                write!(f, "close authority {} can close the mint", authority)
+            }
+            MintRisk::TransferHook(program) => {
+                write!(f, "transfer hook {} runs on every transfer", program)
+            }
+            MintRisk::HighTransferFee {
+                transfer_fee_bps,
+                maximum_fee,
+            } => write!(
+                f,
+                "transfer fee {} bps, max fee {}",
+                transfer_fee_bps, maximum_fee
+            ),
+            MintRisk::ChangingTransferFee {
+                epoch,
+                transfer_fee_bps,
+                maximum_fee,
+            } => write!(
+                f,
+                "transfer fee changes to {} bps, max fee {} from epoch {}",
+                transfer_fee_bps, maximum_fee, epoch
+            ),
+        }
+    }
+}
+
+/// The base state, extensions and risks of a spl token or token 2022 mint.
+#[derive(Debug)]
+pub struct MintInspection {
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub decimals: u8,
+    pub supply: u64,
+    pub mint_authority: Option<Pubkey>,
+    pub freeze_authority: Option<Pubkey>,
+    /// The epoch the mint is inspected at.
+    pub epoch: u64,
+    pub extensions: Vec<ExtensionStruct>,
+    pub risks: Vec<MintRisk>,
+}
+
+fn coption_key(key: COption<Pubkey>) -> Option<Pubkey> {
+    match key {
+        COption::Some(key) => Some(key),
+        COption::None => None,
+    }
+}
+
+/// The transfer fee in bps and the max fee of the epoch.
+fn epoch_transfer_fee(transfer_fee: &TransferFee) -> (u16, u64) {
+    (
+        u16::from(transfer_fee.transfer_fee_basis_points),
+        u64::from(transfer_fee.maximum_fee),
+    )
+}
+
+/// Inspect the mint account at the epoch, the transfer fee above `max_transfer_fee_bps` is risky.
+pub fn inspect_mint_account(
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    data: &[u8],
+    epoch: u64,
+    max_transfer_fee_bps: u64,
+) -> Result<MintInspection> {
+    let mint_state = common_utils::unpack_mint(data)?;
+    let mint_authority = coption_key(mint_state.base.mint_authority);
+    let freeze_authority = coption_key(mint_state.base.freeze_authority);
+    let extensions = common_utils::get_account_extensions(&mint_state);
+
+    let mut risks = Vec::new();
+    if let Some(freeze_authority) = freeze_authority {
+        risks.push(MintRisk::FreezeAuthority(freeze_authority));
+    }
+    for extension in extensions.iter() {
+        match extension {
+            ExtensionStruct::PermanentDelegate(extension) => {
+                if let Some(delegate) = Option::<Pubkey>::from(extension.delegate) {
+                    risks.push(MintRisk::PermanentDelegate(delegate));
+                }
+            }
+            ExtensionStruct::DefaultAccountState(extension)
+                if extension.state == AccountState::Frozen as u8 =>
+            {
+                risks.push(MintRisk::DefaultFrozen);
+            }
+            ExtensionStruct::NonTransferable(_) => {
+                risks.push(MintRisk::NonTransferable);
+            }
+            ExtensionStruct::MintCloseAuthority(extension) => {
+                if let Some(authority) = Option::<Pubkey>::from(extension.close_authority) {
+                    risks.push(MintRisk::MintCloseAuthority(authority));
+                }
+            }
+            ExtensionStruct::TransferHook(extension) => {
+                if let Some(program) = Option::<Pubkey>::from(extension.program_id) {
+                    risks.push(MintRisk::TransferHook(program));
+                }
+            }
+            ExtensionStruct::TransferFeeConfig(extension) => {
+                let (transfer_fee_bps, maximum_fee) =
+                    epoch_transfer_fee(extension.get_epoch_fee(epoch));
+                if transfer_fee_bps as u64 > max_transfer_fee_bps {
+                    risks.push(MintRisk::HighTransferFee {
+                        transfer_fee_bps,
+                        maximum_fee,
+                    });
+                }
+                let newer_epoch = u64::from(extension.newer_transfer_fee.epoch);
+                let (newer_fee_bps, newer_maximum_fee) =
+                    epoch_transfer_fee(&extension.newer_transfer_fee);
+                if newer_epoch > epoch
+                    && (newer_fee_bps, newer_maximum_fee) != (transfer_fee_bps, maximum_fee)
+                {
+                    risks.push(MintRisk::ChangingTransferFee {
+                        epoch: newer_epoch,
+                        transfer_fee_bps: newer_fee_bps,
+                        maximum_fee: newer_maximum_fee,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(MintInspection {
+        mint: *mint,
+        token_program: *token_program,
+        decimals: mint_state.base.decimals,
+        supply: mint_state.base.supply,
+        mint_authority,
+        freeze_authority,
+        epoch,
+        extensions,
+        risks,
+    })
+}
+
+/// Fetch and inspect the mints at the current epoch.
+pub fn inspect_mints(
+    rpc_client: &RpcClient,
+    mints: &[Pubkey],
+    max_transfer_fee_bps: u64,
+) -> Result<Vec<MintInspection>> {
+    let epoch = rpc_client.get_epoch_info()?.epoch;
+    let rsps = rpc_client.get_multiple_accounts(mints)?;
+    let mut inspections = Vec::new();
+    for (mint, rsp) in mints.iter().zip(rsps) {
+        let account = rsp.ok_or(format_err!("mint {} not found", mint))?;
+        inspections.push(inspect_mint_account(
+            mint,
+            &account.owner,
+            &account.data,
+            epoch,
+            max_transfer_fee_bps,
+        )?);
+    }
+    Ok(inspections)
+}
+
+pub fn print_mint_inspection(inspection: &MintInspection) {
+    println!("mint:{}", inspection.mint);
+    println!("token_program:{}", inspection.token_program);
+    println!("decimals:{}", inspection.decimals);
+    println!("supply:{}", inspection.supply);
+    println!("mint_authority:{:?}", inspection.mint_authority);
+    println!("freeze_authority:{:?}", inspection.freeze_authority);
+    for extension in inspection.extensions.iter() {
+        println!("extension:{:?}", extension);
+        if let ExtensionStruct::TransferFeeConfig(extension) = extension {
+            let (transfer_fee_bps, maximum_fee) =
+                epoch_transfer_fee(extension.get_epoch_fee(inspection.epoch));
+            println!(
+                "transfer_fee_bps:{}, maximum_fee:{}, epoch:{}",
+                transfer_fee_bps, maximum_fee, inspection.epoch
+            );
+            let (transfer_fee_bps, maximum_fee) =
+                epoch_transfer_fee(extension.get_epoch_fee(inspection.epoch + 1));
+            println!(
+                "next_epoch_transfer_fee_bps:{}, next_epoch_maximum_fee:{}, epoch:{}",
+                transfer_fee_bps,
+                maximum_fee,
+                inspection.epoch + 1
+            );
+        }
+    }
+    if inspection.risks.is_empty() {
+        println!("no risk found");
+    }
+    for risk in inspection.risks.iter() {
+        println!("warning: {}", risk);
+    }
+}
+
+/// Warn about the risky mints of the trade and, if the config requires, ask the user to confirm.
+pub fn check_mint_risks(
+    rpc_client: &RpcClient,
+    config: &CommonConfig,
+    mints: &[Pubkey],
+) -> Result<()> {
+    let inspections = inspect_mints(rpc_client, mints, config.max_transfer_fee())?;
+    let mut risky = false;
+    for inspection in inspections.iter() {
+        for risk in inspection.risks.iter() {
+            println!("warning: mint {} {}", inspection.mint, risk);
+            risky = true;
+        }
+    }
+    if !risky || !config.confirm_risky_mint() {
+        return Ok(());
+    }
+    print!("trade the risky mints anyway? [y/N] ");
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    if !matches!(answer.trim(), "y" | "Y" | "yes") {
+        return Err(format_err!("trade of the risky mints is not confirmed"));
+    }
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use crate::common_types::TxnEncoding;
+use anyhow::{format_err, Result};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    pubkey::Pubkey,
+    sanitize::Sanitize,
+    signature::Signature,
+    signer::signers::Signers,
+    system_program,
+    transaction::{Transaction, VersionedTransaction},
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OfflineTransaction {
+    pub encoding: TxnEncoding,
+    pub transaction: String,
+    pub summary: TransactionSummary,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransactionSummary {
+    pub fee_payer: String,
+    pub recent_blockhash: String,
+    pub nonce_account: Option<String>,
+    pub signers: Vec<SignerStatus>,
+    pub accounts: Vec<AccountSummary>,
+    pub instructions: Vec<InstructionSummary>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignerStatus {
+    pub pubkey: String,
+    pub signed: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AccountSummary {
+    pub pubkey: String,
+    pub signer: bool,
+    pub writable: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InstructionSummary {
+    pub program_id: String,
+    pub accounts: Vec<String>,
+    pub data: String,
+}
+
+pub fn encode_txn(txn: &Transaction, encoding: TxnEncoding) -> Result<String> {
+    let serialized = bincode::serialize(txn)?;
+    let encoded = match encoding {
+        TxnEncoding::Base58 => bs58::encode(serialized).into_string(),
+        TxnEncoding::Base64 => BASE64_STANDARD.encode(serialized),
+    };
+    Ok(encoded)
+}
+
+/// The client builds legacy transactions, a v0 transaction is refused.
+pub fn decode_txn(data: &str, encoding: TxnEncoding) -> Result<Transaction> {
+    let serialized = match encoding {
+        TxnEncoding::Base58 => bs58::decode(data.trim()).into_vec()?,
+        TxnEncoding::Base64 => BASE64_STANDARD.decode(data.trim())?,
+    };
+    let versioned_txn: VersionedTransaction = bincode::deserialize(&serialized)?;
+    let txn = versioned_txn.into_legacy_transaction().ok_or_else(|| {
+        format_err!(
+            "v0 transactions are not supported, only legacy transactions can be signed offline"
+        )
+    })?;
+    txn.sanitize()?;
+    Ok(txn)
+}
+
+pub fn summarize_txn(txn: &Transaction) -> TransactionSummary {
+    let message = &txn.message;
+    let signers = message
+        .account_keys
+        .iter()
+        .take(message.header.num_required_signatures as usize)
+        .zip(txn.signatures.iter())
+        .map(|(pubkey, signature)| SignerStatus {
+            pubkey: pubkey.to_string(),
+            signed: *signature != Signature::default(),
+        })
+        .collect();
+    let accounts = message
+        .account_keys
+        .iter()
+        .enumerate()
+        .map(|(index, pubkey)| AccountSummary {
+            pubkey: pubkey.to_string(),
+            signer: message.is_signer(index),
+            writable: message.is_writable(index),
+        })
+        .collect();
+    let instructions = message
+        .instructions
+        .iter()
+        .map(|ix| InstructionSummary {
+            program_id: message.account_keys[ix.program_id_index as usize].to_string(),
+            accounts: ix
+                .accounts
+                .iter()
+                .map(|index| message.account_keys[*index as usize].to_string())
+                .collect(),
+            data: hex::encode(&ix.data),
+        })
+        .collect();
+    TransactionSummary {
+        fee_payer: message.account_keys[0].to_string(),
+        recent_blockhash: message.recent_blockhash.to_string(),
+        nonce_account: get_nonce_account(txn).map(|nonce| nonce.to_string()),
+        signers,
+        accounts,
+        instructions,
+    }
+}
+
+/// Returns the nonce account if the first instruction is `advance_nonce_account`.
+pub fn get_nonce_account(txn: &Transaction) -> Option<Pubkey> {
+    let message = &txn.message;
+    let ix = message.instructions.first()?;
+    let program_id = message.account_keys.get(ix.program_id_index as usize)?;
+    // SystemInstruction::AdvanceNonceAccount
+    if *program_id == system_program::id() && ix.data.starts_with(&[4, 0, 0, 0]) {
+        let nonce_index = *ix.accounts.first()?;
+        message.account_keys.get(nonce_index as usize).copied()
+    } else {
+        None
+    }
+}
+
+pub fn export_txn(txn: &Transaction, encoding: TxnEncoding) -> Result<OfflineTransaction> {
+    Ok(OfflineTransaction {
+        encoding,
+        transaction: encode_txn(txn, encoding)?,
+        summary: summarize_txn(txn),
+    })
+}
+
+pub fn write_offline_txn(
+    offline_txn: &OfflineTransaction,
+    output_path: Option<&str>,
+) -> Result<()> {
+    let content = serde_json::to_string_pretty(offline_txn)?;
+    if let Some(output_path) = output_path {
+        std::fs::write(output_path, content)?;
+        println!("transaction exported to {}", output_path);
+    } else {
+        println!("{}", content);
+    }
+    Ok(())
+}
+
+/// Read the exported transaction from a file, or decode the raw transaction blob if the input is not a file.
+pub fn read_offline_txn(input: &str, encoding: TxnEncoding) -> Result<Transaction> {
+    if std::path::Path::new(input).exists() {
+        let content = std::fs::read_to_string(input)?;
+        match serde_json::from_str::<OfflineTransaction>(&content) {
+            Ok(offline_txn) => decode_txn(&offline_txn.transaction, offline_txn.encoding),
+            Err(_) => decode_txn(&content, encoding),
+        }
+    } else {
+        decode_txn(input, encoding)
+    }
+}
+
+pub fn sign_offline_txn(txn: &mut Transaction, signing_keypairs: &dyn Signers) -> Result<()> {
+    let blockhash = txn.message.recent_blockhash;
+    let positions = txn.get_signing_keypair_positions(&signing_keypairs.pubkeys())?;
+    if positions.iter().any(|position| position.is_none()) {
+        return Err(format_err!(
+            "keypair is not a required signer of the transaction"
+        ));
+    }
+    // `try_partial_sign` keeps the existing signatures because the blockhash is not changed.
+    txn.try_partial_sign(signing_keypairs, blockhash)?;
+    Ok(())
+}
+
+pub fn unsigned_signers(txn: &Transaction) -> Vec<Pubkey> {
+    txn.message
+        .account_keys
+        .iter()
+        .zip(txn.signatures.iter())
+        .filter(|(_, signature)| **signature == Signature::default())
+        .map(|(pubkey, _)| *pubkey)
+        .collect()
+}
@@ -0,0 +1,210 @@
+use crate::{
+    common_types::{OracleSource, TEN_THOUSAND},
+    PoolMints,
+};
+use anyhow::{format_err, Result};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{pubkey, pubkey::Pubkey};
+use std::{
+    convert::TryInto,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The pyth push oracle program owning the price accounts.
+#[cfg(not(feature = "devnet"))]
+const PYTH_ORACLE_PROGRAM: Pubkey = pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH");
+#[cfg(feature = "devnet")]
+const PYTH_ORACLE_PROGRAM: Pubkey = pubkey!("gSbePebfvPy7tRqimPoVecS2UsBvYv46ynrzWocc92s");
+/// The pyth receiver program owning the price update accounts of the pull oracle.
+const PYTH_RECEIVER_PROGRAM: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+/// The anchor discriminator of the `PriceUpdateV2` account of the pull oracle.
+const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+/// The magic number of the pyth push oracle price account.
+const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+/// The trading status of the aggregate price of the pyth push oracle.
+const PYTH_STATUS_TRADING: u32 = 1;
+/// The max slots the aggregate price of the pyth push oracle is published before.
+const PYTH_MAX_SLOT_AGE: u64 = 50;
+/// The max seconds the price update of the pyth pull oracle is published before.
+const PYTH_MAX_PRICE_AGE: i64 = 60;
+
+/// The time weighted average price of the pool read from its observation account.
+/// The prices are token 0 quoted in token 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolTwap {
+    pub pool_id: Pubkey,
+    pub mints: PoolMints,
+    pub spot_price: f64,
+    pub twap_price: f64,
+    /// The block timestamp of the observation the window starts from.
+    pub window_start: u64,
+    /// The block timestamp of the newest observation.
+    pub window_end: u64,
+}
+
+impl PoolTwap {
+    /// The deviation in bps of the spot price from the twap, positive if the spot is higher.
+    pub fn deviation_bps(&self) -> f64 {
+        if self.twap_price == 0.0 {
+            return 0.0;
+        }
+        (self.spot_price - self.twap_price) / self.twap_price * TEN_THOUSAND as f64
+    }
+}
+
+/// Walk back the ring buffer from the newest observation to the newest one at or before the
+/// start of the window, or to the oldest one if the buffer does not cover the window.
+/// Returns `None` if there is no older observation than the newest one.
+pub fn observation_window_start(
+    timestamps: &[u64],
+    newest_index: usize,
+    window: u64,
+) -> Option<usize> {
+    let len = timestamps.len();
+    let target = timestamps[newest_index].saturating_sub(window);
+    let mut start_index = newest_index;
+    for i in 1..len {
+        let index = (newest_index + len - i) % len;
+        // the observation is not written yet
+        if timestamps[index] == 0 || timestamps[index] >= timestamps[start_index] {
+            break;
+        }
+        start_index = index;
+        if timestamps[index] <= target {
+            break;
+        }
+    }
+    if start_index == newest_index {
+        None
+    } else {
+        Some(start_index)
+    }
+}
+
+pub fn print_pool_twap(pool_twap: &PoolTwap, window: u64, now: u64) {
+    println!(
+        "pool:{}, mint_0:{}, mint_1:{}",
+        pool_twap.pool_id, pool_twap.mints.mint_0, pool_twap.mints.mint_1
+    );
+    println!(
+        "spot_price:{}, twap_price:{}, deviation_bps:{:.2}",
+        pool_twap.spot_price,
+        pool_twap.twap_price,
+        pool_twap.deviation_bps()
+    );
+    println!(
+        "window:{}s, requested_window:{}s, newest_observation_age:{}s",
+        pool_twap.window_end - pool_twap.window_start,
+        window,
+        now.saturating_sub(pool_twap.window_end)
+    );
+}
+
+fn read_i64(data: &[u8], offset: usize) -> i64 {
+    i64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn read_i32(data: &[u8], offset: usize) -> i32 {
+    i32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+/// The price of the pyth price account, either a price account of the push oracle or a price
+/// update account of the pull oracle. Stale or halted prices are rejected.
+pub fn get_pyth_price(rpc_client: &RpcClient, account: &Pubkey) -> Result<f64> {
+    let price_account = rpc_client.get_account(account)?;
+    let data = &price_account.data;
+    if price_account.owner == PYTH_ORACLE_PROGRAM {
+        if data.len() < 240 || u32::from_le_bytes(data[0..4].try_into().unwrap()) != PYTH_MAGIC {
+            return Err(format_err!("{} is not a pyth price account", account));
+        }
+        let expo = read_i32(data, 20);
+        let price = read_i64(data, 208);
+        let status = u32::from_le_bytes(data[224..228].try_into().unwrap());
+        let pub_slot = u64::from_le_bytes(data[232..240].try_into().unwrap());
+        if status != PYTH_STATUS_TRADING {
+            return Err(format_err!("the pyth price {} is not trading", account));
+        }
+        if rpc_client.get_slot()?.saturating_sub(pub_slot) > PYTH_MAX_SLOT_AGE {
+            return Err(format_err!("the pyth price {} is stale", account));
+        }
+        return Ok(price as f64 * 10f64.powi(expo));
+    }
+    if price_account.owner != PYTH_RECEIVER_PROGRAM
+        || !data.starts_with(&PRICE_UPDATE_V2_DISCRIMINATOR)
+        || data.len() < 42
+    {
+        return Err(format_err!("{} is not a pyth price account", account));
+    }
+    // discriminator, write authority and the verification level, which is one byte if full
+    // or two bytes if partial
+    let offset = if data[40] == 0 { 42 } else { 41 };
+    if data.len() < offset + 68 {
+        return Err(format_err!("{} is not a pyth price account", account));
+    }
+    // skip the feed id
+    let price = read_i64(data, offset + 32);
+    let expo = read_i32(data, offset + 48);
+    let publish_time = read_i64(data, offset + 52);
+    let now: i64 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)?
+        .as_secs()
+        .try_into()?;
+    if now.saturating_sub(publish_time) > PYTH_MAX_PRICE_AGE {
+        return Err(format_err!("the pyth price {} is stale", account));
+    }
+    Ok(price as f64 * 10f64.powi(expo))
+}
+
+/// The reference price of token 0 quoted in token 1 from the pyth accounts or the file.
+/// The twap source depends on the pool program, so it is resolved by the caller.
+pub fn get_reference_price(rpc_client: &RpcClient, source: &OracleSource) -> Result<f64> {
+    match source {
+        OracleSource::Twap => Err(format_err!("the twap is read from the pool")),
+        OracleSource::Pyth(account_0, account_1) => {
+            let price_0 = get_pyth_price(rpc_client, account_0)?;
+            let price_1 = match account_1 {
+                Some(account_1) => get_pyth_price(rpc_client, account_1)?,
+                None => 1.0,
+            };
+            if price_1 <= 0.0 {
+                return Err(format_err!("invalid pyth price {}", price_1));
+            }
+            Ok(price_0 / price_1)
+        }
+        OracleSource::File(path) => {
+            let price = std::fs::read_to_string(path)?
+                .trim()
+                .parse::<f64>()
+                .map_err(|e| format_err!("invalid price in {}: {}", path, e))?;
+            Ok(price)
+        }
+    }
+}
+
+/// Refuse the swap if the pool price deviates more than `max_deviation_bps` from the reference.
+pub fn check_price_deviation(
+    pool_id: &Pubkey,
+    price: f64,
+    reference_price: f64,
+    max_deviation_bps: u64,
+) -> Result<()> {
+    if reference_price <= 0.0 {
+        return Err(format_err!("invalid reference price {}", reference_price));
+    }
+    let deviation_bps = (price - reference_price) / reference_price * TEN_THOUSAND as f64;
+    if deviation_bps.abs() > max_deviation_bps as f64 {
+        return Err(format_err!(
+            "the price {} of the pool {} deviates {:.2} bps from the reference price {}, exceeds the max deviation {} bps",
+            price,
+            pool_id,
+            deviation_bps,
+            reference_price,
+            max_deviation_bps
+        ));
+    }
+    println!(
+        "oracle check passed, price:{}, reference_price:{}, deviation_bps:{:.2}",
+        price, reference_price, deviation_bps
+    );
+    Ok(())
+}
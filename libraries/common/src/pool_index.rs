@@ -0,0 +1,376 @@
+use crate::{common_types::CommonConfig, indexer::PoolMints, rpc};
+use anyhow::{format_err, Result};
+use solana_client::{rpc_client::RpcClient, rpc_filter::RpcFilterType};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, fs,
+    io::{BufRead, BufReader},
+    path::Path,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const POOLS_HEADER: &str = "program,pool_id,mint_0,mint_1,decimals_0,decimals_1,fee_config,vault_0,vault_1,lp_mint,observation,open_orders,target_orders,market_program,market,nonce";
+
+/// The max accounts of a `get_multiple_accounts` request.
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+/// The pages of new pools fetched between two saves of the index, so an interrupted build resumes.
+const SAVE_INTERVAL_PAGES: usize = 50;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PoolProgram {
+    Amm,
+    CpSwap,
+    Clmm,
+}
+
+impl PoolProgram {
+    pub const ALL: [PoolProgram; 3] = [PoolProgram::Amm, PoolProgram::CpSwap, PoolProgram::Clmm];
+}
+
+impl fmt::Display for PoolProgram {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PoolProgram::Amm => write!(f, "amm"),
+            PoolProgram::CpSwap => write!(f, "cpswap"),
+            PoolProgram::Clmm => write!(f, "clmm"),
+        }
+    }
+}
+
+impl FromStr for PoolProgram {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "amm" => Ok(PoolProgram::Amm),
+            "cpswap" => Ok(PoolProgram::CpSwap),
+            "clmm" => Ok(PoolProgram::Clmm),
+            _ => Err(format_err!("invalid pool program {}", s)),
+        }
+    }
+}
+
+/// The keys of the amm pool and its openbook market.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AmmIndexKeys {
+    pub open_orders: Pubkey,
+    pub target_orders: Pubkey,
+    pub market_program: Pubkey,
+    pub market: Pubkey,
+    pub nonce: u64,
+}
+
+/// The keys of the pool fixed at its creation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexedPool {
+    pub program: PoolProgram,
+    pub pool_id: Pubkey,
+    pub mint_0: Pubkey,
+    pub mint_1: Pubkey,
+    pub decimals_0: u8,
+    pub decimals_1: u8,
+    /// The amm config holding the fee rates, default for the amm pools keeping the fees in the pool.
+    pub fee_config: Pubkey,
+    pub vault_0: Pubkey,
+    pub vault_1: Pubkey,
+    /// Default for the clmm pools.
+    pub lp_mint: Pubkey,
+    /// Default for the amm pools.
+    pub observation: Pubkey,
+    /// Only for the amm pools.
+    pub amm_keys: Option<AmmIndexKeys>,
+}
+
+impl IndexedPool {
+    pub fn pool_mints(&self) -> PoolMints {
+        PoolMints {
+            mint_0: self.mint_0,
+            mint_1: self.mint_1,
+            decimals_0: self.decimals_0,
+            decimals_1: self.decimals_1,
+        }
+    }
+
+    fn matches(&self, mint_a: Option<&Pubkey>, mint_b: Option<&Pubkey>) -> bool {
+        match (mint_a, mint_b) {
+            (None, None) => true,
+            (Some(mint), None) | (None, Some(mint)) => self.mint_0 == *mint || self.mint_1 == *mint,
+            (Some(mint_a), Some(mint_b)) => {
+                (self.mint_0 == *mint_a && self.mint_1 == *mint_b)
+                    || (self.mint_0 == *mint_b && self.mint_1 == *mint_a)
+            }
+        }
+    }
+}
+
+/// The pools of the raydium programs and when the pools of each program were last listed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PoolIndex {
+    pub pools: Vec<IndexedPool>,
+    pub refreshed_at: HashMap<PoolProgram, i64>,
+}
+
+impl PoolIndex {
+    pub fn get(&self, pool_id: &Pubkey) -> Option<&IndexedPool> {
+        self.pools.iter().find(|pool| pool.pool_id == *pool_id)
+    }
+
+    /// The pools of the program, or of all the programs if none is given, holding the mint or
+    /// the pair of mints in either order.
+    pub fn find_pools(
+        &self,
+        program: Option<PoolProgram>,
+        mint_a: Option<&Pubkey>,
+        mint_b: Option<&Pubkey>,
+    ) -> Vec<&IndexedPool> {
+        self.pools
+            .iter()
+            .filter(|pool| program.is_none_or(|program| pool.program == program))
+            .filter(|pool| pool.matches(mint_a, mint_b))
+            .collect()
+    }
+
+    /// Whether the pools of the program were never listed or listed more than `ttl` seconds ago.
+    pub fn is_stale(&self, program: PoolProgram, ttl: u64) -> bool {
+        match self.refreshed_at.get(&program) {
+            Some(refreshed_at) => unix_timestamp() - refreshed_at > ttl as i64,
+            None => true,
+        }
+    }
+}
+
+fn unix_timestamp() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+fn refreshed_path(path: &str) -> String {
+    format!("{}.refreshed", path)
+}
+
+fn optional_field(value: Option<String>) -> String {
+    value.unwrap_or_default()
+}
+
+fn pool_record(pool: &IndexedPool) -> String {
+    let amm_keys = pool.amm_keys.as_ref();
+    format!(
+        "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+        pool.program,
+        pool.pool_id,
+        pool.mint_0,
+        pool.mint_1,
+        pool.decimals_0,
+        pool.decimals_1,
+        pool.fee_config,
+        pool.vault_0,
+        pool.vault_1,
+        pool.lp_mint,
+        pool.observation,
+        optional_field(amm_keys.map(|keys| keys.open_orders.to_string())),
+        optional_field(amm_keys.map(|keys| keys.target_orders.to_string())),
+        optional_field(amm_keys.map(|keys| keys.market_program.to_string())),
+        optional_field(amm_keys.map(|keys| keys.market.to_string())),
+        optional_field(amm_keys.map(|keys| keys.nonce.to_string())),
+    )
+}
+
+fn parse_pool_record(line: &str) -> Result<IndexedPool> {
+    let fields: Vec<&str> = line.split(',').collect();
+    if fields.len() != 16 {
+        return Err(format_err!("invalid pool record: {}", line));
+    }
+    let amm_keys = if fields[11].is_empty() {
+        None
+    } else {
+        Some(AmmIndexKeys {
+            open_orders: Pubkey::from_str(fields[11])?,
+            target_orders: Pubkey::from_str(fields[12])?,
+            market_program: Pubkey::from_str(fields[13])?,
+            market: Pubkey::from_str(fields[14])?,
+            nonce: fields[15].parse()?,
+        })
+    };
+    Ok(IndexedPool {
+        program: PoolProgram::from_str(fields[0])?,
+        pool_id: Pubkey::from_str(fields[1])?,
+        mint_0: Pubkey::from_str(fields[2])?,
+        mint_1: Pubkey::from_str(fields[3])?,
+        decimals_0: fields[4].parse()?,
+        decimals_1: fields[5].parse()?,
+        fee_config: Pubkey::from_str(fields[6])?,
+        vault_0: Pubkey::from_str(fields[7])?,
+        vault_1: Pubkey::from_str(fields[8])?,
+        lp_mint: Pubkey::from_str(fields[9])?,
+        observation: Pubkey::from_str(fields[10])?,
+        amm_keys,
+    })
+}
+
+pub fn load_pool_index(path: &str) -> Result<PoolIndex> {
+    let mut index = PoolIndex::default();
+    if !Path::new(path).exists() {
+        return Ok(index);
+    }
+    for line in fs::read_to_string(path)?.lines().skip(1) {
+        index.pools.push(parse_pool_record(line)?);
+    }
+    let refreshed = refreshed_path(path);
+    if Path::new(&refreshed).exists() {
+        for line in fs::read_to_string(&refreshed)?.lines() {
+            if let Some((program, value)) = line.split_once('=') {
+                index
+                    .refreshed_at
+                    .insert(PoolProgram::from_str(program)?, value.parse()?);
+            }
+        }
+    }
+    Ok(index)
+}
+
+pub fn save_pool_index(path: &str, index: &PoolIndex) -> Result<()> {
+    let mut content = format!("{}\n", POOLS_HEADER);
+    for pool in index.pools.iter() {
+        content.push_str(&pool_record(pool));
+        content.push('\n');
+    }
+    fs::write(path, content)?;
+    let mut refreshed = String::new();
+    for program in PoolProgram::ALL {
+        if let Some(refreshed_at) = index.refreshed_at.get(&program) {
+            refreshed.push_str(&format!("{}={}\n", program, refreshed_at));
+        }
+    }
+    fs::write(refreshed_path(path), refreshed)?;
+    Ok(())
+}
+
+/// Look up the pool in the index file line by line without loading the whole index.
+pub fn find_indexed_pool(path: &str, pool_id: &Pubkey) -> Result<Option<IndexedPool>> {
+    if !Path::new(path).exists() {
+        return Ok(None);
+    }
+    let pool_id = pool_id.to_string();
+    for line in BufReader::new(fs::File::open(path)?).lines().skip(1) {
+        let line = line?;
+        if line.split(',').nth(1) == Some(pool_id.as_str()) {
+            return parse_pool_record(&line).map(Some);
+        }
+    }
+    Ok(None)
+}
+
+/// The pool in the index of the config, none if there is no index or the pool is not indexed.
+pub fn get_indexed_pool(config: &CommonConfig, pool_id: &Pubkey) -> Result<Option<IndexedPool>> {
+    match config.pool_index() {
+        Some(path) => find_indexed_pool(&path, pool_id),
+        None => Ok(None),
+    }
+}
+
+/// The mints of the pool from the index of the config, fetched by `fetch` if the pool is not indexed.
+pub fn get_indexed_pool_mints(
+    config: &CommonConfig,
+    pool_id: &Pubkey,
+    fetch: impl FnOnce() -> Result<PoolMints>,
+) -> Result<PoolMints> {
+    match get_indexed_pool(config, pool_id)? {
+        Some(pool) => Ok(pool.pool_mints()),
+        None => fetch(),
+    }
+}
+
+/// Add the pools of the program missing in the index and drop the closed ones.
+/// The pool ids are listed with the data sliced off and only the new pools are fetched,
+/// in pages of `get_multiple_accounts`. The pools failed to decode are skipped.
+/// Returns the number of the added and removed pools.
+pub fn refresh_program_pools(
+    rpc_client: &RpcClient,
+    path: &str,
+    index: &mut PoolIndex,
+    program: PoolProgram,
+    program_id: &Pubkey,
+    pool_len: u64,
+    decode: impl Fn(&Pubkey, &Account) -> Result<IndexedPool>,
+) -> Result<(usize, usize)> {
+    let pool_ids = rpc::get_program_account_keys(
+        rpc_client,
+        *program_id,
+        Some(vec![RpcFilterType::DataSize(pool_len)]),
+    )?;
+    let listed: HashSet<Pubkey> = pool_ids.iter().copied().collect();
+    let pools_len = index.pools.len();
+    index
+        .pools
+        .retain(|pool| pool.program != program || listed.contains(&pool.pool_id));
+    let removed = pools_len - index.pools.len();
+    let known: HashSet<Pubkey> = index
+        .pools
+        .iter()
+        .filter(|pool| pool.program == program)
+        .map(|pool| pool.pool_id)
+        .collect();
+    let new_pool_ids: Vec<Pubkey> = pool_ids
+        .into_iter()
+        .filter(|pool_id| !known.contains(pool_id))
+        .collect();
+    println!(
+        "{} pools: {} listed, {} new, {} removed",
+        program,
+        listed.len(),
+        new_pool_ids.len(),
+        removed
+    );
+    let mut added = 0;
+    for (page, chunk) in new_pool_ids.chunks(MAX_MULTIPLE_ACCOUNTS).enumerate() {
+        let accounts = rpc_client.get_multiple_accounts(chunk)?;
+        for (pool_id, account) in chunk.iter().zip(accounts) {
+            if let Some(pool) = account.and_then(|account| decode(pool_id, &account).ok()) {
+                index.pools.push(pool);
+                added += 1;
+            }
+        }
+        if (page + 1) % SAVE_INTERVAL_PAGES == 0 {
+            save_pool_index(path, index)?;
+        }
+    }
+    index.refreshed_at.insert(program, unix_timestamp());
+    save_pool_index(path, index)?;
+    Ok((added, removed))
+}
+
+/// The accounts of the pools of the program in the index at `path` holding the mints in either
+/// order. The pools of the program are refreshed by `refresh` first once older than the ttl.
+pub fn fetch_indexed_pools(
+    rpc_client: &RpcClient,
+    config: &CommonConfig,
+    path: &str,
+    program: PoolProgram,
+    mint_a: Option<&Pubkey>,
+    mint_b: Option<&Pubkey>,
+    refresh: impl FnOnce(&mut PoolIndex) -> Result<(usize, usize)>,
+) -> Result<Vec<(Pubkey, Account)>> {
+    let mut index = load_pool_index(path)?;
+    if index.is_stale(program, config.pool_index_ttl()) {
+        refresh(&mut index)?;
+    }
+    let pool_ids: Vec<Pubkey> = index
+        .find_pools(Some(program), mint_a, mint_b)
+        .iter()
+        .map(|pool| pool.pool_id)
+        .collect();
+    let mut pools = Vec::new();
+    for chunk in pool_ids.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let accounts = rpc_client.get_multiple_accounts(chunk)?;
+        for (pool_id, account) in chunk.iter().zip(accounts) {
+            if let Some(account) = account {
+                pools.push((*pool_id, account));
+            }
+        }
+    }
+    Ok(pools)
+}
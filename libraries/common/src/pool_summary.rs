@@ -0,0 +1,218 @@
+use crate::{
+    amount::raw_amount_to_ui,
+    token_symbol::{mint_label, TokenSymbol},
+    watch::PoolSnapshot,
+};
+use anyhow::{format_err, Result};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::HashMap,
+    fmt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The denominator of the fee rates, 1_000_000 is 100%.
+pub const FEE_RATE_DENOMINATOR: u64 = 1_000_000;
+
+/// An operation of the pool which its status may disable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoolOperation {
+    Swap,
+    Deposit,
+    Withdraw,
+    OpenPosition,
+    CollectFee,
+    CollectReward,
+}
+
+impl fmt::Display for PoolOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PoolOperation::Swap => write!(f, "swap"),
+            PoolOperation::Deposit => write!(f, "deposit"),
+            PoolOperation::Withdraw => write!(f, "withdraw"),
+            PoolOperation::OpenPosition => write!(f, "open_position"),
+            PoolOperation::CollectFee => write!(f, "collect_fee"),
+            PoolOperation::CollectReward => write!(f, "collect_reward"),
+        }
+    }
+}
+
+/// The status flags of the pool decoded into the operations, and the time the pool opens
+/// for swaps.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolStatus {
+    /// The raw `AmmStatus` of the amm pool, or the status bits of the cp-swap and clmm pools.
+    pub status: u64,
+    pub operations: Vec<(PoolOperation, bool)>,
+    pub open_time: u64,
+}
+
+impl PoolStatus {
+    /// Whether the status enables the operation, the operations the pool does not have are disabled.
+    pub fn is_enabled(&self, operation: PoolOperation) -> bool {
+        self.operations
+            .iter()
+            .any(|(known, enabled)| *known == operation && *enabled)
+    }
+
+    /// The seconds until the pool opens for swaps, none once it is open.
+    pub fn opens_in(&self, now: u64) -> Option<u64> {
+        if self.open_time > now {
+            Some(self.open_time - now)
+        } else {
+            None
+        }
+    }
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// The preflight of the write commands: refuse the operation the status of the pool disables,
+/// and a swap before the pool opens.
+pub fn check_pool_status(
+    pool_id: &Pubkey,
+    status: &PoolStatus,
+    operation: PoolOperation,
+) -> Result<()> {
+    if !status.is_enabled(operation) {
+        let enabled: Vec<String> = status
+            .operations
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(operation, _)| operation.to_string())
+            .collect();
+        return Err(format_err!(
+            "{} is disabled by the status {} of pool {}, enabled operations: [{}]",
+            operation,
+            status.status,
+            pool_id,
+            enabled.join(", ")
+        ));
+    }
+    if operation == PoolOperation::Swap {
+        if let Some(seconds) = status.opens_in(unix_timestamp()) {
+            return Err(format_err!(
+                "pool {} opens for swaps at {}, in {}",
+                pool_id,
+                status.open_time,
+                format_duration(seconds)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The state of the pool for the summary of `fetch-pool`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolSummary {
+    /// The price and the reserves net of the protocol and fund fees.
+    pub snapshot: PoolSnapshot,
+    /// None for the clmm pools, whose positions are nfts.
+    pub lp_supply: Option<u64>,
+    /// The fee rates over `FEE_RATE_DENOMINATOR`.
+    pub fee_rates: Vec<(&'static str, u64)>,
+    pub status: PoolStatus,
+}
+
+/// Format the seconds as days, hours, minutes and seconds, e.g. `1d 2h 3m 4s`.
+pub fn format_duration(seconds: u64) -> String {
+    let (days, hours, minutes) = (seconds / 86400, seconds % 86400 / 3600, seconds % 3600 / 60);
+    if days > 0 {
+        format!("{}d {}h {}m {}s", days, hours, minutes, seconds % 60)
+    } else if hours > 0 {
+        format!("{}h {}m {}s", hours, minutes, seconds % 60)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds % 60)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+fn fee_rate_percent(fee_rate: u64) -> String {
+    format!("{}%", raw_amount_to_ui(fee_rate, 4))
+}
+
+fn token_name(symbols: &HashMap<Pubkey, TokenSymbol>, mint: &Pubkey) -> String {
+    match symbols.get(mint) {
+        Some(token) if !token.symbol.is_empty() => token.symbol.clone(),
+        _ => mint.to_string(),
+    }
+}
+
+pub fn print_pool_summary(summary: &PoolSummary, symbols: &HashMap<Pubkey, TokenSymbol>) {
+    let now = unix_timestamp();
+    let snapshot = &summary.snapshot;
+    let mints = &snapshot.mints;
+    let (name_0, name_1) = (
+        token_name(symbols, &mints.mint_0),
+        token_name(symbols, &mints.mint_1),
+    );
+    println!("pool_id:{}", snapshot.pool_id);
+    println!(
+        "mint_0:{}, decimals:{}",
+        mint_label(symbols, &mints.mint_0),
+        mints.decimals_0
+    );
+    println!(
+        "mint_1:{}, decimals:{}",
+        mint_label(symbols, &mints.mint_1),
+        mints.decimals_1
+    );
+    let inverse_price = if snapshot.price == 0.0 {
+        0.0
+    } else {
+        1.0 / snapshot.price
+    };
+    println!("price: 1 {} = {} {}", name_0, snapshot.price, name_1);
+    println!("price: 1 {} = {} {}", name_1, inverse_price, name_0);
+    println!(
+        "reserves: {} {}, {} {}",
+        raw_amount_to_ui(snapshot.reserve_0, mints.decimals_0),
+        name_0,
+        raw_amount_to_ui(snapshot.reserve_1, mints.decimals_1),
+        name_1
+    );
+    if let Some(lp_supply) = summary.lp_supply {
+        println!("lp_supply:{}", lp_supply);
+    }
+    let fee_rates: Vec<String> = summary
+        .fee_rates
+        .iter()
+        .map(|(name, fee_rate)| format!("{} {}", name, fee_rate_percent(*fee_rate)))
+        .collect();
+    println!("fees: {}", fee_rates.join(", "));
+    let operations: Vec<String> = summary
+        .status
+        .operations
+        .iter()
+        .map(|(operation, enabled)| {
+            format!(
+                "{} {}",
+                operation,
+                if *enabled { "enabled" } else { "disabled" }
+            )
+        })
+        .collect();
+    println!(
+        "status:{} ({})",
+        summary.status.status,
+        operations.join(", ")
+    );
+    match summary.status.opens_in(now) {
+        Some(seconds) => println!(
+            "open_time:{} (opens in {})",
+            summary.status.open_time,
+            format_duration(seconds)
+        ),
+        None => println!("open_time:{} (open)", summary.status.open_time),
+    }
+    if let (Some(liquidity), Some(tick_current)) = (snapshot.liquidity, snapshot.tick_current) {
+        println!("liquidity:{}, tick_current:{}", liquidity, tick_current);
+    }
+}
@@ -0,0 +1,290 @@
+use crate::{amount::raw_amount_to_ui, common_utils};
+use anyhow::{format_err, Result};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{account::Account, program_pack::Pack, pubkey::Pubkey};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_token_2022::{
+    extension::{BaseStateWithExtensions, ExtensionType},
+    state::AccountState,
+};
+use std::collections::HashMap;
+
+/// The lamports allowed for the network and priority fees of the transaction.
+pub const FEE_ALLOWANCE_LAMPORTS: u64 = 50_000;
+
+/// A token the instruction may pull from the user.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenDebit {
+    /// None for the associated token account of the wallet. For the native mint, None or the
+    /// native mint stands for the sol of the wallet wrapped into a temporary account.
+    pub token_account: Option<Pubkey>,
+    pub mint: Pubkey,
+    /// The maximum amount the instruction may pull, with the slippage and the transfer fee.
+    pub amount: u64,
+}
+
+/// A token account the instruction pays into, created by the command when it is missing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenCredit {
+    /// None for the associated token account of the wallet, or a temporary wsol account for
+    /// the native mint.
+    pub token_account: Option<Pubkey>,
+    pub mint: Pubkey,
+}
+
+/// An account the instruction creates, the wallet pays its rent.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccountRent {
+    pub item: String,
+    /// The account is only created if it does not exist, e.g. the tick arrays of a position.
+    pub address: Option<Pubkey>,
+    pub space: usize,
+}
+
+/// The funds the transaction of a command requires from the wallet.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FundsCheck {
+    pub debits: Vec<TokenDebit>,
+    pub credits: Vec<TokenCredit>,
+    /// The lamports the instruction spends besides the token accounts, e.g. the pool creation fee.
+    pub lamports: Vec<(String, u64)>,
+    /// The accounts the instruction creates besides the token accounts of the wallet.
+    pub new_accounts: Vec<AccountRent>,
+}
+
+impl FundsCheck {
+    pub fn debit(&mut self, token_account: Option<Pubkey>, mint: Pubkey, amount: u64) {
+        self.debits.push(TokenDebit {
+            token_account,
+            mint,
+            amount,
+        });
+    }
+
+    pub fn credit(&mut self, token_account: Option<Pubkey>, mint: Pubkey) {
+        self.credits.push(TokenCredit {
+            token_account,
+            mint,
+        });
+    }
+
+    pub fn spend(&mut self, item: &str, lamports: u64) {
+        self.lamports.push((item.to_string(), lamports));
+    }
+
+    pub fn rent(&mut self, item: &str, space: usize) {
+        self.new_accounts.push(AccountRent {
+            item: item.to_string(),
+            address: None,
+            space,
+        });
+    }
+
+    pub fn rent_if_missing(&mut self, item: &str, address: Pubkey, space: usize) {
+        self.new_accounts.push(AccountRent {
+            item: item.to_string(),
+            address: Some(address),
+            space,
+        });
+    }
+}
+
+fn is_wrapped_sol(token_account: Option<&Pubkey>, mint: &Pubkey) -> bool {
+    let native_mint = spl_token::native_mint::id();
+    *mint == native_mint && token_account.is_none_or(|account| *account == native_mint)
+}
+
+/// The size of a new token account of the mint, with the extensions the mint requires and the
+/// immutable owner of the associated token accounts of Token-2022.
+pub fn token_account_len(mint_account: &Account) -> Result<usize> {
+    if mint_account.owner != spl_token_2022::id() {
+        return Ok(spl_token::state::Account::LEN);
+    }
+    let mint_state = common_utils::unpack_mint(&mint_account.data)?;
+    let mut extension_types =
+        ExtensionType::get_required_init_account_extensions(&mint_state.get_extension_types()?);
+    extension_types.push(ExtensionType::ImmutableOwner);
+    Ok(ExtensionType::try_calculate_account_len::<
+        spl_token_2022::state::Account,
+    >(&extension_types)?)
+}
+
+/// Check the wallet can fund the transaction before sending it: every token account the
+/// instruction pulls from exists, is owned by the wallet, holds the mint under the token program
+/// of the mint and holds the maximum amount, every given account it pays into holds the mint,
+/// and the sol of the wallet covers the wrapped sol, the rent of the new accounts and the fees.
+/// Fails with a report of every failed item.
+pub fn check_user_funds(rpc_client: &RpcClient, owner: &Pubkey, funds: &FundsCheck) -> Result<()> {
+    let mut mints: Vec<Pubkey> = funds
+        .debits
+        .iter()
+        .map(|debit| debit.mint)
+        .chain(funds.credits.iter().map(|credit| credit.mint))
+        .collect();
+    mints.sort();
+    mints.dedup();
+    let mint_accounts: HashMap<Pubkey, Option<Account>> = mints
+        .iter()
+        .cloned()
+        .zip(rpc_client.get_multiple_accounts(&mints)?)
+        .collect();
+
+    let mut failures = Vec::new();
+    let mut required_lamports = Vec::new();
+    let mut token_accounts = Vec::new();
+    for (token_account, mint, amount) in funds
+        .debits
+        .iter()
+        .map(|debit| (debit.token_account, debit.mint, Some(debit.amount)))
+        .chain(
+            funds
+                .credits
+                .iter()
+                .map(|credit| (credit.token_account, credit.mint, None)),
+        )
+    {
+        let mint_account = match mint_accounts.get(&mint).cloned().flatten() {
+            Some(mint_account) => mint_account,
+            None => {
+                failures.push(format!("mint {} not found", mint));
+                continue;
+            }
+        };
+        let rent =
+            rpc_client.get_minimum_balance_for_rent_exemption(token_account_len(&mint_account)?)?;
+        if is_wrapped_sol(token_account.as_ref(), &mint) {
+            // the temporary wsol account refunds its rent when closed, but the wallet pays it first
+            required_lamports.push((
+                "wrapped sol and its temporary account".to_string(),
+                amount.unwrap_or_default() + rent,
+            ));
+            continue;
+        }
+        let address = token_account.unwrap_or_else(|| {
+            get_associated_token_address_with_program_id(owner, &mint, &mint_account.owner)
+        });
+        token_accounts.push((address, token_account, mint, mint_account, amount, rent));
+    }
+
+    let addresses: Vec<Pubkey> = token_accounts
+        .iter()
+        .map(|(address, ..)| *address)
+        .collect();
+    let accounts = rpc_client.get_multiple_accounts(&addresses)?;
+    for ((address, given_account, mint, mint_account, amount, rent), account) in
+        token_accounts.into_iter().zip(accounts)
+    {
+        let account = match (account, amount) {
+            (Some(account), _) => account,
+            // the command creates the associated token account it pays into
+            (None, None) if given_account.is_none() => {
+                required_lamports.push((format!("rent of token account {}", address), rent));
+                continue;
+            }
+            (None, _) => {
+                failures.push(format!(
+                    "token account {} of mint {} not found",
+                    address, mint
+                ));
+                continue;
+            }
+        };
+        if account.owner != mint_account.owner {
+            failures.push(format!(
+                "token account {} is owned by the program {}, but mint {} by {}",
+                address, account.owner, mint, mint_account.owner
+            ));
+            continue;
+        }
+        let token_state = match common_utils::unpack_token(&account.data) {
+            Ok(token_state) => token_state,
+            Err(_) => {
+                failures.push(format!("account {} is not a token account", address));
+                continue;
+            }
+        };
+        if token_state.base.mint != mint {
+            failures.push(format!(
+                "token account {} holds mint {}, expected {}",
+                address, token_state.base.mint, mint
+            ));
+            continue;
+        }
+        if token_state.base.state == AccountState::Frozen {
+            failures.push(format!("token account {} is frozen", address));
+        }
+        let amount = match amount {
+            Some(amount) => amount,
+            None => continue,
+        };
+        if token_state.base.owner != *owner {
+            failures.push(format!(
+                "token account {} is owned by {}, not the wallet {}",
+                address, token_state.base.owner, owner
+            ));
+        }
+        if token_state.base.amount < amount {
+            let decimals = common_utils::unpack_mint(&mint_account.data)?.base.decimals;
+            failures.push(format!(
+                "token account {} holds {} of mint {}, but the instruction may pull up to {}",
+                address,
+                raw_amount_to_ui(token_state.base.amount, decimals),
+                mint,
+                raw_amount_to_ui(amount, decimals)
+            ));
+        }
+    }
+
+    let addresses: Vec<Pubkey> = funds
+        .new_accounts
+        .iter()
+        .filter_map(|account| account.address)
+        .collect();
+    let mut existing = if addresses.is_empty() {
+        Vec::new()
+    } else {
+        rpc_client.get_multiple_accounts(&addresses)?
+    }
+    .into_iter();
+    for account in funds.new_accounts.iter() {
+        if account.address.is_some() && existing.next().flatten().is_some() {
+            continue;
+        }
+        required_lamports.push((
+            format!("rent of {}", account.item),
+            rpc_client.get_minimum_balance_for_rent_exemption(account.space)?,
+        ));
+    }
+    required_lamports.extend(funds.lamports.iter().cloned());
+    required_lamports.push((
+        "network and priority fees".to_string(),
+        FEE_ALLOWANCE_LAMPORTS,
+    ));
+    let required: u64 = required_lamports.iter().map(|(_, lamports)| lamports).sum();
+    let balance = rpc_client.get_balance(owner)?;
+    if balance < required {
+        let items: Vec<String> = required_lamports
+            .iter()
+            .map(|(item, lamports)| format!("{} {}", item, raw_amount_to_ui(*lamports, 9)))
+            .collect();
+        failures.push(format!(
+            "wallet {} holds {} sol, but requires {} sol: {}",
+            owner,
+            raw_amount_to_ui(balance, 9),
+            raw_amount_to_ui(required, 9),
+            items.join(", ")
+        ));
+    }
+
+    if failures.is_empty() {
+        return Ok(());
+    }
+    let report: Vec<String> = failures
+        .iter()
+        .map(|failure| format!("  - {}", failure))
+        .collect();
+    Err(format_err!(
+        "the wallet can not fund the transaction:\n{}",
+        report.join("\n")
+    ))
+}
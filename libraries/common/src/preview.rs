@@ -0,0 +1,178 @@
+use crate::{
+    amount::raw_amount_to_ui,
+    common_types::CommonConfig,
+    common_utils,
+    cost::{
+        estimate_cost, print_cost_estimate, simulate_account_changes, CostAccounts, CostEstimate,
+    },
+    token_symbol::{get_token_symbols, mint_label},
+};
+use anyhow::{format_err, Result};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{account::Account, pubkey::Pubkey, transaction::Transaction};
+use std::io::{self, Write};
+
+/// The balance change of a token account of the wallet, zero before it is created and after it
+/// is closed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WalletTokenChange {
+    pub token_account: Pubkey,
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub pre_amount: u64,
+    pub post_amount: u64,
+    pub created: bool,
+    pub closed: bool,
+}
+
+/// What the transaction does to the wallet, from its simulation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TxnPreview {
+    pub token_changes: Vec<WalletTokenChange>,
+    /// The change of the lamports of the wallet.
+    pub sol_change: i128,
+    /// The accounts closed by the transaction and the lamports they release.
+    pub closed_accounts: Vec<(Pubkey, u64)>,
+    pub cost: CostEstimate,
+}
+
+fn wallet_token(account: &Account, owner: &Pubkey) -> Option<(Pubkey, u64)> {
+    if account.owner != spl_token::id() && account.owner != spl_token_2022::id() {
+        return None;
+    }
+    let token_state = common_utils::unpack_token(&account.data).ok()?;
+    if token_state.base.owner != *owner {
+        return None;
+    }
+    Some((token_state.base.mint, token_state.base.amount))
+}
+
+/// Simulate the transaction and collect the balance changes of the token accounts of the wallet,
+/// the change of its sol, the accounts created and closed, and the fees.
+pub fn preview_txn(
+    rpc_client: &RpcClient,
+    txn: &Transaction,
+    owner: &Pubkey,
+    cost_accounts: &[CostAccounts],
+) -> Result<TxnPreview> {
+    let changes = simulate_account_changes(rpc_client, txn)?;
+    let cost = estimate_cost(rpc_client, txn, &changes, owner, cost_accounts)?;
+    let mut token_changes = Vec::new();
+    let mut closed_accounts = Vec::new();
+    let mut sol_change = 0i128;
+    for ((key, pre_account), post_account) in changes
+        .keys
+        .iter()
+        .zip(changes.pre_accounts.iter())
+        .zip(changes.post_accounts.iter())
+    {
+        if key == owner {
+            let pre_lamports = pre_account.as_ref().map_or(0, |account| account.lamports);
+            let post_lamports = post_account.as_ref().map_or(0, |account| account.lamports);
+            sol_change = post_lamports as i128 - pre_lamports as i128;
+            continue;
+        }
+        if let (Some(pre_account), None) = (pre_account, post_account) {
+            closed_accounts.push((*key, pre_account.lamports));
+        }
+        let pre_token = pre_account
+            .as_ref()
+            .and_then(|account| wallet_token(account, owner));
+        let post_token = post_account
+            .as_ref()
+            .and_then(|account| wallet_token(account, owner));
+        let mint = match (pre_token, post_token) {
+            (Some((mint, _)), _) | (None, Some((mint, _))) => mint,
+            (None, None) => continue,
+        };
+        token_changes.push(WalletTokenChange {
+            token_account: *key,
+            mint,
+            decimals: 0,
+            pre_amount: pre_token.map_or(0, |(_, amount)| amount),
+            post_amount: post_token.map_or(0, |(_, amount)| amount),
+            created: pre_account.is_none(),
+            closed: post_account.is_none(),
+        });
+    }
+    let mints: Vec<Pubkey> = token_changes.iter().map(|change| change.mint).collect();
+    for (change, mint_account) in token_changes
+        .iter_mut()
+        .zip(rpc_client.get_multiple_accounts(&mints)?)
+    {
+        if let Some(mint_account) = mint_account {
+            change.decimals = common_utils::unpack_mint(&mint_account.data)?.base.decimals;
+        }
+    }
+    Ok(TxnPreview {
+        token_changes,
+        sol_change,
+        closed_accounts,
+        cost,
+    })
+}
+
+fn signed_ui_amount(pre_amount: u64, post_amount: u64, decimals: u8) -> String {
+    if post_amount >= pre_amount {
+        format!("+{}", raw_amount_to_ui(post_amount - pre_amount, decimals))
+    } else {
+        format!("-{}", raw_amount_to_ui(pre_amount - post_amount, decimals))
+    }
+}
+
+pub fn print_txn_preview(
+    rpc_client: &RpcClient,
+    config: &CommonConfig,
+    preview: &TxnPreview,
+) -> Result<()> {
+    let mints: Vec<Pubkey> = preview
+        .token_changes
+        .iter()
+        .map(|change| change.mint)
+        .collect();
+    let symbols = get_token_symbols(rpc_client, config, &mints)?;
+    for change in preview.token_changes.iter() {
+        println!(
+            "token account:{}{}, mint:{}, balance:{} -> {} ({})",
+            change.token_account,
+            if change.created {
+                " (created)"
+            } else if change.closed {
+                " (closed)"
+            } else {
+                ""
+            },
+            mint_label(&symbols, &change.mint),
+            raw_amount_to_ui(change.pre_amount, change.decimals),
+            raw_amount_to_ui(change.post_amount, change.decimals),
+            signed_ui_amount(change.pre_amount, change.post_amount, change.decimals)
+        );
+    }
+    for (key, lamports) in preview.closed_accounts.iter() {
+        println!(
+            "closed account:{}, released:{} sol",
+            key,
+            raw_amount_to_ui(*lamports, 9)
+        );
+    }
+    print_cost_estimate(&preview.cost);
+    let sol_change = preview.sol_change.unsigned_abs() as u64;
+    println!(
+        "wallet sol change:{}{}",
+        if preview.sol_change < 0 { "-" } else { "+" },
+        raw_amount_to_ui(sol_change, 9)
+    );
+    Ok(())
+}
+
+/// Ask the user to confirm sending the transaction.
+pub fn confirm_txn() -> Result<()> {
+    print!("send the transaction? [y/N] ");
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    if !matches!(answer.trim(), "y" | "Y" | "yes") {
+        return Err(format_err!("the transaction is not confirmed"));
+    }
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use crate::{common_types, common_utils, system};
+use anyhow::Result;
+use clap::Parser;
+use solana_client::{nonce_utils, rpc_client::RpcClient};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    nonce::State as NonceState,
+    pubkey::Pubkey,
+    signer::{keypair::Keypair, Signer},
+};
+use std::sync::Arc;
+
+#[derive(Clone, Debug, Parser)]
+pub enum NonceCommands {
+    Create {
+        /// The keypair file of the new nonce account.
+        /// If none is given, a random keypair will be generated.
+        #[clap(long)]
+        nonce_keypair: Option<String>,
+        /// The authority of the nonce account.
+        /// If none is given, the wallet will be the authority.
+        #[clap(short, long)]
+        authority: Option<Pubkey>,
+    },
+    Inspect {
+        /// The nonce account to inspect.
+        #[clap(short, long)]
+        nonce_account: Pubkey,
+    },
+    Advance {
+        /// The nonce account to advance.
+        /// The nonce authority is specified by `config.nonce_authority`, default is the wallet.
+        #[clap(short, long)]
+        nonce_account: Pubkey,
+    },
+}
+
+pub fn process_nonce_commands(
+    command: NonceCommands,
+    config: &common_types::CommonConfig,
+    signing_keypairs: &mut Vec<Arc<dyn Signer>>,
+) -> Result<Option<Vec<Instruction>>> {
+    let rpc_client = RpcClient::new(config.cluster().url());
+    let payer_pubkey = config.wallet_pubkey()?;
+    // the wallet signs offline in the sign-only mode
+    if !config.sign_only() {
+        let payer: Arc<dyn Signer> = Arc::new(common_utils::read_keypair_file(&config.wallet())?);
+        if !signing_keypairs.contains(&payer) {
+            signing_keypairs.push(payer);
+        }
+    }
+
+    match command {
+        NonceCommands::Create {
+            nonce_keypair,
+            authority,
+        } => {
+            let nonce_keypair = if let Some(nonce_keypair) = nonce_keypair {
+                common_utils::read_keypair_file(&nonce_keypair)?
+            } else {
+                Keypair::new()
+            };
+            let nonce_account = nonce_keypair.pubkey();
+            let signer: Arc<dyn Signer> = Arc::new(nonce_keypair);
+            if !signing_keypairs.contains(&signer) {
+                signing_keypairs.push(signer);
+            }
+            let authority = authority.unwrap_or(payer_pubkey);
+            let lamports = rpc_client.get_minimum_balance_for_rent_exemption(NonceState::size())?;
+            println!(
+                "nonce_account:{}, authority:{}, lamports:{}",
+                nonce_account, authority, lamports
+            );
+            let create_nonce_instr =
+                system::create_nonce_account(&payer_pubkey, &nonce_account, &authority, lamports);
+            return Ok(Some(create_nonce_instr));
+        }
+        NonceCommands::Inspect { nonce_account } => {
+            let account = nonce_utils::get_account_with_commitment(
+                &rpc_client,
+                &nonce_account,
+                CommitmentConfig::confirmed(),
+            )?;
+            let nonce_data = nonce_utils::data_from_account(&account)?;
+            println!(
+                "nonce_account:{}, authority:{}, nonce:{}, lamports_per_signature:{}, balance:{}",
+                nonce_account,
+                nonce_data.authority,
+                nonce_data.blockhash(),
+                nonce_data.get_lamports_per_signature(),
+                account.lamports
+            );
+            return Ok(None);
+        }
+        NonceCommands::Advance { nonce_account } => {
+            let authority = if let Some(nonce_authority) = config.nonce_authority() {
+                let authority_keypair = common_utils::read_keypair_file(&nonce_authority)?;
+                let authority = authority_keypair.pubkey();
+                let signer: Arc<dyn Signer> = Arc::new(authority_keypair);
+                if !signing_keypairs.contains(&signer) {
+                    signing_keypairs.push(signer);
+                }
+                authority
+            } else {
+                payer_pubkey
+            };
+            let advance_nonce_instr = system::advance_nonce_account(&nonce_account, &authority);
+            return Ok(Some(advance_nonce_instr));
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{
+    confirmed_txn::TokenBalanceChange,
+    receipt::{quoted_other_amount, realized_slippage_bps, ReceiptEvent, SwapQuote},
+};
+use anyhow::{format_err, Result};
+use solana_sdk::pubkey::Pubkey;
+
+/// The swap of the simulated transaction from its swap event, or else from the balance changes
+/// of the token accounts of the owner: the largest decrease is the input and the largest increase
+/// the output. The temporary wsol accounts are closed in the transaction and leave no balance
+/// change, then the swap is not found.
+pub fn simulated_swap(
+    events: &[ReceiptEvent],
+    token_balance_changes: &[TokenBalanceChange],
+    owner: &Pubkey,
+) -> Option<ReceiptEvent> {
+    if let Some(event) = events
+        .iter()
+        .find(|event| matches!(event, ReceiptEvent::Swap { .. }))
+    {
+        return Some(event.clone());
+    }
+    let owner = owner.to_string();
+    let changes: Vec<i128> = token_balance_changes
+        .iter()
+        .filter(|change| change.owner.as_ref() == Some(&owner))
+        .map(|change| change.post_amount as i128 - change.pre_amount as i128)
+        .collect();
+    let input_amount = changes.iter().filter(|change| **change < 0).min()?;
+    let output_amount = changes.iter().filter(|change| **change > 0).max()?;
+    Some(ReceiptEvent::Swap {
+        pool_id: None,
+        input_amount: input_amount.unsigned_abs() as u64,
+        output_amount: *output_amount as u64,
+    })
+}
+
+/// Compare the simulated swap to the quote, the output of the swaps with the base input and the
+/// input of the swaps with the base output. Returns the deviation in bps, a negative value means
+/// the swap is executed at a better price than quoted, and fails if it is off the quote by more
+/// than the tolerance in either direction.
+pub fn check_quote(
+    quote: &SwapQuote,
+    slippage_bps: u64,
+    swap: &ReceiptEvent,
+    tolerance_bps: u64,
+) -> Result<i64> {
+    let quoted = quoted_other_amount(quote, slippage_bps).ok_or_else(|| {
+        format_err!(
+            "the quote can not be recovered with the slippage of {} bps",
+            slippage_bps
+        )
+    })?;
+    let deviation_bps = realized_slippage_bps(quote, slippage_bps, swap)
+        .ok_or_else(|| format_err!("the simulated swap can not be compared to the quote"))?;
+    if deviation_bps.unsigned_abs() > tolerance_bps {
+        let (side, simulated) = match swap {
+            ReceiptEvent::Swap { output_amount, .. } if quote.base_in => ("output", *output_amount),
+            ReceiptEvent::Swap { input_amount, .. } => ("input", *input_amount),
+            _ => unreachable!(),
+        };
+        return Err(format_err!(
+            "the simulated {} {} is off the quoted {} by {} bps, more than the tolerance {} bps",
+            side,
+            simulated,
+            quoted,
+            deviation_bps,
+            tolerance_bps
+        ));
+    }
+    Ok(deviation_bps)
+}
@@ -0,0 +1,135 @@
+use crate::{
+    common_types::TEN_THOUSAND,
+    confirmed_txn::{self, TokenBalanceChange},
+};
+use anyhow::Result;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{pubkey::Pubkey, signature::Signature, transaction::TransactionError};
+
+/// The amounts decoded from the swap or liquidity events emitted by the programs.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReceiptEvent {
+    Swap {
+        pool_id: Option<Pubkey>,
+        input_amount: u64,
+        output_amount: u64,
+    },
+    Deposit {
+        pool_id: Option<Pubkey>,
+        amount_0: u64,
+        amount_1: u64,
+    },
+    Withdraw {
+        pool_id: Option<Pubkey>,
+        amount_0: u64,
+        amount_1: u64,
+    },
+}
+
+/// The swap amounts of the sent instruction.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SwapQuote {
+    pub base_in: bool,
+    pub amount_specified: u64,
+    pub other_amount_threshold: u64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TxnReceipt {
+    pub signature: Signature,
+    pub slot: u64,
+    pub err: Option<TransactionError>,
+    pub fee: u64,
+    pub compute_units_consumed: Option<u64>,
+    pub token_balance_changes: Vec<TokenBalanceChange>,
+    pub log_messages: Vec<String>,
+}
+
+pub fn get_txn_receipt(client: &RpcClient, signature: &Signature) -> Result<TxnReceipt> {
+    let confirmed_txn = confirmed_txn::get_confirmed_txn(client, signature)?;
+    let (versioned_txn, meta) = confirmed_txn::decode_confirmed_txn(&confirmed_txn)?;
+    let account_keys = confirmed_txn::get_account_keys(&versioned_txn, &meta)?;
+    let token_balance_changes = confirmed_txn::get_token_balance_changes(&meta, &account_keys)?;
+
+    Ok(TxnReceipt {
+        signature: *signature,
+        slot: confirmed_txn.slot,
+        err: meta.err,
+        fee: meta.fee,
+        compute_units_consumed: Option::from(meta.compute_units_consumed),
+        token_balance_changes,
+        log_messages: Option::from(meta.log_messages).unwrap_or_default(),
+    })
+}
+
+/// Recover the quoted amount of the other side from the threshold with slippage applied.
+/// Returns `None` for the base input with a slippage of 10000 bps or more, the minimum output
+/// no longer depends on the quote.
+pub fn quoted_other_amount(quote: &SwapQuote, slippage_bps: u64) -> Option<u64> {
+    let threshold = quote.other_amount_threshold as u128;
+    let slippage_bps = slippage_bps as u128;
+    let quoted = if quote.base_in {
+        // minimum_amount_out = quoted * (10000 - slippage) / 10000
+        let denominator = TEN_THOUSAND.checked_sub(slippage_bps).filter(|d| *d > 0)?;
+        threshold * TEN_THOUSAND / denominator
+    } else {
+        // maximum_amount_in = quoted * (10000 + slippage) / 10000
+        threshold * TEN_THOUSAND / (TEN_THOUSAND + slippage_bps)
+    };
+    u64::try_from(quoted).ok()
+}
+
+/// The realized slippage in bps of the swap event versus the quote,
+/// a negative value means the swap is executed at a better price than quoted.
+pub fn realized_slippage_bps(
+    quote: &SwapQuote,
+    slippage_bps: u64,
+    event: &ReceiptEvent,
+) -> Option<i64> {
+    if let ReceiptEvent::Swap {
+        input_amount,
+        output_amount,
+        ..
+    } = event
+    {
+        let quoted = quoted_other_amount(quote, slippage_bps)? as i128;
+        if quoted == 0 {
+            return None;
+        }
+        let slippage = if quote.base_in {
+            (quoted - *output_amount as i128) * TEN_THOUSAND as i128 / quoted
+        } else {
+            (*input_amount as i128 - quoted) * TEN_THOUSAND as i128 / quoted
+        };
+        Some(slippage as i64)
+    } else {
+        None
+    }
+}
+
+pub fn print_receipt(
+    receipt: &TxnReceipt,
+    events: &[ReceiptEvent],
+    quote: Option<&SwapQuote>,
+    slippage_bps: u64,
+) {
+    println!(
+        "signature:{}, slot:{}, fee:{}, compute_units_consumed:{:?}, err:{:?}",
+        receipt.signature, receipt.slot, receipt.fee, receipt.compute_units_consumed, receipt.err
+    );
+    confirmed_txn::print_token_balance_changes(&receipt.token_balance_changes);
+    for event in events {
+        println!("{:?}", event);
+        if let Some(quote) = quote {
+            if let (Some(quoted), Some(slippage)) = (
+                quoted_other_amount(quote, slippage_bps),
+                realized_slippage_bps(quote, slippage_bps, event),
+            ) {
+                println!(
+                    "quoted_other_amount:{}, other_amount_threshold:{}, realized_slippage_bps:{}",
+                    quoted, quote.other_amount_threshold, slippage
+                );
+            }
+        }
+    }
+}
@@ -1,20 +1,26 @@
+use crate::offline;
 use anchor_lang::AccountDeserialize;
 use anyhow::Result;
 use base64::{prelude::BASE64_STANDARD, Engine};
-use solana_account_decoder::UiAccountEncoding;
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
 use solana_client::{
+    nonce_utils,
     rpc_client::RpcClient,
     rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSendTransactionConfig},
     rpc_filter::RpcFilterType,
     rpc_request::RpcRequest,
-    rpc_response::{RpcResult, RpcSimulateTransactionResult},
+    rpc_response::{Response, RpcKeyedAccount, RpcResult, RpcSimulateTransactionResult},
 };
 use solana_sdk::{
     account::Account, commitment_config::CommitmentConfig, instruction::Instruction,
-    message::Message, pubkey::Pubkey, signature::Signature, signer::signers::Signers,
-    transaction::Transaction,
+    message::Message, nonce::state::Data as NonceData, pubkey::Pubkey, signature::Signature,
+    signer::signers::Signers, transaction::Transaction,
 };
 use solana_transaction_status::UiTransactionEncoding;
+use std::{thread, time::Duration};
+
+/// The interval to poll the signature status and rebroadcast the transaction.
+pub const REBROADCAST_INTERVAL: Duration = Duration::from_secs(2);
 
 pub fn build_txn(
     client: &RpcClient,
@@ -32,6 +38,37 @@ pub fn build_txn(
     Ok(transaction)
 }
 
+pub fn build_nonce_txn(
+    client: &RpcClient,
+    instructions: &[Instruction],
+    fee_payer: &Pubkey,
+    nonce_account: &Pubkey,
+    nonce_authority: &Pubkey,
+    signing_keypairs: &dyn Signers,
+) -> Result<Transaction> {
+    let nonce_data = get_nonce_data(client, nonce_account)?;
+    // advance_nonce_account is prepended as the first instruction
+    let message = Message::new_with_nonce(
+        instructions.to_vec(),
+        Some(fee_payer),
+        nonce_account,
+        nonce_authority,
+    );
+    let mut transaction = Transaction::new_unsigned(message);
+
+    transaction.try_partial_sign(signing_keypairs, nonce_data.blockhash())?;
+    Ok(transaction)
+}
+
+pub fn get_nonce_data(client: &RpcClient, nonce_account: &Pubkey) -> Result<NonceData> {
+    let account = nonce_utils::get_account_with_commitment(
+        client,
+        nonce_account,
+        CommitmentConfig::confirmed(),
+    )?;
+    Ok(nonce_utils::data_from_account(&account)?)
+}
+
 pub fn send_txn(client: &RpcClient, txn: &Transaction, skip_preflight: bool) -> Result<Signature> {
     Ok(client.send_and_confirm_transaction_with_spinner_and_config(
         txn,
@@ -43,6 +80,62 @@ pub fn send_txn(client: &RpcClient, txn: &Transaction, skip_preflight: bool) ->
     )?)
 }
 
+/// Send the transaction and rebroadcast it until it is confirmed or its blockhash expires.
+/// Returns `None` if the blockhash expired before the transaction landed, the transaction
+/// must be rebuilt and re-signed with a new blockhash.
+pub fn send_txn_with_rebroadcast(
+    client: &RpcClient,
+    txn: &Transaction,
+    skip_preflight: bool,
+) -> Result<Option<Signature>> {
+    let signature = client.send_transaction_with_config(
+        txn,
+        RpcSendTransactionConfig {
+            skip_preflight,
+            ..RpcSendTransactionConfig::default()
+        },
+    )?;
+    println!("signature: {}", signature);
+    let blockhash = txn.message.recent_blockhash;
+    let nonce_account = offline::get_nonce_account(txn);
+    loop {
+        thread::sleep(REBROADCAST_INTERVAL);
+        if let Some(status) = client
+            .get_signature_status_with_commitment(&signature, CommitmentConfig::confirmed())?
+        {
+            status?;
+            return Ok(Some(signature));
+        }
+        let expired = if let Some(nonce_account) = nonce_account {
+            // the durable nonce only expires when it is advanced
+            get_nonce_data(client, &nonce_account)?.blockhash() != blockhash
+        } else {
+            !client.is_blockhash_valid(&blockhash, CommitmentConfig::processed())?
+        };
+        if expired {
+            // the transaction may land right before the blockhash expired
+            if let Some(status) = client.get_signature_status_with_commitment_and_history(
+                &signature,
+                CommitmentConfig::confirmed(),
+                true,
+            )? {
+                status?;
+                return Ok(Some(signature));
+            }
+            println!("blockhash expired: {}", blockhash);
+            return Ok(None);
+        }
+        // rebroadcast, the error is ignored as the transaction may be already processed
+        let _ = client.send_transaction_with_config(
+            txn,
+            RpcSendTransactionConfig {
+                skip_preflight: true,
+                ..RpcSendTransactionConfig::default()
+            },
+        );
+    }
+}
+
 pub fn simulate_transaction(
     client: &RpcClient,
     transaction: &Transaction,
@@ -128,3 +221,51 @@ pub fn get_program_accounts_with_filters(
         .unwrap();
     Ok(accounts)
 }
+
+/// The addresses of the program accounts without their data, which is sliced to zero bytes.
+pub fn get_program_account_keys(
+    client: &RpcClient,
+    program: Pubkey,
+    filters: Option<Vec<RpcFilterType>>,
+) -> Result<Vec<Pubkey>> {
+    let accounts = client.get_program_accounts_with_config(
+        &program,
+        RpcProgramAccountsConfig {
+            filters,
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: Some(UiDataSliceConfig {
+                    offset: 0,
+                    length: 0,
+                }),
+                ..RpcAccountInfoConfig::default()
+            },
+            with_context: Some(false),
+        },
+    )?;
+    Ok(accounts.into_iter().map(|(key, _)| key).collect())
+}
+
+/// The token accounts of the owner under the token program with their raw data, unlike
+/// `RpcClient::get_token_accounts_by_owner` which returns them json parsed.
+pub fn get_token_accounts_by_owner(
+    client: &RpcClient,
+    owner: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<Vec<(Pubkey, Account)>> {
+    let response: Response<Vec<RpcKeyedAccount>> = client.send(
+        RpcRequest::GetTokenAccountsByOwner,
+        serde_json::json!([owner.to_string(), { "programId": token_program.to_string() }, {
+            "encoding": UiAccountEncoding::Base64, "commitment": CommitmentConfig::confirmed().commitment
+        }]),
+    )?;
+    Ok(response
+        .value
+        .into_iter()
+        .filter_map(|keyed_account| {
+            let key = keyed_account.pubkey.parse::<Pubkey>().ok()?;
+            let account = keyed_account.account.decode::<Account>()?;
+            Some((key, account))
+        })
+        .collect())
+}
@@ -0,0 +1,279 @@
+use crate::{common_utils, confirmed_txn::TokenBalanceChange, cost::AccountChanges};
+use anyhow::Result;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig},
+};
+use solana_sdk::{
+    account::Account,
+    commitment_config::CommitmentConfig,
+    instruction::InstructionError,
+    message::Message,
+    pubkey::Pubkey,
+    transaction::{Transaction, TransactionError},
+};
+
+/// The result of the simulation of a transaction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TxnSimulation {
+    pub err: Option<TransactionError>,
+    pub logs: Vec<String>,
+    /// The writable accounts before and after the transaction, and the compute units consumed.
+    pub changes: AccountChanges,
+}
+
+/// The custom error a program of the transaction failed with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InstructionFailure {
+    pub instruction_index: u8,
+    pub program_id: Pubkey,
+    pub code: u32,
+    /// The name of the error code in the program, None if the program is unknown.
+    pub name: Option<String>,
+}
+
+/// Simulate the transaction with the writable accounts returned after it, the simulation
+/// failing on chain is not an error.
+pub fn simulate_txn(rpc_client: &RpcClient, txn: &Transaction) -> Result<TxnSimulation> {
+    let message = &txn.message;
+    let writable_keys: Vec<Pubkey> = message
+        .account_keys
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| message.is_writable(*i))
+        .map(|(_, key)| *key)
+        .collect();
+    // the simulation runs on a bank not older than the one of the pre accounts
+    let response = rpc_client
+        .get_multiple_accounts_with_commitment(&writable_keys, CommitmentConfig::confirmed())?;
+    let pre_accounts = response.value;
+    let result = rpc_client
+        .simulate_transaction_with_config(
+            txn,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                commitment: Some(CommitmentConfig::confirmed()),
+                accounts: Some(RpcSimulateTransactionAccountsConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    addresses: writable_keys.iter().map(|key| key.to_string()).collect(),
+                }),
+                min_context_slot: Some(response.context.slot),
+                ..RpcSimulateTransactionConfig::default()
+            },
+        )?
+        .value;
+    let post_accounts = result
+        .accounts
+        .unwrap_or_default()
+        .into_iter()
+        .map(|account| {
+            account
+                .and_then(|account| account.decode::<Account>())
+                .filter(|account| account.lamports > 0)
+        })
+        .collect();
+    Ok(TxnSimulation {
+        err: result.err,
+        logs: result.logs.unwrap_or_default(),
+        changes: AccountChanges {
+            keys: writable_keys,
+            pre_accounts,
+            post_accounts,
+            units_consumed: result.units_consumed,
+        },
+    })
+}
+
+/// The name of the anchor error from the log of the anchor program failed with the code,
+/// e.g. `AnchorError occurred. Error Code: ExceededSlippage. Error Number: 6005. ...`.
+pub fn anchor_error_name(logs: &[String], code: u32) -> Option<String> {
+    let error_number = format!("Error Number: {}.", code);
+    logs.iter()
+        .filter(|log| log.contains(&error_number))
+        .find_map(|log| {
+            let name = log.split("Error Code: ").nth(1)?;
+            name.split('.').next().map(|name| name.to_string())
+        })
+}
+
+/// The name of the anchor framework error of the code below the `ERROR_CODE_OFFSET` of the
+/// anchor programs, e.g. `ConstraintSeeds` of 2006. The variants are compared by the code anchor
+/// derives for each of them.
+pub fn anchor_lang_error_name(code: u32) -> Option<String> {
+    use anchor_lang::error::ErrorCode::*;
+    [
+        InstructionMissing,
+        InstructionFallbackNotFound,
+        InstructionDidNotDeserialize,
+        InstructionDidNotSerialize,
+        IdlInstructionStub,
+        IdlInstructionInvalidProgram,
+        IdlAccountNotEmpty,
+        EventInstructionStub,
+        ConstraintMut,
+        ConstraintHasOne,
+        ConstraintSigner,
+        ConstraintRaw,
+        ConstraintOwner,
+        ConstraintRentExempt,
+        ConstraintSeeds,
+        ConstraintExecutable,
+        ConstraintState,
+        ConstraintAssociated,
+        ConstraintAssociatedInit,
+        ConstraintClose,
+        ConstraintAddress,
+        ConstraintZero,
+        ConstraintTokenMint,
+        ConstraintTokenOwner,
+        ConstraintMintMintAuthority,
+        ConstraintMintFreezeAuthority,
+        ConstraintMintDecimals,
+        ConstraintSpace,
+        ConstraintAccountIsNone,
+        ConstraintTokenTokenProgram,
+        ConstraintMintTokenProgram,
+        ConstraintAssociatedTokenTokenProgram,
+        RequireViolated,
+        RequireEqViolated,
+        RequireKeysEqViolated,
+        RequireNeqViolated,
+        RequireKeysNeqViolated,
+        RequireGtViolated,
+        RequireGteViolated,
+        AccountDiscriminatorAlreadySet,
+        AccountDiscriminatorNotFound,
+        AccountDiscriminatorMismatch,
+        AccountDidNotDeserialize,
+        AccountDidNotSerialize,
+        AccountNotEnoughKeys,
+        AccountNotMutable,
+        AccountOwnedByWrongProgram,
+        InvalidProgramId,
+        InvalidProgramExecutable,
+        AccountNotSigner,
+        AccountNotSystemOwned,
+        AccountNotInitialized,
+        AccountNotProgramData,
+        AccountNotAssociatedTokenAccount,
+        AccountSysvarMismatch,
+        AccountReallocExceedsLimit,
+        AccountDuplicateReallocs,
+        DeclaredProgramIdMismatch,
+        TryingToInitPayerAsProgramAccount,
+        Deprecated,
+    ]
+    .into_iter()
+    .find(|error| u32::from(*error) == code)
+    .map(|error| error.name())
+}
+
+/// The custom program error of the failed transaction, `error_name` maps the program and the
+/// code to the name of the error code.
+pub fn get_instruction_failure(
+    message: &Message,
+    err: &TransactionError,
+    error_name: impl Fn(&Pubkey, u32) -> Option<String>,
+) -> Option<InstructionFailure> {
+    match err {
+        TransactionError::InstructionError(instruction_index, InstructionError::Custom(code)) => {
+            let instruction = message.instructions.get(*instruction_index as usize)?;
+            let program_id = message.account_keys[instruction.program_id_index as usize];
+            Some(InstructionFailure {
+                instruction_index: *instruction_index,
+                program_id,
+                code: *code,
+                name: error_name(&program_id, *code),
+            })
+        }
+        _ => None,
+    }
+}
+
+fn token_balance(account: &Option<Account>) -> Option<(Pubkey, Pubkey, u64)> {
+    let account = account.as_ref()?;
+    if account.owner != spl_token::id() && account.owner != spl_token_2022::id() {
+        return None;
+    }
+    let token_state = common_utils::unpack_token(&account.data).ok()?;
+    Some((
+        token_state.base.mint,
+        token_state.base.owner,
+        token_state.base.amount,
+    ))
+}
+
+/// The balance changes of the writable token accounts of the simulated transaction.
+pub fn get_simulated_token_balance_changes(changes: &AccountChanges) -> Vec<TokenBalanceChange> {
+    let mut token_balance_changes = Vec::new();
+    for ((key, pre_account), post_account) in changes
+        .keys
+        .iter()
+        .zip(changes.pre_accounts.iter())
+        .zip(changes.post_accounts.iter())
+    {
+        let pre_balance = token_balance(pre_account);
+        let post_balance = token_balance(post_account);
+        // accounts created in the transaction have no pre balance, closed accounts have no post balance
+        let (mint, owner) = match (pre_balance, post_balance) {
+            (Some((mint, owner, _)), _) | (None, Some((mint, owner, _))) => (mint, owner),
+            (None, None) => continue,
+        };
+        token_balance_changes.push(TokenBalanceChange {
+            account: *key,
+            mint: mint.to_string(),
+            owner: Some(owner.to_string()),
+            pre_amount: pre_balance.map_or(0, |(_, _, amount)| amount),
+            post_amount: post_balance.map_or(0, |(_, _, amount)| amount),
+        });
+    }
+    token_balance_changes
+}
+
+pub fn print_simulation_result(simulation: &TxnSimulation, failure: Option<&InstructionFailure>) {
+    println!(
+        "simulation: units_consumed:{:?}, err:{:?}",
+        simulation.changes.units_consumed, simulation.err
+    );
+    if let Some(failure) = failure {
+        println!(
+            "instruction {} failed: program:{}, error:{} ({:#x}){}",
+            failure.instruction_index,
+            failure.program_id,
+            failure.code,
+            failure.code,
+            failure
+                .name
+                .as_ref()
+                .map_or(String::new(), |name| format!(" {}", name))
+        );
+    }
+    if simulation.err.is_some() {
+        for log in simulation.logs.iter() {
+            println!("    {}", log);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anchor_lang_error_name() {
+        assert_eq!(
+            anchor_lang_error_name(100).as_deref(),
+            Some("InstructionMissing")
+        );
+        assert_eq!(
+            anchor_lang_error_name(2006).as_deref(),
+            Some("ConstraintSeeds")
+        );
+        assert_eq!(
+            anchor_lang_error_name(3012).as_deref(),
+            Some("AccountNotInitialized")
+        );
+        assert_eq!(anchor_lang_error_name(6000), None);
+    }
+}
@@ -1,4 +1,4 @@
-use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, system_instruction};
 
 pub fn create_rent_exempt(
     from: &Pubkey,
@@ -11,3 +11,16 @@ pub fn create_rent_exempt(
         from, to, lamports, space, owner,
     )]
 }
+
+pub fn create_nonce_account(
+    from: &Pubkey,
+    nonce: &Pubkey,
+    authority: &Pubkey,
+    lamports: u64,
+) -> Vec<Instruction> {
+    system_instruction::create_nonce_account(from, nonce, authority, lamports)
+}
+
+pub fn advance_nonce_account(nonce: &Pubkey, authority: &Pubkey) -> Vec<Instruction> {
+    vec![system_instruction::advance_nonce_account(nonce, authority)]
+}
@@ -1,6 +1,13 @@
 use solana_sdk::{
-    instruction::Instruction, program_pack::Pack, pubkey::Pubkey, system_instruction,
+    instruction::Instruction, program_pack::Pack, pubkey::Pubkey, rent::Rent, system_instruction,
 };
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Tells apart the temporary wsol accounts created at the same time.
+static TEMP_WSOL_COUNT: AtomicU64 = AtomicU64::new(0);
 
 pub fn create_ata_token_or_not(
     funding: &Pubkey,
@@ -121,3 +128,48 @@ pub fn wrap_sol_instructions(from: &Pubkey, to: &Pubkey, amount: u64) -> Vec<Ins
         spl_token::instruction::sync_native(&spl_token::id(), to).unwrap(),
     ]
 }
+
+/// Wrap `amount` lamports into a temporary wsol account of the owner.
+/// The account is derived from the owner with a seed unique to the transaction, so no other
+/// signer is required. The instructions creating it are appended to `instructions`, and the
+/// one closing it to `close_instructions`, which must end the transaction to unwrap the sol.
+pub fn wrap_sol_temp_account(
+    owner: &Pubkey,
+    amount: u64,
+    instructions: &mut Vec<Instruction>,
+    close_instructions: &mut Vec<Instruction>,
+) -> Pubkey {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let seed = format!(
+        "wsol{:x}{:x}",
+        nanos,
+        TEMP_WSOL_COUNT.fetch_add(1, Ordering::Relaxed)
+    );
+    let wsol_account = Pubkey::create_with_seed(owner, &seed, &spl_token::id()).unwrap();
+    instructions.push(system_instruction::create_account_with_seed(
+        owner,
+        &wsol_account,
+        owner,
+        &seed,
+        Rent::default().minimum_balance(spl_token::state::Account::LEN),
+        spl_token::state::Account::LEN as u64,
+        &spl_token::id(),
+    ));
+    instructions.push(
+        spl_token::instruction::initialize_account3(
+            &spl_token::id(),
+            &wsol_account,
+            &spl_token::native_mint::id(),
+            owner,
+        )
+        .unwrap(),
+    );
+    if amount > 0 {
+        instructions.extend(wrap_sol_instructions(owner, &wsol_account, amount));
+    }
+    close_instructions.extend(close_spl_account(&wsol_account, owner, owner, None));
+    wsol_account
+}
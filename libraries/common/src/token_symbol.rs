@@ -0,0 +1,233 @@
+use crate::{
+    common_types::{CommonConfig, ExtensionStruct},
+    common_utils,
+    indexer::PoolMints,
+};
+use anchor_spl::metadata::mpl_token_metadata::accounts::Metadata;
+use anyhow::{format_err, Result};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use std::{collections::HashMap, fs, str::FromStr};
+
+/// The max accounts of a `get_multiple_accounts` request.
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// The symbols of the well-known mints, the token list of the config overrides them.
+#[cfg(not(feature = "devnet"))]
+const WELL_KNOWN_TOKENS: &[(&str, &str)] = &[
+    ("SOL", "So11111111111111111111111111111111111111112"),
+    ("USDC", "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"),
+    ("USDT", "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB"),
+    ("RAY", "4k3Dyjzvzp8eMZWUXbBCjEvwSkkk59S5iCNLY3QrkX6R"),
+    ("MSOL", "mSoLzYCxHdYgdzU16g5QSh3i5K3z3KZK7ytfqcJm7So"),
+    ("JITOSOL", "J1toso1uCk3RLmjorhTtrVwY9HJ7X8V9yYac6Y7kGCPn"),
+    ("BONK", "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263"),
+];
+#[cfg(feature = "devnet")]
+const WELL_KNOWN_TOKENS: &[(&str, &str)] =
+    &[("SOL", "So11111111111111111111111111111111111111112")];
+
+/// The name and symbol of a mint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenSymbol {
+    pub mint: Pubkey,
+    pub symbol: String,
+    pub name: String,
+}
+
+/// The upper case symbols and their mints, the well-known ones first and then the token list.
+pub fn load_token_list(config: &CommonConfig) -> Result<Vec<(String, Pubkey)>> {
+    let mut tokens: Vec<(String, Pubkey)> = WELL_KNOWN_TOKENS
+        .iter()
+        .map(|(symbol, mint)| (symbol.to_string(), Pubkey::from_str(mint).unwrap()))
+        .collect();
+    if let Some(path) = config.token_list() {
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format_err!("read token list {} failed: {}", path, e))?;
+        let value: toml::Value = toml::from_str(&content)?;
+        let table = value
+            .as_table()
+            .ok_or(format_err!("token list {} is not a table", path))?;
+        for (symbol, mint) in table {
+            let mint = mint
+                .as_str()
+                .ok_or(format_err!("mint of symbol {} is not a string", symbol))?;
+            let symbol = symbol.to_uppercase();
+            let mint = Pubkey::from_str(mint)?;
+            tokens.retain(|(known, _)| *known != symbol);
+            tokens.push((symbol, mint));
+        }
+    }
+    Ok(tokens)
+}
+
+/// A mint or token account given by its address, or a mint given by its symbol, e.g. `USDC`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MintInput {
+    Address(Pubkey),
+    Symbol(String),
+}
+
+impl FromStr for MintInput {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Ok(address) = Pubkey::from_str(s) {
+            return Ok(MintInput::Address(address));
+        }
+        if s.is_empty()
+            || !s
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+        {
+            return Err(format_err!("invalid address or token symbol {}", s));
+        }
+        Ok(MintInput::Symbol(s.to_string()))
+    }
+}
+
+impl MintInput {
+    /// The address, or the mint of the symbol in the token list.
+    pub fn resolve(&self, config: &CommonConfig) -> Result<Pubkey> {
+        match self {
+            MintInput::Address(address) => Ok(*address),
+            MintInput::Symbol(symbol) => {
+                let symbol = symbol.to_uppercase();
+                load_token_list(config)?
+                    .into_iter()
+                    .find(|(known, _)| *known == symbol)
+                    .map(|(_, mint)| mint)
+                    .ok_or(format_err!(
+                        "unknown token symbol {}, add it to the token list of --config.token_list",
+                        symbol
+                    ))
+            }
+        }
+    }
+}
+
+/// The token account paying the swap: the address as is, or for a symbol the native mint standing
+/// for the sol of the wallet or the associated token account of the mint.
+pub fn resolve_user_input_token(
+    rpc_client: &RpcClient,
+    config: &CommonConfig,
+    token: &MintInput,
+    owner: &Pubkey,
+) -> Result<Pubkey> {
+    if let MintInput::Address(address) = token {
+        return Ok(*address);
+    }
+    let mint = token.resolve(config)?;
+    if mint == spl_token::native_mint::id() {
+        return Ok(mint);
+    }
+    let token_program = rpc_client.get_account(&mint)?.owner;
+    Ok(get_associated_token_address_with_program_id(
+        owner,
+        &mint,
+        &token_program,
+    ))
+}
+
+/// The token account receiving the swap, none for a symbol to use the default account of the output mint.
+pub fn resolve_user_output_token(
+    config: &CommonConfig,
+    token: Option<&MintInput>,
+    pool_mints: &PoolMints,
+) -> Result<Option<Pubkey>> {
+    match token {
+        None => Ok(None),
+        Some(MintInput::Address(address)) => Ok(Some(*address)),
+        Some(token) => {
+            let mint = token.resolve(config)?;
+            if mint != pool_mints.mint_0 && mint != pool_mints.mint_1 {
+                return Err(format_err!("mint {} is not a mint of the pool", mint));
+            }
+            Ok(None)
+        }
+    }
+}
+
+fn trim_metadata_string(value: &str) -> String {
+    value.trim_matches(char::from(0)).trim().to_string()
+}
+
+/// The name and symbol of the mints from the token list, the token metadata extension of
+/// Token-2022 or the metaplex metadata account. The mints without any are omitted.
+pub fn get_token_symbols(
+    rpc_client: &RpcClient,
+    config: &CommonConfig,
+    mints: &[Pubkey],
+) -> Result<HashMap<Pubkey, TokenSymbol>> {
+    let mut symbols = HashMap::new();
+    // the token list overrides the well-known symbols, so it is searched from the end
+    let tokens = load_token_list(config)?;
+    for mint in mints {
+        if let Some((symbol, _)) = tokens.iter().rev().find(|(_, known)| known == mint) {
+            symbols.insert(
+                *mint,
+                TokenSymbol {
+                    mint: *mint,
+                    symbol: symbol.clone(),
+                    name: symbol.clone(),
+                },
+            );
+        }
+    }
+    let mut missing: Vec<Pubkey> = Vec::new();
+    for mint in mints {
+        if !symbols.contains_key(mint) && !missing.contains(mint) {
+            missing.push(*mint);
+        }
+    }
+    if missing.is_empty() {
+        return Ok(symbols);
+    }
+
+    let mut load_pubkeys = missing.clone();
+    load_pubkeys.extend(missing.iter().map(|mint| Metadata::find_pda(mint).0));
+    let mut rsps = Vec::new();
+    for chunk in load_pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        rsps.extend(rpc_client.get_multiple_accounts(chunk)?);
+    }
+    let (mint_rsps, metadata_rsps) = rsps.split_at(missing.len());
+    for ((mint, mint_rsp), metadata_rsp) in missing.iter().zip(mint_rsps).zip(metadata_rsps) {
+        let token_metadata = mint_rsp
+            .as_ref()
+            .and_then(|account| common_utils::unpack_mint(&account.data).ok())
+            .and_then(|mint_state| {
+                common_utils::get_account_extensions(&mint_state)
+                    .into_iter()
+                    .find_map(|extension| match extension {
+                        ExtensionStruct::TokenMetadata(metadata) => {
+                            Some((metadata.name, metadata.symbol))
+                        }
+                        _ => None,
+                    })
+            });
+        let metaplex_metadata = metadata_rsp
+            .as_ref()
+            .and_then(|account| Metadata::from_bytes(&account.data).ok())
+            .map(|metadata| (metadata.name, metadata.symbol));
+        if let Some((name, symbol)) = token_metadata.or(metaplex_metadata) {
+            symbols.insert(
+                *mint,
+                TokenSymbol {
+                    mint: *mint,
+                    symbol: trim_metadata_string(&symbol),
+                    name: trim_metadata_string(&name),
+                },
+            );
+        }
+    }
+    Ok(symbols)
+}
+
+/// The symbol and the mint, or the mint alone if the symbol is unknown.
+pub fn mint_label(symbols: &HashMap<Pubkey, TokenSymbol>, mint: &Pubkey) -> String {
+    match symbols.get(mint) {
+        Some(token) if !token.symbol.is_empty() => format!("{} ({})", token.symbol, mint),
+        _ => mint.to_string(),
+    }
+}
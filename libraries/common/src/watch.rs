@@ -0,0 +1,175 @@
+use crate::{common_types::TEN_THOUSAND, PoolMints};
+use anyhow::{format_err, Result};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    pubsub_client::PubsubClient, rpc_client::RpcClient, rpc_config::RpcAccountInfoConfig,
+};
+use solana_sdk::{account::Account, commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::{collections::HashMap, sync::mpsc, thread};
+
+/// The state of the pool recomputed from the watched accounts.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolSnapshot {
+    pub pool_id: Pubkey,
+    pub mints: PoolMints,
+    /// The price of token 0 quoted in token 1.
+    pub price: f64,
+    pub reserve_0: u64,
+    pub reserve_1: u64,
+    /// The active liquidity of the clmm pool.
+    pub liquidity: Option<u128>,
+    pub tick_current: Option<i32>,
+    /// The initialized ticks in the watched tick arrays of the clmm pool.
+    pub initialized_ticks: Option<u32>,
+}
+
+/// The price of token 0 quoted in token 1 from the reserves, adjusted with the decimals.
+pub fn reserves_price(reserve_0: u64, reserve_1: u64, mints: &PoolMints) -> f64 {
+    if reserve_0 == 0 {
+        return 0.0;
+    }
+    reserve_1 as f64 / reserve_0 as f64
+        * 10f64.powi(mints.decimals_0 as i32 - mints.decimals_1 as i32)
+}
+
+pub fn get_watched_account<'a>(
+    accounts: &'a HashMap<Pubkey, Account>,
+    key: &Pubkey,
+) -> Result<&'a Account> {
+    accounts
+        .get(key)
+        .ok_or(format_err!("account {} is not loaded", key))
+}
+
+/// Subscribe to the accounts and call `on_update` with the slot and the latest data of all the
+/// accounts on each update, starting with the data fetched over rpc.
+/// `on_update` returns false to unsubscribe, e.g. when the accounts to watch changed, then
+/// `Ok` is returned. Otherwise returns only if `on_update` fails or the subscriptions are closed.
+pub fn watch_accounts<F>(
+    rpc_client: &RpcClient,
+    ws_url: &str,
+    accounts: &[Pubkey],
+    mut on_update: F,
+) -> Result<()>
+where
+    F: FnMut(u64, &HashMap<Pubkey, Account>) -> Result<bool>,
+{
+    let config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        ..RpcAccountInfoConfig::default()
+    };
+    let (sender, receiver) = mpsc::channel();
+    // the subscriptions are unsubscribed once dropped
+    let mut subscriptions = Vec::new();
+    for account in accounts {
+        let (subscription, updates) =
+            PubsubClient::account_subscribe(ws_url, account, Some(config.clone()))?;
+        subscriptions.push(subscription);
+        let sender = sender.clone();
+        let account = *account;
+        thread::spawn(move || {
+            for update in updates.iter() {
+                if sender.send((account, update)).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    drop(sender);
+
+    // fetch after subscribed, so no update is missed in between
+    let response = rpc_client
+        .get_multiple_accounts_with_commitment(accounts, CommitmentConfig::confirmed())?;
+    let mut account_datas: HashMap<Pubkey, Account> = accounts
+        .iter()
+        .zip(response.value)
+        .filter_map(|(key, account)| account.map(|account| (*key, account)))
+        .collect();
+    if !on_update(response.context.slot, &account_datas)? {
+        return Ok(());
+    }
+    for (account, update) in receiver.iter() {
+        if let Some(data) = update.value.decode::<Account>() {
+            account_datas.insert(account, data);
+        }
+        if !on_update(update.context.slot, &account_datas)? {
+            return Ok(());
+        }
+    }
+    Err(format_err!("the websocket subscriptions are closed"))
+}
+
+fn change_bps(baseline: f64, value: f64) -> f64 {
+    if baseline == 0.0 {
+        return 0.0;
+    }
+    (value - baseline) / baseline * TEN_THOUSAND as f64
+}
+
+/// The alerts of the snapshot versus the baseline, the price and the reserves are alerted
+/// once they moved more than the thresholds in bps.
+pub fn snapshot_alerts(
+    baseline: &PoolSnapshot,
+    snapshot: &PoolSnapshot,
+    price_change_bps: Option<u64>,
+    reserve_change_bps: Option<u64>,
+) -> Vec<String> {
+    let mut alerts = Vec::new();
+    if let Some(threshold) = price_change_bps {
+        let change = change_bps(baseline.price, snapshot.price);
+        if change.abs() >= threshold as f64 {
+            alerts.push(format!("price changed {:.2} bps", change));
+        }
+    }
+    if let Some(threshold) = reserve_change_bps {
+        for (name, baseline_reserve, reserve) in [
+            ("reserve_0", baseline.reserve_0, snapshot.reserve_0),
+            ("reserve_1", baseline.reserve_1, snapshot.reserve_1),
+        ] {
+            let change = change_bps(baseline_reserve as f64, reserve as f64);
+            if change.abs() >= threshold as f64 {
+                alerts.push(format!("{} changed {:.2} bps", name, change));
+            }
+        }
+    }
+    alerts
+}
+
+pub fn print_snapshot(slot: u64, snapshot: &PoolSnapshot, alerts: &[String], json: bool) {
+    let reserve_0 = snapshot.reserve_0 as f64 / 10f64.powi(snapshot.mints.decimals_0 as i32);
+    let reserve_1 = snapshot.reserve_1 as f64 / 10f64.powi(snapshot.mints.decimals_1 as i32);
+    if json {
+        println!(
+            "{}",
+            serde_json::json!({
+                "slot": slot,
+                "pool_id": snapshot.pool_id.to_string(),
+                "mint_0": snapshot.mints.mint_0.to_string(),
+                "mint_1": snapshot.mints.mint_1.to_string(),
+                "price": snapshot.price,
+                "reserve_0": reserve_0,
+                "reserve_1": reserve_1,
+                "liquidity": snapshot.liquidity.map(|liquidity| liquidity.to_string()),
+                "tick_current": snapshot.tick_current,
+                "initialized_ticks": snapshot.initialized_ticks,
+                "alerts": alerts,
+            })
+        );
+    } else {
+        println!(
+            "slot:{}, pool:{}, price:{}, reserve_0:{}, reserve_1:{}, liquidity:{:?}, tick_current:{:?}, initialized_ticks:{:?}",
+            slot,
+            snapshot.pool_id,
+            snapshot.price,
+            reserve_0,
+            reserve_1,
+            snapshot.liquidity,
+            snapshot.tick_current,
+            snapshot.initialized_ticks
+        );
+        for alert in alerts {
+            println!("alert: {}", alert);
+        }
+    }
+}
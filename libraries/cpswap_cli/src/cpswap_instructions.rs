@@ -6,9 +6,12 @@ use raydium_cp_swap::{
     states::{AMM_CONFIG_SEED, OBSERVATION_SEED, POOL_LP_MINT_SEED, POOL_SEED, POOL_VAULT_SEED},
     AUTH_SEED,
 };
-use solana_sdk::{instruction::Instruction, pubkey::Pubkey, system_program, sysvar};
+use solana_sdk::{
+    instruction::Instruction, pubkey::Pubkey, signer::null_signer::NullSigner, system_program,
+    sysvar,
+};
 
-use anyhow::{format_err, Result};
+use anyhow::Result;
 use std::rc::Rc;
 
 pub fn create_config_instr(
@@ -19,8 +22,7 @@ pub fn create_config_instr(
     fund_fee_rate: u64,
     create_pool_fee: u64,
 ) -> Result<Vec<Instruction>> {
-    let wallet = solana_sdk::signature::read_keypair_file(config.wallet())
-        .map_err(|_| format_err!("failed to read keypair from {}", config.wallet()))?;
+    let wallet = NullSigner::new(&config.wallet_pubkey()?);
     let cluster = config.cluster();
     // Client.
     let client = Client::new(cluster, Rc::new(wallet));
@@ -64,8 +66,7 @@ pub fn initialize_pool_instr(
     init_amount_1: u64,
     open_time: u64,
 ) -> Result<Vec<Instruction>> {
-    let wallet = solana_sdk::signature::read_keypair_file(config.wallet())
-        .map_err(|_| format_err!("failed to read keypair from {}", config.wallet()))?;
+    let wallet = NullSigner::new(&config.wallet_pubkey()?);
     let cluster = config.cluster();
     // Client.
     let client = Client::new(cluster, Rc::new(wallet));
@@ -177,8 +178,7 @@ pub fn deposit_instr(
     maximum_token_0_amount: u64,
     maximum_token_1_amount: u64,
 ) -> Result<Vec<Instruction>> {
-    let wallet = solana_sdk::signature::read_keypair_file(config.wallet())
-        .map_err(|_| format_err!("failed to read keypair from {}", config.wallet()))?;
+    let wallet = NullSigner::new(&config.wallet_pubkey()?);
     let cluster = config.cluster();
     // Client.
     let client = Client::new(cluster, Rc::new(wallet));
@@ -227,8 +227,7 @@ pub fn withdraw_instr(
     minimum_token_0_amount: u64,
     minimum_token_1_amount: u64,
 ) -> Result<Vec<Instruction>> {
-    let wallet = solana_sdk::signature::read_keypair_file(config.wallet())
-        .map_err(|_| format_err!("failed to read keypair from {}", config.wallet()))?;
+    let wallet = NullSigner::new(&config.wallet_pubkey()?);
     let cluster = config.cluster();
     // Client.
     let client = Client::new(cluster, Rc::new(wallet));
@@ -279,8 +278,7 @@ pub fn swap_base_input_instr(
     amount_in: u64,
     minimum_amount_out: u64,
 ) -> Result<Vec<Instruction>> {
-    let wallet = solana_sdk::signature::read_keypair_file(config.wallet())
-        .map_err(|_| format_err!("failed to read keypair from {}", config.wallet()))?;
+    let wallet = NullSigner::new(&config.wallet_pubkey()?);
     let cluster = config.cluster();
     // Client.
     let client = Client::new(cluster, Rc::new(wallet));
@@ -329,8 +327,7 @@ pub fn swap_base_output_instr(
     max_amount_in: u64,
     amount_out: u64,
 ) -> Result<Vec<Instruction>> {
-    let wallet = solana_sdk::signature::read_keypair_file(config.wallet())
-        .map_err(|_| format_err!("failed to read keypair from {}", config.wallet()))?;
+    let wallet = NullSigner::new(&config.wallet_pubkey()?);
     let cluster = config.cluster();
     // Client.
     let client = Client::new(cluster, Rc::new(wallet));
@@ -29,4 +29,8 @@ pub struct CpSwapSwapChangeResult {
     pub output_token_program: Pubkey,
     pub amount_specified: u64,
     pub other_amount_threshold: u64,
+    /// The price impact of the quote versus the spot price, the trade fee included.
+    pub price_impact_bps: u64,
+    /// The slippage applied to the other amount threshold.
+    pub slippage_bps: u64,
 }
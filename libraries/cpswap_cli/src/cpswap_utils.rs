@@ -1,9 +1,14 @@
 use crate::cpswap_types::{CpSwapLiquidityChangeResult, CpSwapSwapChangeResult};
-use anyhow::Result;
+use anyhow::{format_err, Result};
 use arrayref::array_ref;
-use common::{common_utils, rpc};
+use common::{
+    common_types::Slippage, common_utils, get_watched_account, rpc, CommonConfig, CostAccounts,
+    FundsCheck, IndexedPool, PoolIndex, PoolMints, PoolOperation, PoolProgram, PoolSnapshot,
+    PoolStatus, PoolSummary, PoolTwap,
+};
 use solana_client::rpc_client::RpcClient;
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{account::Account, program_pack::Pack, pubkey::Pubkey};
+use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
 
 pub fn specified_tokens_to_lp_tokens(
@@ -73,6 +78,8 @@ pub fn add_liquidity_calculate(
         common_utils::unpack_mint(&token_0_mint_account.as_ref().unwrap().data).unwrap();
     let token_1_mint_info =
         common_utils::unpack_mint(&token_1_mint_account.as_ref().unwrap().data).unwrap();
+    common_utils::check_transfer_hook(&pool_state.token_0_mint, &token_0_mint_info)?;
+    common_utils::check_transfer_hook(&pool_state.token_1_mint, &token_1_mint_info)?;
     let epoch = rpc_client.get_epoch_info().unwrap().epoch;
 
     let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
@@ -175,6 +182,8 @@ pub fn remove_liquidity_calculate(
         common_utils::unpack_mint(&token_0_mint_account.as_ref().unwrap().data).unwrap();
     let token_1_mint_info =
         common_utils::unpack_mint(&token_1_mint_account.as_ref().unwrap().data).unwrap();
+    common_utils::check_transfer_hook(&pool_state.token_0_mint, &token_0_mint_info)?;
+    common_utils::check_transfer_hook(&pool_state.token_1_mint, &token_1_mint_info)?;
     let epoch = rpc_client.get_epoch_info().unwrap().epoch;
 
     let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
@@ -235,7 +244,7 @@ pub fn swap_calculate(
     pool_id: Pubkey,
     user_input_token: Pubkey,
     amount_specified: u64,
-    slippage_bps: u64,
+    slippage: Slippage,
     base_in: bool,
 ) -> Result<CpSwapSwapChangeResult> {
     let pool_state =
@@ -270,8 +279,10 @@ pub fn swap_calculate(
         common_utils::unpack_mint(&token_0_mint_account.as_ref().unwrap().data).unwrap();
     let token_1_mint_info =
         common_utils::unpack_mint(&token_1_mint_account.as_ref().unwrap().data).unwrap();
-    let user_input_token_info =
-        common_utils::unpack_token(&user_input_token_account.as_ref().unwrap().data).unwrap();
+    common_utils::check_transfer_hook(&pool_state.token_0_mint, &token_0_mint_info)?;
+    common_utils::check_transfer_hook(&pool_state.token_1_mint, &token_1_mint_info)?;
+    let user_input_mint =
+        common_utils::get_user_token_mint(&user_input_token, user_input_token_account.as_ref())?;
 
     let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
         token_0_vault_info.base.amount,
@@ -289,7 +300,7 @@ pub fn swap_calculate(
         input_token_program,
         output_token_program,
         transfer_fee,
-    ) = if user_input_token_info.base.mint == token_0_vault_info.base.mint {
+    ) = if user_input_mint == token_0_vault_info.base.mint {
         (
             raydium_cp_swap::curve::TradeDirection::ZeroForOne,
             total_token_0_amount,
@@ -306,7 +317,7 @@ pub fn swap_calculate(
                 common_utils::get_transfer_inverse_fee(&token_1_mint_info, epoch, amount_specified)
             },
         )
-    } else if user_input_token_info.base.mint == token_1_vault_info.base.mint {
+    } else if user_input_mint == token_1_vault_info.base.mint {
         (
             raydium_cp_swap::curve::TradeDirection::OneForZero,
             total_token_1_amount,
@@ -327,7 +338,8 @@ pub fn swap_calculate(
         panic!("input tokens not match pool vaults");
     };
 
-    let other_amount_threshold = if base_in {
+    let spot_rate = total_output_token_amount as f64 / total_input_token_amount as f64;
+    let (other_amount_threshold, price_impact_bps, slippage_bps) = if base_in {
         // Take transfer fees into account for actual amount transferred in
         let actual_amount_in = amount_specified.saturating_sub(transfer_fee);
         let result = raydium_cp_swap::curve::CurveCalculator::swap_base_input(
@@ -350,10 +362,15 @@ pub fn swap_calculate(
             }
         };
         let amount_received = amount_out.checked_sub(transfer_fee).unwrap();
+        let price_impact_bps = common_utils::price_impact_bps(
+            spot_rate,
+            result.destination_amount_swapped as f64 / actual_amount_in as f64,
+        );
+        let slippage_bps = slippage.bps(price_impact_bps);
         // calc mint out amount with slippage
         let minimum_amount_out =
             common_utils::amount_with_slippage(amount_received, slippage_bps, false)?;
-        minimum_amount_out
+        (minimum_amount_out, price_impact_bps, slippage_bps)
     } else {
         // Take transfer fees into account for actual amount user received
         let actual_amount_out = amount_specified.checked_add(transfer_fee).unwrap();
@@ -389,10 +406,15 @@ pub fn swap_calculate(
         let input_transfer_amount = source_amount_swapped
             .checked_add(amount_in_transfer_fee)
             .unwrap();
+        let price_impact_bps = common_utils::price_impact_bps(
+            spot_rate,
+            actual_amount_out as f64 / source_amount_swapped as f64,
+        );
+        let slippage_bps = slippage.bps(price_impact_bps);
         // calc max in with slippage
         let max_amount_in =
             common_utils::amount_with_slippage(input_transfer_amount, slippage_bps, true)?;
-        max_amount_in
+        (max_amount_in, price_impact_bps, slippage_bps)
     };
 
     Ok(CpSwapSwapChangeResult {
@@ -408,5 +430,277 @@ pub fn swap_calculate(
         output_token_program,
         amount_specified,
         other_amount_threshold,
+        price_impact_bps,
+        slippage_bps,
+    })
+}
+
+pub fn get_pool_mints(rpc_client: &RpcClient, pool_id: &Pubkey) -> Result<PoolMints> {
+    let pool_state =
+        rpc::get_anchor_account::<raydium_cp_swap::states::PoolState>(rpc_client, pool_id)?
+            .unwrap();
+    Ok(PoolMints {
+        mint_0: pool_state.token_0_mint,
+        mint_1: pool_state.token_1_mint,
+        decimals_0: pool_state.mint_0_decimals,
+        decimals_1: pool_state.mint_1_decimals,
     })
 }
+
+/// Decode the keys of the cp-swap pool for the pool index.
+pub fn decode_indexed_pool(pool_id: &Pubkey, pool_account: &Account) -> Result<IndexedPool> {
+    let pool_state = common_utils::deserialize_anchor_account::<raydium_cp_swap::states::PoolState>(
+        pool_account,
+    )?;
+    Ok(IndexedPool {
+        program: PoolProgram::CpSwap,
+        pool_id: *pool_id,
+        mint_0: pool_state.token_0_mint,
+        mint_1: pool_state.token_1_mint,
+        decimals_0: pool_state.mint_0_decimals,
+        decimals_1: pool_state.mint_1_decimals,
+        fee_config: pool_state.amm_config,
+        vault_0: pool_state.token_0_vault,
+        vault_1: pool_state.token_1_vault,
+        lp_mint: pool_state.lp_mint,
+        observation: pool_state.observation_key,
+        amm_keys: None,
+    })
+}
+
+/// Add the new cp-swap pools to the index at `path` and drop the closed ones.
+pub fn refresh_pool_index(
+    rpc_client: &RpcClient,
+    config: &CommonConfig,
+    path: &str,
+    index: &mut PoolIndex,
+) -> Result<(usize, usize)> {
+    common::refresh_program_pools(
+        rpc_client,
+        path,
+        index,
+        PoolProgram::CpSwap,
+        &config.cp_program(),
+        raydium_cp_swap::states::PoolState::LEN as u64,
+        decode_indexed_pool,
+    )
+}
+
+/// The pool and the vaults of the cp-swap pool.
+pub fn get_watch_accounts(rpc_client: &RpcClient, pool_id: &Pubkey) -> Result<Vec<Pubkey>> {
+    let pool_state =
+        rpc::get_anchor_account::<raydium_cp_swap::states::PoolState>(rpc_client, pool_id)?
+            .unwrap();
+    Ok(vec![
+        *pool_id,
+        pool_state.token_0_vault,
+        pool_state.token_1_vault,
+    ])
+}
+
+/// Recompute the reserves and the price of the cp-swap pool from the watched accounts,
+/// the reserves are the vault amounts without the protocol and fund fees.
+pub fn pool_snapshot(
+    pool_id: &Pubkey,
+    accounts: &HashMap<Pubkey, Account>,
+) -> Result<PoolSnapshot> {
+    let pool_state = common_utils::deserialize_anchor_account::<raydium_cp_swap::states::PoolState>(
+        get_watched_account(accounts, pool_id)?,
+    )?;
+    let token_0_vault_info = common_utils::unpack_token(
+        &get_watched_account(accounts, &pool_state.token_0_vault)?.data,
+    )?;
+    let token_1_vault_info = common_utils::unpack_token(
+        &get_watched_account(accounts, &pool_state.token_1_vault)?.data,
+    )?;
+    let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
+        token_0_vault_info.base.amount,
+        token_1_vault_info.base.amount,
+    );
+    let mints = PoolMints {
+        mint_0: pool_state.token_0_mint,
+        mint_1: pool_state.token_1_mint,
+        decimals_0: pool_state.mint_0_decimals,
+        decimals_1: pool_state.mint_1_decimals,
+    };
+    Ok(PoolSnapshot {
+        pool_id: *pool_id,
+        mints,
+        price: common::reserves_price(total_token_0_amount, total_token_1_amount, &mints),
+        reserve_0: total_token_0_amount,
+        reserve_1: total_token_1_amount,
+        liquidity: None,
+        tick_current: None,
+        initialized_ticks: None,
+    })
+}
+
+/// The twap of the pool over the window in seconds, computed from the cumulative token 0
+/// price of the observations.
+pub fn get_pool_twap(rpc_client: &RpcClient, pool_id: &Pubkey, window: u64) -> Result<PoolTwap> {
+    let pool_state =
+        rpc::get_anchor_account::<raydium_cp_swap::states::PoolState>(rpc_client, pool_id)?
+            .unwrap();
+    let load_pubkeys = vec![
+        pool_state.observation_key,
+        pool_state.token_0_vault,
+        pool_state.token_1_vault,
+    ];
+    let rsps = rpc_client.get_multiple_accounts(&load_pubkeys)?;
+    let [observation_account, token_0_vault_account, token_1_vault_account] =
+        array_ref![rsps, 0, 3];
+    let observation_state = common_utils::deserialize_anchor_account::<
+        raydium_cp_swap::states::ObservationState,
+    >(observation_account.as_ref().unwrap())?;
+    let token_0_vault_info =
+        common_utils::unpack_token(&token_0_vault_account.as_ref().unwrap().data)?;
+    let token_1_vault_info =
+        common_utils::unpack_token(&token_1_vault_account.as_ref().unwrap().data)?;
+    let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
+        token_0_vault_info.base.amount,
+        token_1_vault_info.base.amount,
+    );
+    let mints = PoolMints {
+        mint_0: pool_state.token_0_mint,
+        mint_1: pool_state.token_1_mint,
+        decimals_0: pool_state.mint_0_decimals,
+        decimals_1: pool_state.mint_1_decimals,
+    };
+
+    // copy out of the packed account
+    let observations = observation_state.observations;
+    let newest_index = observation_state.observation_index as usize;
+    let timestamps: Vec<u64> = observations
+        .iter()
+        .map(|observation| observation.block_timestamp)
+        .collect();
+    let start_index = common::observation_window_start(&timestamps, newest_index, window).ok_or(
+        format_err!("not enough observations of the pool {}", pool_id),
+    )?;
+    let newest = observations[newest_index];
+    let start = observations[start_index];
+    let duration = newest.block_timestamp - start.block_timestamp;
+    let price_x32 = newest
+        .cumulative_token_0_price_x32
+        .wrapping_sub(start.cumulative_token_0_price_x32)
+        / duration as u128;
+    Ok(PoolTwap {
+        pool_id: *pool_id,
+        mints,
+        spot_price: common::reserves_price(total_token_0_amount, total_token_1_amount, &mints),
+        twap_price: price_x32 as f64 / (1u64 << 32) as f64
+            * 10f64.powi(mints.decimals_0 as i32 - mints.decimals_1 as i32),
+        window_start: start.block_timestamp,
+        window_end: newest.block_timestamp,
+    })
+}
+
+/// The spot price of token 0 quoted in token 1 of the cp-swap pool.
+pub fn get_pool_price(rpc_client: &RpcClient, pool_id: &Pubkey) -> Result<f64> {
+    let accounts = get_watch_accounts(rpc_client, pool_id)?;
+    let rsps = rpc_client.get_multiple_accounts(&accounts)?;
+    let account_datas: HashMap<Pubkey, Account> = accounts
+        .into_iter()
+        .zip(rsps)
+        .filter_map(|(key, account)| account.map(|account| (key, account)))
+        .collect();
+    Ok(pool_snapshot(pool_id, &account_datas)?.price)
+}
+
+/// Decode the status bits of the cp-swap pool into the operations, a set bit disables one.
+pub fn decode_pool_status(pool_state: &raydium_cp_swap::states::PoolState) -> PoolStatus {
+    let enabled = |bit: u8| pool_state.status & (1 << bit) == 0;
+    PoolStatus {
+        status: pool_state.status as u64,
+        operations: vec![
+            (PoolOperation::Deposit, enabled(0)),
+            (PoolOperation::Withdraw, enabled(1)),
+            (PoolOperation::Swap, enabled(2)),
+        ],
+        open_time: pool_state.open_time,
+    }
+}
+
+/// Refuse the operation the status of the cp-swap pool disables, or a swap before the pool opens.
+pub fn check_pool_operation(
+    rpc_client: &RpcClient,
+    pool_id: &Pubkey,
+    operation: PoolOperation,
+) -> Result<()> {
+    let pool_state =
+        rpc::get_anchor_account::<raydium_cp_swap::states::PoolState>(rpc_client, pool_id)?
+            .unwrap();
+    common::check_pool_status(pool_id, &decode_pool_status(&pool_state), operation)
+}
+
+/// The price, the reserves without the protocol and fund fees, the lp supply, the fees of the
+/// amm config and the status of the cp-swap pool.
+pub fn get_pool_summary(rpc_client: &RpcClient, pool_id: &Pubkey) -> Result<PoolSummary> {
+    let pool_state =
+        rpc::get_anchor_account::<raydium_cp_swap::states::PoolState>(rpc_client, pool_id)?
+            .unwrap();
+    let load_pubkeys = vec![
+        *pool_id,
+        pool_state.token_0_vault,
+        pool_state.token_1_vault,
+        pool_state.amm_config,
+    ];
+    let rsps = rpc_client.get_multiple_accounts(&load_pubkeys)?;
+    let account_datas: HashMap<Pubkey, Account> = load_pubkeys
+        .into_iter()
+        .zip(rsps)
+        .filter_map(|(key, account)| account.map(|account| (key, account)))
+        .collect();
+    let snapshot = pool_snapshot(pool_id, &account_datas)?;
+    let amm_config_state = common_utils::deserialize_anchor_account::<
+        raydium_cp_swap::states::AmmConfig,
+    >(get_watched_account(&account_datas, &pool_state.amm_config)?)?;
+    Ok(PoolSummary {
+        snapshot,
+        lp_supply: Some(pool_state.lp_supply),
+        fee_rates: vec![
+            ("trade", amm_config_state.trade_fee_rate),
+            ("protocol_share", amm_config_state.protocol_fee_rate),
+            ("fund_share", amm_config_state.fund_fee_rate),
+        ],
+        status: decode_pool_status(&pool_state),
+    })
+}
+
+/// The recent price movement in bps of the cp-swap pool, the deviation of the spot price from
+/// the twap over the window.
+pub fn get_price_movement_bps(
+    rpc_client: &RpcClient,
+    pool_id: &Pubkey,
+    window: u64,
+) -> Result<u64> {
+    let pool_twap = get_pool_twap(rpc_client, pool_id, window)?;
+    Ok(pool_twap.deviation_bps().abs().ceil() as u64)
+}
+
+/// The rent of the accounts the initialization of a pool creates, the vaults hold the extensions
+/// the mints require.
+pub fn create_pool_rent(
+    funds: &mut FundsCheck,
+    mint0_account: &Account,
+    mint1_account: &Account,
+) -> Result<()> {
+    funds.rent("pool state", raydium_cp_swap::states::PoolState::LEN);
+    funds.rent(
+        "observation",
+        raydium_cp_swap::states::ObservationState::LEN,
+    );
+    funds.rent("lp mint", spl_token::state::Mint::LEN);
+    funds.rent("token_0 vault", common::token_account_len(mint0_account)?);
+    funds.rent("token_1 vault", common::token_account_len(mint1_account)?);
+    funds.rent("lp token account", spl_token::state::Account::LEN);
+    Ok(())
+}
+
+/// The create pool fee receiver receives the creation fee of the cp-swap pools.
+pub fn cost_accounts() -> CostAccounts {
+    CostAccounts {
+        fee_receivers: vec![raydium_cp_swap::create_pool_fee_reveiver::id()],
+        refundable: Vec::new(),
+    }
+}
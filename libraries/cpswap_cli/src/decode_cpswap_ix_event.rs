@@ -1,7 +1,8 @@
 use anchor_client::ClientError;
+use anchor_lang::prelude::Pubkey;
 use anchor_lang::Discriminator;
 use anyhow::Result;
-use common::{common_types, InstructionDecodeType};
+use common::{common_types, InstructionDecodeType, ReceiptEvent, SwapQuote, SwapTrade};
 use raydium_cp_swap::instruction;
 use raydium_cp_swap::states::*;
 
@@ -36,6 +37,69 @@ pub fn handle_program_instruction(
         }
     }
 
+    match decode_cpswap_instruction(&data)? {
+        Some(instruction) => println!("{:#?}", instruction),
+        None => println!("unknow instruction: {}", instr_data),
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum CpSwapInstruction {
+    CreateAmmConfig {
+        index: u16,
+        trade_fee_rate: u64,
+        protocol_fee_rate: u64,
+        fund_fee_rate: u64,
+        create_pool_fee: u64,
+    },
+    UpdateAmmConfig {
+        param: u8,
+        value: u64,
+    },
+    Initialize {
+        init_amount_0: u64,
+        init_amount_1: u64,
+        open_time: u64,
+    },
+    UpdatePoolStatus {
+        status: u8,
+    },
+    CollectProtocolFee {
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    },
+    CollectFundFee {
+        amount_0_requested: u64,
+        amount_1_requested: u64,
+    },
+    Deposit {
+        lp_token_amount: u64,
+        maximum_token_0_amount: u64,
+        maximum_token_1_amount: u64,
+    },
+    Withdraw {
+        lp_token_amount: u64,
+        minimum_token_0_amount: u64,
+        minimum_token_1_amount: u64,
+    },
+    SwapBaseInput {
+        amount_in: u64,
+        minimum_amount_out: u64,
+    },
+    SwapBaseOutput {
+        max_amount_in: u64,
+        amount_out: u64,
+    },
+}
+
+/// Decode the instruction data, returns `None` if the instruction is unknown.
+pub fn decode_cpswap_instruction(data: &[u8]) -> Result<Option<CpSwapInstruction>, ClientError> {
+    if data.len() < 8 {
+        return Err(ClientError::AnchorError(
+            anchor_lang::error::ErrorCode::InstructionMissing.into(),
+        ));
+    }
     let mut ix_data: &[u8] = &data[..];
     let disc: [u8; 8] = {
         let mut disc = [0; 8];
@@ -43,204 +107,127 @@ pub fn handle_program_instruction(
         ix_data = &ix_data[8..];
         disc
     };
-    // println!("{:?}", disc);
-
-    match disc {
+    let instruction = match disc {
         instruction::CreateAmmConfig::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::CreateAmmConfig>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct CreateAmmConfig {
-                pub index: u16,
-                pub trade_fee_rate: u64,
-                pub protocol_fee_rate: u64,
-                pub fund_fee_rate: u64,
-                pub create_pool_fee: u64,
-            }
-            impl From<instruction::CreateAmmConfig> for CreateAmmConfig {
-                fn from(instr: instruction::CreateAmmConfig) -> CreateAmmConfig {
-                    CreateAmmConfig {
-                        index: instr.index,
-                        trade_fee_rate: instr.trade_fee_rate,
-                        protocol_fee_rate: instr.protocol_fee_rate,
-                        fund_fee_rate: instr.fund_fee_rate,
-                        create_pool_fee: instr.create_pool_fee,
-                    }
-                }
+            let ix = decode_instruction::<instruction::CreateAmmConfig>(&mut ix_data)?;
+            CpSwapInstruction::CreateAmmConfig {
+                index: ix.index,
+                trade_fee_rate: ix.trade_fee_rate,
+                protocol_fee_rate: ix.protocol_fee_rate,
+                fund_fee_rate: ix.fund_fee_rate,
+                create_pool_fee: ix.create_pool_fee,
             }
-            println!("{:#?}", CreateAmmConfig::from(ix));
         }
         instruction::UpdateAmmConfig::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::UpdateAmmConfig>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct UpdateAmmConfig {
-                pub param: u8,
-                pub value: u64,
+            let ix = decode_instruction::<instruction::UpdateAmmConfig>(&mut ix_data)?;
+            CpSwapInstruction::UpdateAmmConfig {
+                param: ix.param,
+                value: ix.value,
             }
-            impl From<instruction::UpdateAmmConfig> for UpdateAmmConfig {
-                fn from(instr: instruction::UpdateAmmConfig) -> UpdateAmmConfig {
-                    UpdateAmmConfig {
-                        param: instr.param,
-                        value: instr.value,
-                    }
-                }
-            }
-            println!("{:#?}", UpdateAmmConfig::from(ix));
         }
         instruction::Initialize::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::Initialize>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct Initialize {
-                pub init_amount_0: u64,
-                pub init_amount_1: u64,
-                pub open_time: u64,
-            }
-            impl From<instruction::Initialize> for Initialize {
-                fn from(instr: instruction::Initialize) -> Initialize {
-                    Initialize {
-                        init_amount_0: instr.init_amount_0,
-                        init_amount_1: instr.init_amount_1,
-                        open_time: instr.open_time,
-                    }
-                }
+            let ix = decode_instruction::<instruction::Initialize>(&mut ix_data)?;
+            CpSwapInstruction::Initialize {
+                init_amount_0: ix.init_amount_0,
+                init_amount_1: ix.init_amount_1,
+                open_time: ix.open_time,
             }
-            println!("{:#?}", Initialize::from(ix));
         }
         instruction::UpdatePoolStatus::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::UpdatePoolStatus>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct UpdatePoolStatus {
-                pub status: u8,
-            }
-            impl From<instruction::UpdatePoolStatus> for UpdatePoolStatus {
-                fn from(instr: instruction::UpdatePoolStatus) -> UpdatePoolStatus {
-                    UpdatePoolStatus {
-                        status: instr.status,
-                    }
-                }
-            }
-            println!("{:#?}", UpdatePoolStatus::from(ix));
+            let ix = decode_instruction::<instruction::UpdatePoolStatus>(&mut ix_data)?;
+            CpSwapInstruction::UpdatePoolStatus { status: ix.status }
         }
         instruction::CollectProtocolFee::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::CollectProtocolFee>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct CollectProtocolFee {
-                pub amount_0_requested: u64,
-                pub amount_1_requested: u64,
-            }
-            impl From<instruction::CollectProtocolFee> for CollectProtocolFee {
-                fn from(instr: instruction::CollectProtocolFee) -> CollectProtocolFee {
-                    CollectProtocolFee {
-                        amount_0_requested: instr.amount_0_requested,
-                        amount_1_requested: instr.amount_1_requested,
-                    }
-                }
+            let ix = decode_instruction::<instruction::CollectProtocolFee>(&mut ix_data)?;
+            CpSwapInstruction::CollectProtocolFee {
+                amount_0_requested: ix.amount_0_requested,
+                amount_1_requested: ix.amount_1_requested,
             }
-            println!("{:#?}", CollectProtocolFee::from(ix));
         }
         instruction::CollectFundFee::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::CollectFundFee>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct CollectFundFee {
-                pub amount_0_requested: u64,
-                pub amount_1_requested: u64,
-            }
-            impl From<instruction::CollectFundFee> for CollectFundFee {
-                fn from(instr: instruction::CollectFundFee) -> CollectFundFee {
-                    CollectFundFee {
-                        amount_0_requested: instr.amount_0_requested,
-                        amount_1_requested: instr.amount_1_requested,
-                    }
-                }
+            let ix = decode_instruction::<instruction::CollectFundFee>(&mut ix_data)?;
+            CpSwapInstruction::CollectFundFee {
+                amount_0_requested: ix.amount_0_requested,
+                amount_1_requested: ix.amount_1_requested,
             }
-            println!("{:#?}", CollectFundFee::from(ix));
         }
         instruction::Deposit::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::Deposit>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct Deposit {
-                pub lp_token_amount: u64,
-                pub maximum_token_0_amount: u64,
-                pub maximum_token_1_amount: u64,
+            let ix = decode_instruction::<instruction::Deposit>(&mut ix_data)?;
+            CpSwapInstruction::Deposit {
+                lp_token_amount: ix.lp_token_amount,
+                maximum_token_0_amount: ix.maximum_token_0_amount,
+                maximum_token_1_amount: ix.maximum_token_1_amount,
             }
-            impl From<instruction::Deposit> for Deposit {
-                fn from(instr: instruction::Deposit) -> Deposit {
-                    Deposit {
-                        lp_token_amount: instr.lp_token_amount,
-                        maximum_token_0_amount: instr.maximum_token_0_amount,
-                        maximum_token_1_amount: instr.maximum_token_1_amount,
-                    }
-                }
-            }
-            println!("{:#?}", Deposit::from(ix));
         }
         instruction::Withdraw::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::Withdraw>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct Withdraw {
-                pub lp_token_amount: u64,
-                pub minimum_token_0_amount: u64,
-                pub minimum_token_1_amount: u64,
-            }
-            impl From<instruction::Withdraw> for Withdraw {
-                fn from(instr: instruction::Withdraw) -> Withdraw {
-                    Withdraw {
-                        lp_token_amount: instr.lp_token_amount,
-                        minimum_token_0_amount: instr.minimum_token_0_amount,
-                        minimum_token_1_amount: instr.minimum_token_1_amount,
-                    }
-                }
+            let ix = decode_instruction::<instruction::Withdraw>(&mut ix_data)?;
+            CpSwapInstruction::Withdraw {
+                lp_token_amount: ix.lp_token_amount,
+                minimum_token_0_amount: ix.minimum_token_0_amount,
+                minimum_token_1_amount: ix.minimum_token_1_amount,
             }
-            println!("{:#?}", Withdraw::from(ix));
         }
         instruction::SwapBaseInput::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::SwapBaseInput>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct SwapBaseInput {
-                pub amount_in: u64,
-                pub minimum_amount_out: u64,
-            }
-            impl From<instruction::SwapBaseInput> for SwapBaseInput {
-                fn from(instr: instruction::SwapBaseInput) -> SwapBaseInput {
-                    SwapBaseInput {
-                        amount_in: instr.amount_in,
-                        minimum_amount_out: instr.minimum_amount_out,
-                    }
-                }
+            let ix = decode_instruction::<instruction::SwapBaseInput>(&mut ix_data)?;
+            CpSwapInstruction::SwapBaseInput {
+                amount_in: ix.amount_in,
+                minimum_amount_out: ix.minimum_amount_out,
             }
-            println!("{:#?}", SwapBaseInput::from(ix));
         }
         instruction::SwapBaseOutput::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::SwapBaseOutput>(&mut ix_data).unwrap();
-            #[derive(Debug)]
-            pub struct SwapBaseOutput {
-                pub max_amount_in: u64,
-                pub amount_out: u64,
+            let ix = decode_instruction::<instruction::SwapBaseOutput>(&mut ix_data)?;
+            CpSwapInstruction::SwapBaseOutput {
+                max_amount_in: ix.max_amount_in,
+                amount_out: ix.amount_out,
             }
-            impl From<instruction::SwapBaseOutput> for SwapBaseOutput {
-                fn from(instr: instruction::SwapBaseOutput) -> SwapBaseOutput {
-                    SwapBaseOutput {
-                        max_amount_in: instr.max_amount_in,
-                        amount_out: instr.amount_out,
-                    }
-                }
-            }
-            println!("{:#?}", SwapBaseOutput::from(ix));
-        }
-        _ => {
-            println!("unknow instruction: {}", instr_data);
         }
-    }
-    Ok(())
+        _ => return Ok(None),
+    };
+    Ok(Some(instruction))
 }
 
 fn decode_instruction<T: anchor_lang::AnchorDeserialize>(
     slice: &mut &[u8],
-) -> Result<T, anchor_lang::error::ErrorCode> {
-    let instruction: T = anchor_lang::AnchorDeserialize::deserialize(slice)
-        .map_err(|_| anchor_lang::error::ErrorCode::InstructionDidNotDeserialize)?;
+) -> Result<T, ClientError> {
+    let instruction: T = anchor_lang::AnchorDeserialize::deserialize(slice).map_err(|_| {
+        ClientError::AnchorError(anchor_lang::error::ErrorCode::InstructionDidNotDeserialize.into())
+    })?;
     Ok(instruction)
 }
 
+#[derive(Debug)]
+pub enum CpSwapEvent {
+    LpChange(LpChangeEvent),
+    Swap(SwapEvent),
+}
+
+/// Decode the base64 data of a `Program data:` log, returns `None` if the event is unknown.
+pub fn decode_cpswap_event(log: &str) -> Result<Option<CpSwapEvent>, ClientError> {
+    let borsh_bytes = anchor_lang::__private::base64::decode(log)
+        .map_err(|e| ClientError::LogParseError(e.to_string()))?;
+    if borsh_bytes.len() < 8 {
+        return Err(ClientError::LogParseError(format!(
+            "event data is too short: {}",
+            log
+        )));
+    }
+    let mut slice: &[u8] = &borsh_bytes[..];
+    let disc: [u8; 8] = {
+        let mut disc = [0; 8];
+        disc.copy_from_slice(&borsh_bytes[..8]);
+        slice = &slice[8..];
+        disc
+    };
+    let event = match disc {
+        LpChangeEvent::DISCRIMINATOR => {
+            CpSwapEvent::LpChange(decode_event::<LpChangeEvent>(&mut slice)?)
+        }
+        SwapEvent::DISCRIMINATOR => CpSwapEvent::Swap(decode_event::<SwapEvent>(&mut slice)?),
+        _ => return Ok(None),
+    };
+    Ok(Some(event))
+}
+
 pub fn handle_program_event(log_event: &str, with_prefix: bool) -> Result<(), ClientError> {
     // Log emitted from the current program.
     if let Some(log) = if with_prefix {
@@ -250,36 +237,12 @@ pub fn handle_program_event(log_event: &str, with_prefix: bool) -> Result<(), Cl
     } else {
         Some(log_event)
     } {
-        let borsh_bytes = match anchor_lang::__private::base64::decode(log) {
-            Ok(borsh_bytes) => borsh_bytes,
-            _ => {
-                println!("Could not base64 decode log: {}", log);
-                return Ok(());
-            }
-        };
-
-        let mut slice: &[u8] = &borsh_bytes[..];
-        let disc: [u8; 8] = {
-            let mut disc = [0; 8];
-            disc.copy_from_slice(&borsh_bytes[..8]);
-            slice = &slice[8..];
-            disc
-        };
-        match disc {
-            LpChangeEvent::DISCRIMINATOR => {
-                println!("{:#?}", decode_event::<LpChangeEvent>(&mut slice)?);
-            }
-            SwapEvent::DISCRIMINATOR => {
-                println!("{:#?}", decode_event::<SwapEvent>(&mut slice)?);
-            }
-            _ => {
-                println!("unknow event: {}", log_event);
-            }
+        match decode_cpswap_event(log)? {
+            Some(event) => println!("{:#?}", event),
+            None => println!("unknow event: {}", log_event),
         }
-        return Ok(());
-    } else {
-        return Ok(());
     }
+    Ok(())
 }
 
 fn decode_event<T: anchor_lang::Event + anchor_lang::AnchorDeserialize>(
@@ -289,3 +252,230 @@ fn decode_event<T: anchor_lang::Event + anchor_lang::AnchorDeserialize>(
         .map_err(|e| ClientError::LogParseError(e.to_string()))?;
     Ok(event)
 }
+
+/// The name of the event.
+pub fn event_type(event: &CpSwapEvent) -> &'static str {
+    match event {
+        CpSwapEvent::LpChange(_) => "LpChange",
+        CpSwapEvent::Swap(_) => "Swap",
+    }
+}
+
+pub fn event_pool_id(event: &CpSwapEvent) -> Option<Pubkey> {
+    match event {
+        CpSwapEvent::LpChange(event) => Some(event.pool_id),
+        CpSwapEvent::Swap(event) => Some(event.pool_id),
+    }
+}
+
+/// The swap and liquidity amounts of the event.
+pub fn receipt_event(event: &CpSwapEvent) -> Option<ReceiptEvent> {
+    match event {
+        CpSwapEvent::Swap(event) => Some(ReceiptEvent::Swap {
+            pool_id: Some(event.pool_id),
+            input_amount: event.input_amount,
+            output_amount: event.output_amount,
+        }),
+        // change_type: 0 is deposit, 1 is withdraw
+        CpSwapEvent::LpChange(event) if event.change_type == 0 => Some(ReceiptEvent::Deposit {
+            pool_id: Some(event.pool_id),
+            amount_0: event.token_0_amount,
+            amount_1: event.token_1_amount,
+        }),
+        CpSwapEvent::LpChange(event) => Some(ReceiptEvent::Withdraw {
+            pool_id: Some(event.pool_id),
+            amount_0: event.token_0_amount,
+            amount_1: event.token_1_amount,
+        }),
+    }
+}
+
+/// The swap amounts of the swap instruction.
+pub fn swap_quote(instruction: &CpSwapInstruction) -> Option<SwapQuote> {
+    match instruction {
+        CpSwapInstruction::SwapBaseInput {
+            amount_in,
+            minimum_amount_out,
+        } => Some(SwapQuote {
+            base_in: true,
+            amount_specified: *amount_in,
+            other_amount_threshold: *minimum_amount_out,
+        }),
+        CpSwapInstruction::SwapBaseOutput {
+            max_amount_in,
+            amount_out,
+        } => Some(SwapQuote {
+            base_in: false,
+            amount_specified: *amount_out,
+            other_amount_threshold: *max_amount_in,
+        }),
+        _ => None,
+    }
+}
+
+/// The swap of the event, the mints of the cp-swap pool are sorted, so the direction is
+/// derived from the input and output mint accounts of the swap instruction emitted it.
+pub fn swap_trade(event: &CpSwapEvent, accounts: &[Pubkey]) -> Option<SwapTrade> {
+    if let CpSwapEvent::Swap(event) = event {
+        let zero_for_one = accounts.get(10)? < accounts.get(11)?;
+        let (amount_0, amount_1) = if zero_for_one {
+            (event.input_amount, event.output_amount)
+        } else {
+            (event.output_amount, event.input_amount)
+        };
+        Some(SwapTrade {
+            pool_id: event.pool_id,
+            zero_for_one,
+            amount_0,
+            amount_1,
+        })
+    } else {
+        None
+    }
+}
+
+/// The name of the cp-swap `ErrorCode`, or of the anchor framework error below the
+/// `ERROR_CODE_OFFSET`, of the custom program error code. The variants are compared by the code
+/// anchor derives for each of them, so the order of the table doesn't matter, and a variant
+/// missing from it has no name.
+pub fn cpswap_error_name(code: u32) -> Option<String> {
+    if code < anchor_lang::error::ERROR_CODE_OFFSET {
+        return common::anchor_lang_error_name(code);
+    }
+    use raydium_cp_swap::error::ErrorCode::*;
+    [
+        NotApproved,
+        InvalidOwner,
+        EmptySupply,
+        InvalidInput,
+        IncorrectLpMint,
+        ExceededSlippage,
+        ZeroTradingTokens,
+        NotSupportMint,
+        InvalidVault,
+        InitLpAmountTooLess,
+        TransferFeeCalculateNotMatch,
+    ]
+    .into_iter()
+    .find(|error| u32::from(*error) == code)
+    .map(|error| error.name())
+}
+
+/// The account names of the instruction in the order of the account metas.
+pub fn instruction_account_names(instr_data: &[u8]) -> Vec<&'static str> {
+    if instr_data.len() < 8 {
+        return Vec::new();
+    }
+    let mut disc = [0; 8];
+    disc.copy_from_slice(&instr_data[..8]);
+    match disc {
+        instruction::CreateAmmConfig::DISCRIMINATOR => {
+            vec!["owner", "amm_config", "system_program"]
+        }
+        instruction::UpdateAmmConfig::DISCRIMINATOR => vec!["owner", "amm_config"],
+        instruction::UpdatePoolStatus::DISCRIMINATOR => vec!["authority", "pool_state"],
+        instruction::CollectProtocolFee::DISCRIMINATOR
+        | instruction::CollectFundFee::DISCRIMINATOR => vec![
+            "owner",
+            "authority",
+            "pool_state",
+            "amm_config",
+            "token_0_vault",
+            "token_1_vault",
+            "vault_0_mint",
+            "vault_1_mint",
+            "recipient_token_0_account",
+            "recipient_token_1_account",
+            "token_program",
+            "token_program_2022",
+        ],
+        instruction::Initialize::DISCRIMINATOR => vec![
+            "creator",
+            "amm_config",
+            "authority",
+            "pool_state",
+            "token_0_mint",
+            "token_1_mint",
+            "lp_mint",
+            "creator_token_0",
+            "creator_token_1",
+            "creator_lp_token",
+            "token_0_vault",
+            "token_1_vault",
+            "create_pool_fee",
+            "observation_state",
+            "token_program",
+            "token_0_program",
+            "token_1_program",
+            "associated_token_program",
+            "system_program",
+            "rent",
+        ],
+        instruction::Deposit::DISCRIMINATOR => vec![
+            "owner",
+            "authority",
+            "pool_state",
+            "owner_lp_token",
+            "token_0_account",
+            "token_1_account",
+            "token_0_vault",
+            "token_1_vault",
+            "token_program",
+            "token_program_2022",
+            "vault_0_mint",
+            "vault_1_mint",
+            "lp_mint",
+        ],
+        instruction::Withdraw::DISCRIMINATOR => vec![
+            "owner",
+            "authority",
+            "pool_state",
+            "owner_lp_token",
+            "token_0_account",
+            "token_1_account",
+            "token_0_vault",
+            "token_1_vault",
+            "token_program",
+            "token_program_2022",
+            "vault_0_mint",
+            "vault_1_mint",
+            "lp_mint",
+            "memo_program",
+        ],
+        instruction::SwapBaseInput::DISCRIMINATOR | instruction::SwapBaseOutput::DISCRIMINATOR => {
+            vec![
+                "payer",
+                "authority",
+                "amm_config",
+                "pool_state",
+                "input_token_account",
+                "output_token_account",
+                "input_vault",
+                "output_vault",
+                "input_token_program",
+                "output_token_program",
+                "input_token_mint",
+                "output_token_mint",
+                "observation_state",
+            ]
+        }
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpswap_error_name() {
+        assert_eq!(cpswap_error_name(6005).as_deref(), Some("ExceededSlippage"));
+        assert_eq!(cpswap_error_name(6000).as_deref(), Some("NotApproved"));
+        assert_eq!(
+            cpswap_error_name(6006).as_deref(),
+            Some("ZeroTradingTokens")
+        );
+        assert_eq!(cpswap_error_name(2006).as_deref(), Some("ConstraintSeeds"));
+        assert_eq!(cpswap_error_name(6999), None);
+    }
+}
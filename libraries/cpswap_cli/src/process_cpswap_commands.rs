@@ -1,20 +1,21 @@
 use crate::{cpswap_instructions, cpswap_utils, decode_cpswap_ix_event};
-use anyhow::Result;
+use anyhow::{format_err, Result};
 use clap::Parser;
-use common::{common_types, common_utils, rpc, token};
+use common::{
+    common_types, common_utils, rpc, token, AmountInput, MintInput, PoolOperation, PoolProgram,
+};
 use rand::rngs::OsRng;
 use solana_client::{
     rpc_client::RpcClient,
     rpc_filter::{Memcmp, RpcFilterType},
 };
 use solana_sdk::{
-    instruction::Instruction,
     pubkey::Pubkey,
     signer::{keypair::Keypair, Signer},
 };
 use std::sync::Arc;
 
-#[derive(Debug, Parser)]
+#[derive(Clone, Debug, Parser)]
 pub enum CpSwapCommands {
     CreatePool {
         /// User's token0.
@@ -29,13 +30,13 @@ pub enum CpSwapCommands {
         #[clap(short, long)]
         amm_config: Pubkey,
         /// The amount to init of toke0.
-        /// Amount can't be 0.
+        /// Amount can't be 0, accepts ui units like `1.5`, base units like `1500000raw`, a percentage like `50%` or `max`.
         #[clap(long)]
-        init_amount_0: u64,
+        init_amount_0: AmountInput,
         /// The amount to init of toke1.
-        /// Ammount can't be 0.
+        /// Ammount can't be 0, accepts ui units like `1.5`, base units like `1500000raw`, a percentage like `50%` or `max`.
         #[clap(long)]
-        init_amount_1: u64,
+        init_amount_1: AmountInput,
         /// The time of the pool is allowed to swap.
         /// If time is less than or equal to the on-chain timestamp, it will be set to the on-chain timestamp + 1.
         #[clap(short, long, default_value_t = 0)]
@@ -50,10 +51,12 @@ pub enum CpSwapCommands {
         pool_id: Pubkey,
         /// The specified token0 of the user deposit.
         /// If none is given, the account will be ATA account.
+        /// For the native mint, a temporary wsol account is used and closed after.
         #[clap(long)]
         deposit_token0: Option<Pubkey>,
         /// The specified token1 of the user deposit.
         /// If none is given, the account will be ATA account.
+        /// For the native mint, a temporary wsol account is used and closed after.
         #[clap(long)]
         deposit_token1: Option<Pubkey>,
         /// The specified lp token of the user will receive.
@@ -61,8 +64,9 @@ pub enum CpSwapCommands {
         #[clap(long)]
         recipient_token_lp: Option<Pubkey>,
         /// The amount of the specified token to deposit.
+        /// Accepts ui units like `1.5`, base units like `1500000raw`, a percentage of the balance like `50%` or `max`.
         #[clap(short, long)]
-        amount_specified: u64,
+        amount_specified: AmountInput,
         /// Indicates which token is specified of the `amount_specified`.
         #[clap(short, long, action)]
         base_token1: bool,
@@ -77,30 +81,38 @@ pub enum CpSwapCommands {
         withdraw_token_lp: Option<Pubkey>,
         /// The specified token0 of the user will receive.
         /// If none is given, the account will be ATA account.
+        /// For the native mint, a temporary wsol account is used and closed after.
         #[clap(long)]
         recipient_token0: Option<Pubkey>,
         /// The specified token1 of the user will receive.
         /// If none is given, the account will be ATA account.
+        /// For the native mint, a temporary wsol account is used and closed after.
         #[clap(long)]
         recipient_token1: Option<Pubkey>,
         /// The amount of liquidity to withdraw.
+        /// Accepts ui units like `1.5`, base units like `1500000raw`, a percentage of the lp balance like `50%` or `max`.
         #[clap(short, long)]
-        input_lp_amount: u64,
+        input_lp_amount: AmountInput,
     },
     Swap {
         /// The specified pool of trading.
         #[clap(short, long)]
         pool_id: Pubkey,
         /// The token of user want to swap from.
+        /// Pass the native mint to pay with sol, which is wrapped into a temporary wsol account.
+        /// A token symbol like `SOL` or `USDC` stands for the sol of the wallet or the ATA account.
         #[clap(long)]
-        user_input_token: Pubkey,
+        user_input_token: MintInput,
         /// The token of user want to swap to.
         /// If none is given, the account will be ATA account.
+        /// For the native mint, a temporary wsol account is used and closed after.
+        /// A token symbol like `USDC` is the same as none given.
         #[clap(long)]
-        user_output_token: Option<Pubkey>,
+        user_output_token: Option<MintInput>,
         /// The amount specified of user want to swap from or to token
+        /// Accepts ui units like `1.5`, base units like `1500000raw`, and for the input a percentage like `50%` or `max`.
         #[clap(short, long)]
-        amount_specified: u64,
+        amount_specified: AmountInput,
         /// The amount specified is output_token or not.
         #[clap(short, long, action)]
         base_out: bool,
@@ -110,12 +122,15 @@ pub enum CpSwapCommands {
         /// If the pool_id is specified, mint0 and mint1 will be ignored.
         #[clap(long)]
         pool_id: Option<Pubkey>,
-        /// Fetch pools by specified mint0.
+        /// Fetch pools by specified mint0, or its symbol.
         #[clap(long)]
-        mint0: Option<Pubkey>,
-        /// Fetch pools by specified mint1.
+        mint0: Option<MintInput>,
+        /// Fetch pools by specified mint1, or its symbol.
         #[clap(long)]
-        mint1: Option<Pubkey>,
+        mint1: Option<MintInput>,
+        /// Print the raw pool state instead of the summary of the specified pool.
+        #[clap(long, action)]
+        raw: bool,
     },
     FetchConfig {
         /// The specified amm config to fetch. If none is given, fetch all configs.
@@ -138,13 +153,15 @@ pub fn process_cpswap_commands(
     command: CpSwapCommands,
     config: &common_types::CommonConfig,
     signing_keypairs: &mut Vec<Arc<dyn Signer>>,
-) -> Result<Option<Vec<Instruction>>> {
+) -> Result<Option<common_types::CommandInstructions>> {
     let rpc_client = RpcClient::new(config.cluster().url());
-    let wallet_keypair = common_utils::read_keypair_file(&config.wallet())?;
-    let payer_pubkey = wallet_keypair.pubkey();
-    let payer: Arc<dyn Signer> = Arc::new(wallet_keypair);
-    if !signing_keypairs.contains(&payer) {
-        signing_keypairs.push(payer);
+    let payer_pubkey = config.wallet_pubkey()?;
+    // the wallet signs offline in the sign-only mode
+    if !config.sign_only() {
+        let payer: Arc<dyn Signer> = Arc::new(common_utils::read_keypair_file(&config.wallet())?);
+        if !signing_keypairs.contains(&payer) {
+            signing_keypairs.push(payer);
+        }
     }
 
     match command {
@@ -159,12 +176,30 @@ pub fn process_cpswap_commands(
         } => {
             let load_pubkeys = vec![user_token0, user_token1];
             let rsps = rpc_client.get_multiple_accounts(&load_pubkeys)?;
-            let token0_program = rsps[0].as_ref().unwrap().owner;
-            let token1_program = rsps[1].as_ref().unwrap().owner;
-            let user_token0_account =
-                common_utils::unpack_token(&rsps[0].as_ref().unwrap().data).unwrap();
-            let user_token1_account =
-                common_utils::unpack_token(&rsps[1].as_ref().unwrap().data).unwrap();
+            let user_token0_rsp = rsps[0]
+                .as_ref()
+                .ok_or_else(|| format_err!("token account {} not found", user_token0))?;
+            let user_token1_rsp = rsps[1]
+                .as_ref()
+                .ok_or_else(|| format_err!("token account {} not found", user_token1))?;
+            let token0_program = user_token0_rsp.owner;
+            let token1_program = user_token1_rsp.owner;
+            let user_token0_account = common_utils::unpack_token(&user_token0_rsp.data)?;
+            let user_token1_account = common_utils::unpack_token(&user_token1_rsp.data)?;
+            let init_amount_0 = common::resolve_token_amount(
+                &rpc_client,
+                &init_amount_0,
+                &payer_pubkey,
+                &user_token0_account.base.mint,
+                Some(&user_token0),
+            )?;
+            let init_amount_1 = common::resolve_token_amount(
+                &rpc_client,
+                &init_amount_1,
+                &payer_pubkey,
+                &user_token1_account.base.mint,
+                Some(&user_token1),
+            )?;
 
             let (
                 user_token0,
@@ -176,7 +211,15 @@ pub fn process_cpswap_commands(
                 init_amount_0,
                 init_amount_1,
             ) = if user_token0_account.base.mint > user_token1_account.base.mint {
-                println!("Flip user_token0, init_amount_0 and user_token1, init_amount_1 because mint0 be less than mint1");
+                println!(
+                    "the pool orders its mints, so mint_0 is {} with init_amount {} from {}, and mint_1 is {} with init_amount {} from {}",
+                    user_token1_account.base.mint,
+                    init_amount_1,
+                    user_token1,
+                    user_token0_account.base.mint,
+                    init_amount_0,
+                    user_token0
+                );
                 (
                     user_token1,
                     user_token0,
@@ -199,6 +242,21 @@ pub fn process_cpswap_commands(
                     init_amount_1,
                 )
             };
+            let amm_config_state = common_utils::deserialize_anchor_account::<
+                raydium_cp_swap::states::AmmConfig,
+            >(&rpc_client.get_account(&amm_config)?)?;
+            let mut funds = common::FundsCheck::default();
+            funds.debit(Some(user_token0), mint0, init_amount_0);
+            funds.debit(Some(user_token1), mint1, init_amount_1);
+            funds.spend("pool creation fee", amm_config_state.create_pool_fee);
+            let mint_accounts = rpc_client.get_multiple_accounts(&[mint0, mint1])?;
+            match (&mint_accounts[0], &mint_accounts[1]) {
+                (Some(mint0_account), Some(mint1_account)) => {
+                    cpswap_utils::create_pool_rent(&mut funds, mint0_account, mint1_account)?
+                }
+                _ => return Err(format_err!("mint {} or {} not found", mint0, mint1)),
+            }
+            common::check_user_funds(&rpc_client, &payer_pubkey, &funds)?;
 
             let random_pool_id = if random_pool {
                 let random_pool_keypair = Keypair::generate(&mut OsRng);
@@ -228,7 +286,7 @@ pub fn process_cpswap_commands(
                 init_amount_1,
                 open_time,
             )?;
-            return Ok(Some(initialize_pool_instr));
+            return Ok(Some(initialize_pool_instr.into()));
         }
         CpSwapCommands::Deposit {
             pool_id,
@@ -238,7 +296,28 @@ pub fn process_cpswap_commands(
             amount_specified,
             base_token1,
         } => {
+            cpswap_utils::check_pool_operation(&rpc_client, &pool_id, PoolOperation::Deposit)?;
             let base_token0 = !base_token1;
+            let pool_mints = common::get_indexed_pool_mints(config, &pool_id, || {
+                cpswap_utils::get_pool_mints(&rpc_client, &pool_id)
+            })?;
+            let amount_specified = if base_token0 {
+                common::resolve_token_amount(
+                    &rpc_client,
+                    &amount_specified,
+                    &payer_pubkey,
+                    &pool_mints.mint_0,
+                    deposit_token0.as_ref(),
+                )?
+            } else {
+                common::resolve_token_amount(
+                    &rpc_client,
+                    &amount_specified,
+                    &payer_pubkey,
+                    &pool_mints.mint_1,
+                    deposit_token1.as_ref(),
+                )?
+            };
             let result = cpswap_utils::add_liquidity_calculate(
                 &rpc_client,
                 pool_id,
@@ -246,8 +325,28 @@ pub fn process_cpswap_commands(
                 config.slippage(),
                 base_token0,
             )?;
+            println!(
+                "ui amount_0:{}, ui amount_1:{}",
+                pool_mints.ui_amount(&result.mint0, result.amount_0),
+                pool_mints.ui_amount(&result.mint1, result.amount_1)
+            );
+            let mut funds = common::FundsCheck::default();
+            funds.debit(deposit_token0, result.mint0, result.amount_0);
+            funds.debit(deposit_token1, result.mint1, result.amount_1);
+            funds.credit(recipient_token_lp, result.mintlp);
+            common::check_user_funds(&rpc_client, &payer_pubkey, &funds)?;
+            let mut instructions = Vec::new();
+            // the sol is wrapped into a temporary wsol account, which is closed at the end
+            let mut close_wsol_instructions = Vec::new();
             let deposit_token0 = if let Some(deposit_token0) = deposit_token0 {
                 deposit_token0
+            } else if result.mint0 == spl_token::native_mint::id() {
+                token::wrap_sol_temp_account(
+                    &payer_pubkey,
+                    result.amount_0,
+                    &mut instructions,
+                    &mut close_wsol_instructions,
+                )
             } else {
                 spl_associated_token_account::get_associated_token_address_with_program_id(
                     &payer_pubkey,
@@ -257,6 +356,13 @@ pub fn process_cpswap_commands(
             };
             let deposit_token1 = if let Some(deposit_token1) = deposit_token1 {
                 deposit_token1
+            } else if result.mint1 == spl_token::native_mint::id() {
+                token::wrap_sol_temp_account(
+                    &payer_pubkey,
+                    result.amount_1,
+                    &mut instructions,
+                    &mut close_wsol_instructions,
+                )
             } else {
                 spl_associated_token_account::get_associated_token_address_with_program_id(
                     &payer_pubkey,
@@ -265,7 +371,6 @@ pub fn process_cpswap_commands(
                 )
             };
 
-            let mut instructions = Vec::new();
             let recipient_token_lp = if let Some(recipient_token_lp) = recipient_token_lp {
                 recipient_token_lp
             } else {
@@ -299,7 +404,8 @@ pub fn process_cpswap_commands(
                 result.amount_1,
             )?;
             instructions.extend(deposit_instr);
-            return Ok(Some(instructions));
+            instructions.extend(close_wsol_instructions);
+            return Ok(Some(instructions.into()));
         }
         CpSwapCommands::Withdraw {
             pool_id,
@@ -308,6 +414,25 @@ pub fn process_cpswap_commands(
             recipient_token1,
             input_lp_amount,
         } => {
+            cpswap_utils::check_pool_operation(&rpc_client, &pool_id, PoolOperation::Withdraw)?;
+            let lp_mint = match common::get_indexed_pool(config, &pool_id)? {
+                Some(pool) => pool.lp_mint,
+                None => {
+                    rpc::get_anchor_account::<raydium_cp_swap::states::PoolState>(
+                        &rpc_client,
+                        &pool_id,
+                    )?
+                    .unwrap()
+                    .lp_mint
+                }
+            };
+            let input_lp_amount = common::resolve_token_amount(
+                &rpc_client,
+                &input_lp_amount,
+                &payer_pubkey,
+                &lp_mint,
+                withdraw_token_lp.as_ref(),
+            )?;
             let result = cpswap_utils::remove_liquidity_calculate(
                 &rpc_client,
                 pool_id,
@@ -324,8 +449,17 @@ pub fn process_cpswap_commands(
             };
 
             let mut instructions = Vec::new();
+            // the sol is received by a temporary wsol account, which is closed at the end
+            let mut close_wsol_instructions = Vec::new();
             let recipient_token0 = if let Some(recipient_token0) = recipient_token0 {
                 recipient_token0
+            } else if result.mint0 == spl_token::native_mint::id() {
+                token::wrap_sol_temp_account(
+                    &payer_pubkey,
+                    0,
+                    &mut instructions,
+                    &mut close_wsol_instructions,
+                )
             } else {
                 // mint0 maybe token22
                 let create_user_token0_instr = token::create_ata_token_or_not(
@@ -344,6 +478,13 @@ pub fn process_cpswap_commands(
             };
             let recipient_token1 = if let Some(recipient_token1) = recipient_token1 {
                 recipient_token1
+            } else if result.mint1 == spl_token::native_mint::id() {
+                token::wrap_sol_temp_account(
+                    &payer_pubkey,
+                    0,
+                    &mut instructions,
+                    &mut close_wsol_instructions,
+                )
             } else {
                 // mint1 maybe token22
                 let create_user_token1_instr = token::create_ata_token_or_not(
@@ -377,7 +518,8 @@ pub fn process_cpswap_commands(
                 result.amount_1,
             )?;
             instructions.extend(withdraw_instr);
-            return Ok(Some(instructions));
+            instructions.extend(close_wsol_instructions);
+            return Ok(Some(instructions.into()));
         }
         CpSwapCommands::Swap {
             pool_id,
@@ -386,19 +528,127 @@ pub fn process_cpswap_commands(
             amount_specified,
             base_out,
         } => {
+            cpswap_utils::check_pool_operation(&rpc_client, &pool_id, PoolOperation::Swap)?;
             let base_in = !base_out;
+            if let Some(max_deviation_bps) = config.oracle_max_deviation_bps() {
+                let (price, reference_price) = match config.oracle_source() {
+                    common_types::OracleSource::Twap => {
+                        let pool_twap = cpswap_utils::get_pool_twap(
+                            &rpc_client,
+                            &pool_id,
+                            config.oracle_twap_window(),
+                        )?;
+                        (pool_twap.spot_price, pool_twap.twap_price)
+                    }
+                    source => (
+                        cpswap_utils::get_pool_price(&rpc_client, &pool_id)?,
+                        common::get_reference_price(&rpc_client, &source)?,
+                    ),
+                };
+                common::check_price_deviation(&pool_id, price, reference_price, max_deviation_bps)?;
+            }
+            let slippage = config.swap_slippage(|| {
+                cpswap_utils::get_price_movement_bps(
+                    &rpc_client,
+                    &pool_id,
+                    common_types::AUTO_SLIPPAGE_WINDOW,
+                )
+            });
+            let user_input_token = common::resolve_user_input_token(
+                &rpc_client,
+                config,
+                &user_input_token,
+                &payer_pubkey,
+            )?;
+            let pool_mints = common::get_indexed_pool_mints(config, &pool_id, || {
+                cpswap_utils::get_pool_mints(&rpc_client, &pool_id)
+            })?;
+            let user_output_token =
+                common::resolve_user_output_token(config, user_output_token.as_ref(), &pool_mints)?;
+            let amount_specified = common::resolve_swap_amount(
+                &rpc_client,
+                &amount_specified,
+                &payer_pubkey,
+                &user_input_token,
+                &pool_mints,
+                base_out,
+            )?;
             let result = cpswap_utils::swap_calculate(
                 &rpc_client,
                 pool_id,
                 user_input_token,
                 amount_specified,
-                config.slippage(),
+                slippage,
                 base_in,
             )?;
+            let symbols = common::get_token_symbols(
+                &rpc_client,
+                config,
+                &[result.input_mint, result.output_mint],
+            )?;
+            println!(
+                "swap {} to {}",
+                common::mint_label(&symbols, &result.input_mint),
+                common::mint_label(&symbols, &result.output_mint)
+            );
+            println!(
+                "amount_specified:{}, other_amount_threshold:{}, price_impact_bps:{}, slippage_bps:{}, auto_slippage:{}",
+                result.amount_specified,
+                result.other_amount_threshold,
+                result.price_impact_bps,
+                result.slippage_bps,
+                config.auto_slippage()
+            );
+            let (specified_mint, other_mint) = if base_in {
+                (result.input_mint, result.output_mint)
+            } else {
+                (result.output_mint, result.input_mint)
+            };
+            println!(
+                "ui amount_specified:{}, ui other_amount_threshold:{}",
+                pool_mints.ui_amount(&specified_mint, result.amount_specified),
+                pool_mints.ui_amount(&other_mint, result.other_amount_threshold)
+            );
+            let mut funds = common::FundsCheck::default();
+            funds.debit(
+                Some(result.user_input_token),
+                result.input_mint,
+                if base_in {
+                    result.amount_specified
+                } else {
+                    result.other_amount_threshold
+                },
+            );
+            funds.credit(user_output_token, result.output_mint);
+            common::check_user_funds(&rpc_client, &payer_pubkey, &funds)?;
 
             let mut instructions = Vec::new();
+            // the sol is wrapped into a temporary wsol account, which is closed at the end
+            let mut close_wsol_instructions = Vec::new();
+            let user_input_token = if result.user_input_token == spl_token::native_mint::id() {
+                let amount = if base_in {
+                    result.amount_specified
+                } else {
+                    result.other_amount_threshold
+                };
+                token::wrap_sol_temp_account(
+                    &payer_pubkey,
+                    amount,
+                    &mut instructions,
+                    &mut close_wsol_instructions,
+                )
+            } else {
+                result.user_input_token
+            };
             let user_output_token = if let Some(user_output_token) = user_output_token {
                 user_output_token
+            } else if result.output_mint == spl_token::native_mint::id() {
+                token::wrap_sol_temp_account(
+                    &payer_pubkey,
+                    0,
+                    &mut instructions,
+                    &mut close_wsol_instructions,
+                )
             } else {
                 let create_user_output_token_instr = token::create_ata_token_or_not(
                     &payer_pubkey,
@@ -421,7 +671,7 @@ pub fn process_cpswap_commands(
                     pool_id,
                     result.pool_config,
                     result.pool_observation,
-                    result.user_input_token,
+                    user_input_token,
                     user_output_token,
                     result.input_vault,
                     result.output_vault,
@@ -438,7 +688,7 @@ pub fn process_cpswap_commands(
                     pool_id,
                     result.pool_config,
                     result.pool_observation,
-                    result.user_input_token,
+                    user_input_token,
                     user_output_token,
                     result.input_vault,
                     result.output_vault,
@@ -451,14 +701,34 @@ pub fn process_cpswap_commands(
                 )?
             };
             instructions.extend(swap_instruction);
-            return Ok(Some(instructions));
+            instructions.extend(close_wsol_instructions);
+            return Ok(Some(common_types::CommandInstructions {
+                instructions,
+                // the receipt recovers the quote with the applied slippage
+                slippage_bps: Some(result.slippage_bps),
+            }));
         }
         CpSwapCommands::FetchPool {
             pool_id,
             mint0,
             mint1,
+            raw,
         } => {
+            let mint0 = mint0.map(|mint| mint.resolve(config)).transpose()?;
+            let mint1 = mint1.map(|mint| mint.resolve(config)).transpose()?;
             if let Some(pool_id) = pool_id {
+                if !raw {
+                    // summarize specified pool
+                    let summary = cpswap_utils::get_pool_summary(&rpc_client, &pool_id)?;
+                    let mints = summary.snapshot.mints;
+                    let symbols = common::get_token_symbols(
+                        &rpc_client,
+                        config,
+                        &[mints.mint_0, mints.mint_1],
+                    )?;
+                    common::print_pool_summary(&summary, &symbols);
+                    return Ok(None);
+                }
                 // fetch specified pool
                 let pool_state = rpc::get_anchor_account::<raydium_cp_swap::states::PoolState>(
                     &rpc_client,
@@ -467,9 +737,22 @@ pub fn process_cpswap_commands(
                 .unwrap()
                 .unwrap();
                 println!("{:#?}", pool_state);
+                let symbols = common::get_token_symbols(
+                    &rpc_client,
+                    config,
+                    &[pool_state.token_0_mint, pool_state.token_1_mint],
+                )?;
+                println!(
+                    "mint0:{}, mint1:{}",
+                    common::mint_label(&symbols, &pool_state.token_0_mint),
+                    common::mint_label(&symbols, &pool_state.token_1_mint)
+                );
             } else {
-                // fetch pool by filters
+                // fetch pool by filters, or from the pool index in either order of the mints
                 let pool_len = raydium_cp_swap::states::PoolState::LEN as u64;
+                let pool_index = config
+                    .pool_index()
+                    .filter(|_| mint0.is_some() || mint1.is_some());
                 let filters = match (mint0, mint1) {
                     (None, None) => Some(vec![RpcFilterType::DataSize(pool_len)]),
                     (Some(mint0), None) => Some(vec![
@@ -498,20 +781,50 @@ pub fn process_cpswap_commands(
                         RpcFilterType::DataSize(pool_len),
                     ]),
                 };
-                let pools = rpc::get_program_accounts_with_filters(
-                    &rpc_client,
-                    config.cp_program(),
-                    filters,
-                )
-                .unwrap();
-                for pool in pools {
+                let pools = if let Some(path) = pool_index {
+                    common::fetch_indexed_pools(
+                        &rpc_client,
+                        config,
+                        &path,
+                        PoolProgram::CpSwap,
+                        mint0.as_ref(),
+                        mint1.as_ref(),
+                        |index| cpswap_utils::refresh_pool_index(&rpc_client, config, &path, index),
+                    )?
+                } else {
+                    rpc::get_program_accounts_with_filters(
+                        &rpc_client,
+                        config.cp_program(),
+                        filters,
+                    )
+                    .unwrap()
+                };
+                let pool_states: Vec<_> =
+                    pools
+                        .iter()
+                        .map(|pool| {
+                            common_utils::deserialize_anchor_account::<
+                                raydium_cp_swap::states::PoolState,
+                            >(&pool.1)
+                        })
+                        .collect();
+                // the symbols of all the pools are fetched in batches
+                let mut mints = Vec::new();
+                for pool_state in pool_states.iter().flatten() {
+                    mints.push(pool_state.token_0_mint);
+                    mints.push(pool_state.token_1_mint);
+                }
+                let symbols = common::get_token_symbols(&rpc_client, config, &mints)?;
+                for (pool, pool_state) in pools.iter().zip(pool_states) {
                     println!("pool_id:{}", pool.0);
-                    println!(
-                        "{:#?}",
-                        common_utils::deserialize_anchor_account::<
-                            raydium_cp_swap::states::PoolState,
-                        >(&pool.1)
-                    );
+                    println!("{:#?}", pool_state);
+                    if let Some(pool_state) = pool_state.ok() {
+                        println!(
+                            "mint0:{}, mint1:{}",
+                            common::mint_label(&symbols, &pool_state.token_0_mint),
+                            common::mint_label(&symbols, &pool_state.token_1_mint)
+                        );
+                    }
                 }
             }
             return Ok(None);
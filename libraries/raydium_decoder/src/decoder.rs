@@ -0,0 +1,181 @@
+use amm_cli::{AmmEvent, AmmInstruction};
+use anyhow::Result;
+use clmm_cli::{ClmmEvent, ClmmInstruction};
+use common::{common_types, ReceiptEvent, SwapQuote, SwapTrade};
+use cpswap_cli::{CpSwapEvent, CpSwapInstruction};
+use solana_sdk::pubkey::Pubkey;
+
+/// An instruction of the raydium programs.
+#[derive(Debug)]
+pub enum RaydiumInstruction {
+    Amm(AmmInstruction),
+    CpSwap(CpSwapInstruction),
+    Clmm(ClmmInstruction),
+}
+
+/// An event emitted by the raydium programs.
+#[derive(Debug)]
+pub enum RaydiumEvent {
+    Amm(AmmEvent),
+    CpSwap(CpSwapEvent),
+    Clmm(ClmmEvent),
+}
+
+/// Route the instruction data to the decoder of the program,
+/// returns `None` if the program is not a raydium program or the instruction is unknown.
+pub fn decode_instruction(
+    config: &common_types::CommonConfig,
+    program_id: &Pubkey,
+    data: &[u8],
+) -> Result<Option<RaydiumInstruction>> {
+    let instruction = if *program_id == config.amm_program() {
+        // the unknown instructions fail to unpack
+        amm_cli::decode_amm_instruction(data)
+            .ok()
+            .map(RaydiumInstruction::Amm)
+    } else if *program_id == config.cp_program() {
+        cpswap_cli::decode_cpswap_instruction(data)?.map(RaydiumInstruction::CpSwap)
+    } else if *program_id == config.clmm_program() {
+        clmm_cli::decode_clmm_instruction(data)?.map(RaydiumInstruction::Clmm)
+    } else {
+        None
+    };
+    Ok(instruction)
+}
+
+/// Route the log line emitted by the program to the decoder of the program.
+/// The amm emits `Program log: ray_log: <base64>`, the anchor programs emit
+/// `Program data: <base64>`, other lines return `None`.
+pub fn decode_event(
+    config: &common_types::CommonConfig,
+    program_id: &Pubkey,
+    log: &str,
+) -> Result<Option<RaydiumEvent>> {
+    let event = if *program_id == config.amm_program() {
+        match log
+            .strip_prefix(common_types::PROGRAM_LOG)
+            .and_then(|log| log.strip_prefix(common_types::RAY_LOG))
+        {
+            Some(data) => amm_cli::decode_amm_event(data)?.map(RaydiumEvent::Amm),
+            None => None,
+        }
+    } else if *program_id == config.cp_program() {
+        match log.strip_prefix(common_types::PROGRAM_DATA) {
+            Some(data) => cpswap_cli::decode_cpswap_event(data)?.map(RaydiumEvent::CpSwap),
+            None => None,
+        }
+    } else if *program_id == config.clmm_program() {
+        match log.strip_prefix(common_types::PROGRAM_DATA) {
+            Some(data) => clmm_cli::decode_clmm_event(data)?.map(RaydiumEvent::Clmm),
+            None => None,
+        }
+    } else {
+        None
+    };
+    Ok(event)
+}
+
+/// The name of the custom error code the raydium program failed with, decoded from the code.
+/// The name logged by the anchor programs is the fallback of the codes the tables miss.
+pub fn error_name(
+    config: &common_types::CommonConfig,
+    program_id: &Pubkey,
+    code: u32,
+    logs: &[String],
+) -> Option<String> {
+    if *program_id == config.amm_program() {
+        amm_cli::amm_error_name(code)
+    } else if *program_id == config.cp_program() {
+        cpswap_cli::cpswap_error_name(code).or_else(|| common::anchor_error_name(logs, code))
+    } else if *program_id == config.clmm_program() {
+        clmm_cli::clmm_error_name(code).or_else(|| common::anchor_error_name(logs, code))
+    } else {
+        None
+    }
+}
+
+/// The pool account of the instruction, the `amm` account of the amm instructions or
+/// the `pool_state` account of the anchor programs.
+pub fn instruction_pool_id(
+    config: &common_types::CommonConfig,
+    program_id: &Pubkey,
+    data: &[u8],
+    accounts: &[Pubkey],
+) -> Option<Pubkey> {
+    instruction_account_names(config, program_id, data, accounts.len())
+        .iter()
+        .position(|name| *name == "amm" || *name == "pool_state")
+        .and_then(|index| accounts.get(index).copied())
+}
+
+/// The account names of the instruction in the order of the account metas.
+pub fn instruction_account_names(
+    config: &common_types::CommonConfig,
+    program_id: &Pubkey,
+    data: &[u8],
+    accounts_len: usize,
+) -> Vec<&'static str> {
+    if *program_id == config.amm_program() {
+        amm_cli::instruction_account_names(data, accounts_len)
+    } else if *program_id == config.cp_program() {
+        cpswap_cli::instruction_account_names(data)
+    } else if *program_id == config.clmm_program() {
+        clmm_cli::instruction_account_names(data)
+    } else {
+        Vec::new()
+    }
+}
+
+impl RaydiumInstruction {
+    pub fn swap_quote(&self) -> Option<SwapQuote> {
+        match self {
+            RaydiumInstruction::Amm(instruction) => amm_cli::swap_quote(instruction),
+            RaydiumInstruction::CpSwap(instruction) => cpswap_cli::swap_quote(instruction),
+            RaydiumInstruction::Clmm(instruction) => clmm_cli::swap_quote(instruction),
+        }
+    }
+}
+
+impl RaydiumEvent {
+    pub fn receipt_event(&self) -> Option<ReceiptEvent> {
+        match self {
+            RaydiumEvent::Amm(event) => amm_cli::receipt_event(event),
+            RaydiumEvent::CpSwap(event) => cpswap_cli::receipt_event(event),
+            RaydiumEvent::Clmm(event) => clmm_cli::receipt_event(event),
+        }
+    }
+
+    pub fn program(&self) -> &'static str {
+        match self {
+            RaydiumEvent::Amm(_) => "amm",
+            RaydiumEvent::CpSwap(_) => "cpswap",
+            RaydiumEvent::Clmm(_) => "clmm",
+        }
+    }
+
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            RaydiumEvent::Amm(event) => amm_cli::event_type(event),
+            RaydiumEvent::CpSwap(event) => cpswap_cli::event_type(event),
+            RaydiumEvent::Clmm(event) => clmm_cli::event_type(event),
+        }
+    }
+
+    /// The pool of the event if the event contains it.
+    pub fn pool_id(&self) -> Option<Pubkey> {
+        match self {
+            RaydiumEvent::Amm(event) => amm_cli::event_pool_id(event),
+            RaydiumEvent::CpSwap(event) => cpswap_cli::event_pool_id(event),
+            RaydiumEvent::Clmm(event) => clmm_cli::event_pool_id(event),
+        }
+    }
+
+    /// The swap of the event, `accounts` are the accounts of the instruction emitted it.
+    pub fn swap_trade(&self, accounts: &[Pubkey]) -> Option<SwapTrade> {
+        match self {
+            RaydiumEvent::Amm(event) => amm_cli::swap_trade(event, accounts),
+            RaydiumEvent::CpSwap(event) => cpswap_cli::swap_trade(event, accounts),
+            RaydiumEvent::Clmm(event) => clmm_cli::swap_trade(event, accounts),
+        }
+    }
+}